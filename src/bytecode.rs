@@ -0,0 +1,288 @@
+use crate::function::Signature;
+use crate::lexer::boolean::ComparisonOpType;
+use crate::lexer::numeric::NumericOpType;
+use crate::span::Span;
+use crate::variable::VariableType;
+use std::collections::HashMap;
+
+pub mod compiler;
+pub mod vm;
+
+/// Defines a single instruction for the stack-based virtual machine. Jump targets are given as
+/// absolute instruction indices within the chunk
+#[derive(Debug, PartialEq, Clone)]
+pub enum Instruction
+{
+    /// Pushes the constant at the index within the constant table
+    LoadConst(usize),
+
+    /// Pushes the value of the variable in the slot
+    LoadVar(usize),
+
+    /// Pops a value and stores it in the variable slot
+    StoreVar(usize),
+
+    /// Pops and discards a value
+    Pop,
+
+    /// Pushes a copy of the top value
+    Dup,
+
+    /// Pops two numeric values and pushes the result of the operation
+    Numeric(NumericOpType),
+
+    /// Pops two numeric values, or two string values for an equality comparison, and pushes the
+    /// boolean result of the comparison
+    Compare(ComparisonOpType),
+
+    /// Pops two string values and pushes the first joined with the second
+    Concat,
+
+    /// Negates the top numeric value
+    Negate,
+
+    /// Inverts the top boolean value
+    Not,
+
+    /// Converts the top numeric value to a float
+    ToFloat,
+
+    /// Continues execution at the target instruction
+    Jump(usize),
+
+    /// Pops a boolean value, continuing execution at the target instruction if it is false
+    JumpIfFalse(usize),
+
+    /// Pops a boolean value, continuing execution at the target instruction if it is true
+    JumpIfTrue(usize),
+
+    /// Pops the number of arguments for the function at the index within the function table, in
+    /// order, and pushes the result of calling it
+    Call(usize, usize),
+
+    /// Pops the number of values and writes them on a single line, in the order they were pushed
+    Print(usize),
+
+    /// Reads a value of the type from the input, storing it in the variable slot
+    Input(usize, VariableType)
+}
+
+impl Instruction
+{
+    /// Returns the mnemonic of the instruction, as used by the disassembler
+    pub fn mnemonic(&self) -> &'static str
+    {
+        return match self
+        {
+            Instruction::LoadConst(_) => "load_const",
+            Instruction::LoadVar(_) => "load_var",
+            Instruction::StoreVar(_) => "store_var",
+            Instruction::Pop => "pop",
+            Instruction::Dup => "dup",
+            Instruction::Numeric(NumericOpType::Add) => "add",
+            Instruction::Numeric(NumericOpType::Subtract) => "sub",
+            Instruction::Numeric(NumericOpType::Multiply) => "mul",
+            Instruction::Numeric(NumericOpType::Divide) => "div",
+            Instruction::Compare(ComparisonOpType::Equal) => "cmp_eq",
+            Instruction::Compare(ComparisonOpType::NotEqual) => "cmp_ne",
+            Instruction::Compare(ComparisonOpType::Less) => "cmp_lt",
+            Instruction::Compare(ComparisonOpType::Greater) => "cmp_gt",
+            Instruction::Compare(ComparisonOpType::LessEqual) => "cmp_le",
+            Instruction::Compare(ComparisonOpType::GreaterEqual) => "cmp_ge",
+            Instruction::Concat => "concat",
+            Instruction::Negate => "neg",
+            Instruction::Not => "not",
+            Instruction::ToFloat => "to_float",
+            Instruction::Jump(_) => "jump",
+            Instruction::JumpIfFalse(_) => "jump_if_false",
+            Instruction::JumpIfTrue(_) => "jump_if_true",
+            Instruction::Call(_, _) => "call",
+            Instruction::Print(_) => "print",
+            Instruction::Input(_, _) => "input"
+        };
+    }
+}
+
+/// Defines a compiled program, being the instructions along with the constants and variable
+/// slots they refer to
+#[derive(Debug, PartialEq, Default)]
+pub struct Chunk
+{
+    /// Defines the instructions to execute
+    pub code: Vec<Instruction>,
+
+    /// Defines the source location of each instruction, used to report runtime errors
+    pub spans: Vec<Span>,
+
+    /// Defines the constant values loaded by the instructions
+    pub constants: Vec<VariableType>,
+
+    /// Defines the variable name associated with each variable slot
+    pub slots: Vec<String>,
+
+    /// Defines the name and signature of each function called by the instructions
+    pub functions: Vec<(String, Signature)>,
+
+    /// Defines the index of each value within the constant table
+    constant_indices: HashMap<ConstantKey, usize>
+}
+
+/// Defines the key of a value within the constant table, with floats compared by their bit
+/// patterns so that distinct values such as 0.0 and -0.0 are kept apart
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum ConstantKey
+{
+    Boolean(bool),
+    Integer(i32),
+    Float(u32),
+    String(String)
+}
+
+impl From<&VariableType> for ConstantKey
+{
+    /// Creates the key of the constant value
+    fn from(value: &VariableType) -> ConstantKey
+    {
+        return match value
+        {
+            VariableType::Boolean(v) => ConstantKey::Boolean(*v),
+            VariableType::Integer(v) => ConstantKey::Integer(*v),
+            VariableType::Float(v) => ConstantKey::Float(v.to_bits()),
+            VariableType::String(v) => ConstantKey::String(v.clone())
+        };
+    }
+}
+
+impl Chunk
+{
+    /// Returns a new chunk with no instructions
+    pub fn new() -> Chunk
+    {
+        return Chunk::default();
+    }
+
+    /// Adds an instruction to the chunk, returning its index
+    pub fn push(&mut self, instruction: Instruction, span: Span) -> usize
+    {
+        self.code.push(instruction);
+        self.spans.push(span);
+        return self.code.len() - 1;
+    }
+
+    /// Returns the index of the constant within the constant table, adding it if not present
+    pub fn constant(&mut self, value: VariableType) -> usize
+    {
+        let constants = &mut self.constants;
+
+        return *self.constant_indices.entry(ConstantKey::from(&value)).or_insert_with(||
+        {
+            constants.push(value);
+            constants.len() - 1
+        });
+    }
+
+    /// Returns the index of the function within the function table, adding it if not present
+    pub fn function(&mut self, name: &str, signature: &Signature) -> usize
+    {
+        return match self.functions.iter().position(|(n, s)| n == name && s == signature)
+        {
+            Some(i) => i,
+            None =>
+            {
+                self.functions.push((name.to_string(), signature.clone()));
+                self.functions.len() - 1
+            }
+        };
+    }
+
+    /// Provides a human-readable listing of the instructions, with each operand annotated with
+    /// the constant value, variable name or function it refers to
+    pub fn disassemble(&self) -> String
+    {
+        let mut output = String::new();
+
+        for (i, (instruction, span)) in self.code.iter().zip(self.spans.iter()).enumerate()
+        {
+            let (operand, comment) = match instruction
+            {
+                Instruction::LoadConst(c) => (c.to_string(), self.constants[*c].to_string()),
+                Instruction::LoadVar(s) | Instruction::StoreVar(s) => (s.to_string(), self.slots[*s].clone()),
+                Instruction::Input(s, t) => (s.to_string(), format!("{0:} ({1:})", self.slots[*s], t.type_name())),
+                Instruction::Print(n) => (n.to_string(), String::new()),
+                Instruction::Call(f, n) => (format!("{0:} {1:}", f, n), format!("{0:}{1:}", self.functions[*f].0, self.functions[*f].1)),
+                Instruction::Jump(t) | Instruction::JumpIfFalse(t) | Instruction::JumpIfTrue(t) => (format!("{0:04}", t), String::new()),
+                _ => (String::new(), String::new())
+            };
+
+            let line = format!("{0:04} {1:>7}  {2:<14} {3:<5}", i, span.to_string(), instruction.mnemonic(), operand);
+
+            if comment.is_empty()
+            {
+                output.push_str(line.trim_end());
+            }
+            else
+            {
+                output.push_str(&format!("{0:} ; {1:}", line, comment));
+            }
+
+            output.push('\n');
+        }
+
+        return output;
+    }
+}
+
+/// Defines tests of the bytecode chunk
+#[cfg(test)]
+mod tests
+{
+    use super::{compiler, Chunk};
+    use crate::checker;
+    use crate::lexer::program::Program;
+    use crate::token::Token;
+    use crate::variable::VariableType;
+
+    /// Tests that equal constants share a single entry in the constant table, with floats only
+    /// shared when their bit patterns match
+    #[test]
+    fn constants()
+    {
+        let mut chunk = Chunk::new();
+
+        assert_eq!(chunk.constant(VariableType::Integer(1)), 0);
+        assert_eq!(chunk.constant(VariableType::Float(1.0)), 1);
+        assert_eq!(chunk.constant(VariableType::Integer(1)), 0);
+        assert_eq!(chunk.constant(VariableType::Float(-0.0)), 2);
+        assert_eq!(chunk.constant(VariableType::Float(0.0)), 3);
+        assert_eq!(chunk.constant(VariableType::Float(-0.0)), 2);
+        assert_eq!(chunk.constant(VariableType::String(String::from("a"))), 4);
+        assert_eq!(chunk.constant(VariableType::String(String::from("a"))), 4);
+        assert_eq!(chunk.constants.len(), 5);
+    }
+
+    /// Tests that the disassembler lists each instruction with its location and operands
+    #[test]
+    fn disassemble()
+    {
+        let mut program = Program::parse(&mut Token::tokenize("a = 1;\nwhile (a < 10 && true) {\n  a *= 2;\n}").unwrap()).unwrap();
+        checker::check(&mut program).unwrap();
+
+        assert_eq!(
+            compiler::compile(&program).disassemble(),
+            "0000     1:5  load_const     0     ; 1\n\
+             0001     1:1  store_var      0     ; a\n\
+             0002     2:8  load_var       0     ; a\n\
+             0003    2:12  load_const     1     ; 10\n\
+             0004     2:8  cmp_lt\n\
+             0005     2:8  dup\n\
+             0006     2:8  jump_if_false  0009\n\
+             0007     2:8  pop\n\
+             0008    2:18  load_const     2     ; true\n\
+             0009     2:1  jump_if_false  0015\n\
+             0010     3:3  load_var       0     ; a\n\
+             0011     3:8  load_const     3     ; 2\n\
+             0012     3:3  mul\n\
+             0013     3:3  store_var      0     ; a\n\
+             0014     2:1  jump           0002\n");
+    }
+}
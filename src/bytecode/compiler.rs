@@ -0,0 +1,414 @@
+use crate::lexer::boolean::{BoolExpression, BoolExpressionType, BooleanOpType};
+use crate::lexer::call::CallExpression;
+use crate::lexer::expression::Expression;
+use crate::lexer::numeric::{NumericExpression, NumericExpressionDataType, NumericExpressionType};
+use crate::lexer::program::Program;
+use crate::lexer::string::{StringExpression, StringExpressionType};
+use crate::lexer::statement::{Statement, StatementType};
+use crate::span::Span;
+use crate::variable::VariableType;
+use std::collections::HashMap;
+
+use super::{Chunk, Instruction};
+
+/// Compiles the program into a bytecode chunk. The program should be type checked first, such
+/// that the data type of each numeric expression matches the values it provides. Chunks compiled
+/// from programs that were not type checked report RuntimeError::TypeMismatch when a value of
+/// the wrong kind is reached
+pub fn compile(program: &Program) -> Chunk
+{
+    let mut compiler = Compiler
+    {
+        chunk: Chunk::new(),
+        slots: HashMap::new()
+    };
+
+    compiler.compile_statement(&program.main);
+    return compiler.chunk;
+}
+
+/// Defines the state of the compiler while walking a program
+struct Compiler
+{
+    /// Defines the chunk being written
+    chunk: Chunk,
+
+    /// Defines the variable slot assigned to each variable name
+    slots: HashMap<String, usize>
+}
+
+impl Compiler
+{
+    /// Returns the slot of the variable with the provided name, assigning a new slot if needed
+    fn slot(&mut self, name: &str) -> usize
+    {
+        if let Some(s) = self.slots.get(name)
+        {
+            return *s;
+        }
+
+        self.chunk.slots.push(name.to_string());
+        let slot = self.chunk.slots.len() - 1;
+        self.slots.insert(name.to_string(), slot);
+        return slot;
+    }
+
+    /// Returns the index of the next instruction to be written
+    fn here(&self) -> usize
+    {
+        return self.chunk.code.len();
+    }
+
+    /// Sets the target of the jump instruction at the index to the next instruction to be written
+    fn patch(&mut self, index: usize)
+    {
+        let target = self.here();
+
+        self.chunk.code[index] = match &self.chunk.code[index]
+        {
+            Instruction::Jump(_) => Instruction::Jump(target),
+            Instruction::JumpIfFalse(_) => Instruction::JumpIfFalse(target),
+            Instruction::JumpIfTrue(_) => Instruction::JumpIfTrue(target),
+            other => panic!("cannot patch non-jump instruction {0:?}", other)
+        };
+    }
+
+    /// Compiles the statement and each statement linked after it
+    fn compile_statement(&mut self, statement: &Statement)
+    {
+        let mut current = Some(statement);
+
+        while let Some(s) = current
+        {
+            match &s.data
+            {
+                StatementType::Empty => (),
+                StatementType::If(v) =>
+                {
+                    self.compile_bool(&v.boolexpr);
+                    let skip_then = self.chunk.push(Instruction::JumpIfFalse(0), s.span);
+                    self.compile_statement(&v.statement);
+
+                    // Only jump over the else block if one is present
+                    if v.else_statement.data == StatementType::Empty && v.else_statement.next.is_none()
+                    {
+                        self.patch(skip_then);
+                    }
+                    else
+                    {
+                        let skip_else = self.chunk.push(Instruction::Jump(0), s.span);
+                        self.patch(skip_then);
+                        self.compile_statement(&v.else_statement);
+                        self.patch(skip_else);
+                    }
+                },
+                StatementType::While(v) =>
+                {
+                    let start = self.here();
+                    self.compile_bool(&v.boolexpr);
+                    let exit = self.chunk.push(Instruction::JumpIfFalse(0), s.span);
+                    self.compile_statement(&v.statement);
+                    self.chunk.push(Instruction::Jump(start), s.span);
+                    self.patch(exit);
+                },
+                StatementType::For(v) =>
+                {
+                    self.compile_statement(&v.init);
+
+                    let start = self.here();
+                    self.compile_bool(&v.boolexpr);
+                    let exit = self.chunk.push(Instruction::JumpIfFalse(0), s.span);
+                    self.compile_statement(&v.statement);
+                    self.compile_statement(&v.step);
+                    self.chunk.push(Instruction::Jump(start), s.span);
+                    self.patch(exit);
+                },
+                StatementType::Assign(v) =>
+                {
+                    self.compile_expression(&v.expr);
+                    let slot = self.slot(&v.varname);
+                    self.chunk.push(Instruction::StoreVar(slot), s.span);
+                },
+                StatementType::Print(v) =>
+                {
+                    for e in v.exprs.iter()
+                    {
+                        self.compile_expression(e);
+                    }
+
+                    self.chunk.push(Instruction::Print(v.exprs.len()), s.span);
+                },
+                StatementType::Input(v) =>
+                {
+                    let slot = self.slot(&v.varname);
+                    self.chunk.push(Instruction::Input(slot, v.vartype.clone()), s.span);
+                },
+                StatementType::Expr(e) =>
+                {
+                    self.compile_expression(e);
+                    self.chunk.push(Instruction::Pop, s.span);
+                }
+            }
+
+            current = s.next.as_deref();
+        }
+    }
+
+    /// Compiles an expression, leaving its value on the stack
+    fn compile_expression(&mut self, expr: &Expression)
+    {
+        match expr
+        {
+            Expression::Numeric(e) => self.compile_numeric(e),
+            Expression::Boolean(e) => self.compile_bool(e),
+            Expression::String(e) => self.compile_string(e)
+        }
+    }
+
+    /// Compiles a string expression, leaving its value on the stack
+    fn compile_string(&mut self, expr: &StringExpression)
+    {
+        match &expr.value
+        {
+            StringExpressionType::Constant(v) =>
+            {
+                let index = self.chunk.constant(VariableType::String(v.clone()));
+                self.chunk.push(Instruction::LoadConst(index), expr.span);
+            },
+            StringExpressionType::Variable(name) =>
+            {
+                let slot = self.slot(name);
+                self.chunk.push(Instruction::LoadVar(slot), expr.span);
+            },
+            StringExpressionType::Call(call) => self.compile_call(call, expr.span),
+            StringExpressionType::Concat(c) =>
+            {
+                self.compile_string(&c.a);
+                self.compile_string(&c.b);
+                self.chunk.push(Instruction::Concat, expr.span);
+            }
+        }
+    }
+
+    /// Compiles a numeric expression, leaving its value on the stack
+    fn compile_numeric(&mut self, expr: &NumericExpression)
+    {
+        // Write the value, tracking the data type it provides before any promotion
+        let provided = match &expr.value
+        {
+            NumericExpressionType::IntConstant(v) =>
+            {
+                // Promote integer constants directly, rather than at runtime
+                let value = match expr.data_type
+                {
+                    NumericExpressionDataType::Int => VariableType::Integer(*v),
+                    NumericExpressionDataType::Float => VariableType::Float(*v as f32)
+                };
+
+                let index = self.chunk.constant(value);
+                self.chunk.push(Instruction::LoadConst(index), expr.span);
+                expr.data_type
+            },
+            NumericExpressionType::FloatConstant(v) =>
+            {
+                let index = self.chunk.constant(VariableType::Float(*v));
+                self.chunk.push(Instruction::LoadConst(index), expr.span);
+                NumericExpressionDataType::Float
+            },
+            NumericExpressionType::Variable(name) =>
+            {
+                let slot = self.slot(name);
+                self.chunk.push(Instruction::LoadVar(slot), expr.span);
+                expr.data_type
+            },
+            NumericExpressionType::Operation(op) =>
+            {
+                self.compile_numeric(&op.a);
+                self.compile_numeric(&op.b);
+                self.chunk.push(Instruction::Numeric(op.op), expr.span);
+                NumericExpressionDataType::promote(op.a.data_type, op.b.data_type)
+            },
+            NumericExpressionType::Call(call) =>
+            {
+                self.compile_call(call, expr.span);
+
+                // Calls returning other types of value are rejected by the type checker
+                match call.signature.result
+                {
+                    VariableType::Float(_) => NumericExpressionDataType::Float,
+                    _ => NumericExpressionDataType::Int
+                }
+            }
+        };
+
+        if expr.inverted
+        {
+            self.chunk.push(Instruction::Negate, expr.span);
+        }
+
+        // Promote integer results assigned a float type by the type checker
+        if provided == NumericExpressionDataType::Int && expr.data_type == NumericExpressionDataType::Float
+        {
+            self.chunk.push(Instruction::ToFloat, expr.span);
+        }
+    }
+
+    /// Compiles a function call, leaving the arguments on the stack in order followed by the call
+    fn compile_call(&mut self, call: &CallExpression, span: Span)
+    {
+        for arg in call.args.iter()
+        {
+            self.compile_expression(arg);
+        }
+
+        let index = self.chunk.function(&call.name, &call.signature);
+        self.chunk.push(Instruction::Call(index, call.args.len()), span);
+    }
+
+    /// Compiles a boolean expression, leaving its value on the stack. Boolean operations
+    /// short-circuit, skipping the second value if the result is already known
+    fn compile_bool(&mut self, expr: &BoolExpression)
+    {
+        match &expr.value
+        {
+            BoolExpressionType::Constant(v) =>
+            {
+                let index = self.chunk.constant(VariableType::Boolean(*v));
+                self.chunk.push(Instruction::LoadConst(index), expr.span);
+            },
+            BoolExpressionType::Variable(name) =>
+            {
+                let slot = self.slot(name);
+                self.chunk.push(Instruction::LoadVar(slot), expr.span);
+            },
+            BoolExpressionType::Call(call) => self.compile_call(call, expr.span),
+            BoolExpressionType::Comparison(c) =>
+            {
+                self.compile_numeric(&c.a);
+                self.compile_numeric(&c.b);
+                self.chunk.push(Instruction::Compare(c.op), expr.span);
+            },
+            BoolExpressionType::StringComparison(c) =>
+            {
+                self.compile_string(&c.a);
+                self.compile_string(&c.b);
+                self.chunk.push(Instruction::Compare(c.op), expr.span);
+            },
+            BoolExpressionType::Operation(op) =>
+            {
+                // Keep the first value as the result if it decides the operation
+                self.compile_bool(&op.a);
+                self.chunk.push(Instruction::Dup, expr.span);
+
+                let jump = match op.op
+                {
+                    BooleanOpType::And => Instruction::JumpIfFalse(0),
+                    BooleanOpType::Or => Instruction::JumpIfTrue(0)
+                };
+                let skip = self.chunk.push(jump, expr.span);
+
+                self.chunk.push(Instruction::Pop, expr.span);
+                self.compile_bool(&op.b);
+                self.patch(skip);
+            }
+        }
+
+        if expr.inverted
+        {
+            self.chunk.push(Instruction::Not, expr.span);
+        }
+    }
+}
+
+/// Defines tests of the bytecode compiler
+#[cfg(test)]
+mod tests
+{
+    use super::compile;
+    use crate::bytecode::Instruction;
+    use crate::checker;
+    use crate::lexer::numeric::NumericOpType;
+    use crate::lexer::program::Program;
+    use crate::token::Token;
+    use crate::variable::VariableType;
+
+    /// Parses, type checks and compiles the provided input, returning the instructions
+    fn instructions(input: &str) -> Vec<Instruction>
+    {
+        let mut program = Program::parse(&mut Token::tokenize(input).unwrap()).unwrap();
+        checker::check(&mut program).unwrap();
+        return compile(&program).code;
+    }
+
+    /// Tests that expressions are written in evaluation order with shared constants
+    #[test]
+    fn compile_expression()
+    {
+        let mut program = Program::parse(&mut Token::tokenize("a = 2 * -(1 + 2); b = a - 2;").unwrap()).unwrap();
+        checker::check(&mut program).unwrap();
+        let chunk = compile(&program);
+
+        assert_eq!(
+            chunk.code,
+            vec!{
+                Instruction::LoadConst(0),
+                Instruction::LoadConst(1),
+                Instruction::LoadConst(0),
+                Instruction::Numeric(NumericOpType::Add),
+                Instruction::Negate,
+                Instruction::Numeric(NumericOpType::Multiply),
+                Instruction::StoreVar(0),
+                Instruction::LoadVar(0),
+                Instruction::LoadConst(0),
+                Instruction::Numeric(NumericOpType::Subtract),
+                Instruction::StoreVar(1)
+            });
+        assert_eq!(chunk.constants, vec!{VariableType::Integer(2), VariableType::Integer(1)});
+        assert_eq!(chunk.slots, vec!{"a".to_string(), "b".to_string()});
+    }
+
+    /// Tests that integer values are promoted when assigned to float variables
+    #[test]
+    fn compile_promotion()
+    {
+        assert_eq!(
+            instructions("a = 1; a = 1 / 2; a = 0.5;"),
+            vec!{
+                Instruction::LoadConst(0),
+                Instruction::StoreVar(0),
+                Instruction::LoadConst(1),
+                Instruction::LoadConst(2),
+                Instruction::Numeric(NumericOpType::Divide),
+                Instruction::ToFloat,
+                Instruction::StoreVar(0),
+                Instruction::LoadConst(3),
+                Instruction::StoreVar(0)
+            });
+    }
+
+    /// Tests that if statements only jump over else blocks that are present
+    #[test]
+    fn compile_if()
+    {
+        assert_eq!(
+            instructions("if (true) { a = 1; }"),
+            vec!{
+                Instruction::LoadConst(0),
+                Instruction::JumpIfFalse(4),
+                Instruction::LoadConst(1),
+                Instruction::StoreVar(0)
+            });
+        assert_eq!(
+            instructions("if (!true) { a = 1; } else { a = 2; }"),
+            vec!{
+                Instruction::LoadConst(0),
+                Instruction::Not,
+                Instruction::JumpIfFalse(6),
+                Instruction::LoadConst(1),
+                Instruction::StoreVar(0),
+                Instruction::Jump(8),
+                Instruction::LoadConst(2),
+                Instruction::StoreVar(0)
+            });
+    }
+}
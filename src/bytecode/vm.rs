@@ -0,0 +1,438 @@
+use crate::function::Functions;
+use crate::interpreter::Interpreter;
+use crate::interpreter::error::RuntimeError;
+use crate::interpreter::io::{self, Reader, StdinReader, Writer};
+use crate::span::Span;
+use crate::variable::{Variable, VariableType};
+use std::collections::HashMap;
+
+use super::{Chunk, Instruction};
+
+/// Defines a stack-based virtual machine that executes compiled chunks. Input and print
+/// instructions use the reader and writer, which may borrow for the lifetime of the machine
+pub struct Vm<'io>
+{
+    /// Defines the value in each variable slot, or None if the variable has not been assigned
+    slots: Vec<Option<VariableType>>,
+
+    /// Defines the stack of intermediate values
+    stack: Vec<VariableType>,
+
+    /// Defines the host functions that chunks may call
+    pub functions: Functions,
+
+    /// Defines the source of lines read by input instructions
+    pub reader: Box<dyn Reader + Send + 'io>,
+
+    /// Defines the destination of lines written by print instructions
+    pub writer: Box<dyn Writer + Send + 'io>
+}
+
+impl Default for Vm<'static>
+{
+    fn default() -> Vm<'static>
+    {
+        return Vm::new();
+    }
+}
+
+impl Vm<'static>
+{
+    /// Returns a new virtual machine with no variables defined, reading from standard input and
+    /// writing to standard output
+    pub fn new() -> Vm<'static>
+    {
+        return Vm::with_io(Box::new(StdinReader), Box::new(std::io::stdout()));
+    }
+
+    /// Returns a new virtual machine with no variables defined, calling the provided functions
+    pub fn with_functions(functions: Functions) -> Vm<'static>
+    {
+        return Vm
+        {
+            functions,
+            ..Vm::new()
+        };
+    }
+}
+
+impl<'io> Vm<'io>
+{
+    /// Returns a new virtual machine with no variables defined, using the provided reader and
+    /// writer for input and print instructions. The reader and writer must be Send, so that the
+    /// machine may be moved to another thread
+    pub fn with_io(reader: Box<dyn Reader + Send + 'io>, writer: Box<dyn Writer + Send + 'io>) -> Vm<'io>
+    {
+        return Vm
+        {
+            slots: Vec::new(),
+            stack: Vec::new(),
+            functions: Functions::new(),
+            reader,
+            writer
+        };
+    }
+
+    /// Executes the chunk from the first instruction, returning the final variable state
+    pub fn run(&mut self, chunk: &Chunk) -> Result<HashMap<String, Variable>, RuntimeError>
+    {
+        self.slots = vec![None; chunk.slots.len()];
+        self.stack.clear();
+
+        let mut pc = 0;
+
+        while pc < chunk.code.len()
+        {
+            let instruction = chunk.code[pc].clone();
+            let span = chunk.spans[pc];
+            pc += 1;
+
+            match instruction
+            {
+                Instruction::LoadConst(c) => self.stack.push(chunk.constants[c].clone()),
+                Instruction::LoadVar(s) => match &self.slots[s]
+                {
+                    Some(v) => self.stack.push(v.clone()),
+                    None => return Err(RuntimeError::UndefinedVariable
+                    {
+                        name: chunk.slots[s].clone(),
+                        span
+                    })
+                },
+                Instruction::StoreVar(s) => self.slots[s] = Some(self.pop()),
+                Instruction::Pop =>
+                {
+                    self.pop();
+                },
+                Instruction::Dup =>
+                {
+                    let v = self.peek();
+                    self.stack.push(v);
+                },
+                Instruction::Numeric(op) =>
+                {
+                    let b = self.pop();
+                    let a = self.pop();
+                    self.stack.push(Interpreter::numeric_operation(op, a, b, span)?);
+                },
+                Instruction::Compare(op) =>
+                {
+                    let b = self.pop();
+                    let a = self.pop();
+
+                    let result = match (a, b)
+                    {
+                        (VariableType::Integer(a), VariableType::Integer(b)) => op.compare(a, b),
+                        (VariableType::String(a), VariableType::String(b)) => op.compare(a, b),
+                        (a, b) => op.compare(Interpreter::as_float(a, span)?, Interpreter::as_float(b, span)?)
+                    };
+
+                    self.stack.push(VariableType::Boolean(result));
+                },
+                Instruction::Concat =>
+                {
+                    let b = self.pop_string(span)?;
+                    let a = self.pop_string(span)?;
+                    self.stack.push(VariableType::String(a + &b));
+                },
+                Instruction::Negate =>
+                {
+                    let value = match self.pop()
+                    {
+                        VariableType::Integer(v) => VariableType::Integer(v.checked_neg().ok_or(RuntimeError::IntegerOverflow { span })?),
+                        VariableType::Float(v) => VariableType::Float(-v),
+                        v => return Err(RuntimeError::mismatch("numeric", v, span))
+                    };
+
+                    self.stack.push(value);
+                },
+                Instruction::Not =>
+                {
+                    let value = self.pop_bool(span)?;
+                    self.stack.push(VariableType::Boolean(!value));
+                },
+                Instruction::ToFloat =>
+                {
+                    let value = Interpreter::as_float(self.pop(), span)?;
+                    self.stack.push(VariableType::Float(value));
+                },
+                Instruction::Jump(t) => pc = t,
+                Instruction::JumpIfFalse(t) =>
+                {
+                    if !self.pop_bool(span)?
+                    {
+                        pc = t;
+                    }
+                },
+                Instruction::JumpIfTrue(t) =>
+                {
+                    if self.pop_bool(span)?
+                    {
+                        pc = t;
+                    }
+                },
+                Instruction::Call(f, n) =>
+                {
+                    let (name, signature) = &chunk.functions[f];
+                    let args = self.stack.split_off(self.stack.len() - n);
+
+                    let function = match self.functions.get(name)
+                    {
+                        Some(v) if v.signature == *signature => v,
+                        _ => return Err(RuntimeError::UndefinedFunction
+                        {
+                            name: name.clone(),
+                            span
+                        })
+                    };
+
+                    self.stack.push(function.call(name, &args, span)?);
+                },
+                Instruction::Print(n) =>
+                {
+                    let values = self.stack.split_off(self.stack.len() - n);
+                    io::print_values(&mut *self.writer, &values, span)?;
+                },
+                Instruction::Input(s, expected) =>
+                {
+                    self.slots[s] = Some(io::read_input(&mut *self.reader, &expected, span)?);
+                }
+            }
+        }
+
+        return Ok(self.variables(chunk));
+    }
+
+    /// Returns the assigned variables, keyed by the variable names within the chunk
+    pub fn variables(&self, chunk: &Chunk) -> HashMap<String, Variable>
+    {
+        return chunk.slots
+            .iter()
+            .zip(self.slots.iter())
+            .filter_map(|(name, value)| value.clone().map(|vartype| (name.clone(), Variable
+            {
+                vartype,
+                varname: name.clone()
+            })))
+            .collect();
+    }
+
+    /// Removes and returns the top value of the stack
+    fn pop(&mut self) -> VariableType
+    {
+        return self.stack.pop().expect("stack underflow");
+    }
+
+    /// Returns the top value of the stack without removing it
+    fn peek(&self) -> VariableType
+    {
+        return self.stack.last().expect("stack underflow").clone();
+    }
+
+    /// Removes and returns the top value of the stack, using the span of the instruction to
+    /// report a value that is not a boolean
+    fn pop_bool(&mut self, span: Span) -> Result<bool, RuntimeError>
+    {
+        return match self.pop()
+        {
+            VariableType::Boolean(v) => Ok(v),
+            v => Err(RuntimeError::mismatch("boolean", v, span))
+        };
+    }
+
+    /// Removes and returns the top value of the stack, using the span of the instruction to
+    /// report a value that is not a string
+    fn pop_string(&mut self, span: Span) -> Result<String, RuntimeError>
+    {
+        return match self.pop()
+        {
+            VariableType::String(v) => Ok(v),
+            v => Err(RuntimeError::mismatch("string", v, span))
+        };
+    }
+}
+
+/// Defines tests of the virtual machine
+#[cfg(test)]
+mod tests
+{
+    use super::Vm;
+    use crate::bytecode::compiler;
+    use crate::checker;
+    use crate::interpreter::Interpreter;
+    use crate::interpreter::error::RuntimeError;
+    use crate::lexer::program::Program;
+    use crate::span::Span;
+    use crate::token::Token;
+    use crate::variable::{Variable, VariableType};
+    use std::collections::HashMap;
+
+    /// Parses and type checks the provided input
+    fn checked(input: &str) -> Program
+    {
+        let mut program = Program::parse(&mut Token::tokenize(input).unwrap()).unwrap();
+        checker::check(&mut program).unwrap();
+        return program;
+    }
+
+    /// Compiles and runs the provided input, returning the final variables
+    fn run(input: &str) -> Result<HashMap<String, Variable>, RuntimeError>
+    {
+        return Vm::new().run(&compiler::compile(&checked(input)));
+    }
+
+    /// Tests that programs provide the same results as the tree-walking interpreter
+    #[test]
+    fn run_matches_interpreter()
+    {
+        let cases = [
+            "a = 1 + 2 * 3; b = 7 / 2; c = 7 / 2.0; d = 1 < 2 && !(2.5 >= 3); e = -4 - -2;",
+            "if (1 == 1.0) { a = 1; } else { a = 2; } if (false || 2 < 1) { b = 1; } else { b = 2; }",
+            "s = 0; for (i = 1; i <= 4; i = i + 1) { s = s + i * i; } f = 1.5; f = f * s;",
+            "n = 0; done = false; while (!done) { n -= 1; done = n < -2 || false; }",
+            "a = 7 / 2; b = a; a += 0.5; c = 1; for (i = 0; i < 2; i += 1) { c = c / 2; c = c * 1.0; }",
+            "x = true; y = !x && (x || 1 / 1 > 0); z = !(x && y) || !!x;",
+            "while (false) { a = 1; } for (i = 3; 2 < 1; j = 1) { k = 1; }",
+            "s = \"a\"; for (i = 0; i < 3; i += 1) { s += s + \"\\n\"; } t = (\"x\" + s) != s && s == s + \"\";"
+        ];
+
+        for input in cases.iter()
+        {
+            let program = checked(input);
+            let mut interpreter = Interpreter::new();
+            let expected = interpreter.run(&program).unwrap().clone();
+
+            assert_eq!(Vm::new().run(&compiler::compile(&program)).unwrap(), expected, "{0:}", input);
+        }
+    }
+
+    /// Tests that calls pass their arguments in order and match the interpreter results
+    #[test]
+    fn run_calls()
+    {
+        let mut interpreter = Interpreter::new();
+        interpreter.register("clamp", |x: f32, lo: f32, hi: f32| x.max(lo).min(hi));
+        interpreter.register("sub", |a: i32, b: i32| a - b);
+        interpreter.register("even", |x: i32| x % 2 == 0);
+
+        let mut program = Program::parse_with(
+            &mut Token::tokenize("a = clamp(7, 0, 2.5); b = sub(10, 3) * 2; c = even(b) && !even(sub(b, 1));").unwrap(),
+            HashMap::new(),
+            &interpreter.functions).unwrap();
+        checker::check(&mut program).unwrap();
+
+        let chunk = compiler::compile(&program);
+        let variables = Vm::with_functions(interpreter.functions.clone()).run(&chunk).unwrap();
+
+        assert_eq!(variables["b"].vartype, VariableType::Integer(14));
+        assert_eq!(&variables, interpreter.run(&program).unwrap());
+        assert!(matches!(Vm::new().run(&chunk), Err(RuntimeError::UndefinedFunction { .. })));
+    }
+
+    /// Tests that print and input instructions provide the same results as the interpreter
+    #[test]
+    fn run_print_input()
+    {
+        let program = checked("n = 0; ok = false; input n; s = 0; for (i = 0; i < n; i += 1) { s += i; print i, s, s > 2; } input ok; print ok;");
+        let input: &[u8] = b"4\ntrue\n";
+
+        let mut expected: Vec<u8> = Vec::new();
+        Interpreter::with_io(Box::new(input), Box::new(&mut expected)).run(&program).unwrap();
+
+        let mut output: Vec<u8> = Vec::new();
+        Vm::with_io(Box::new(input), Box::new(&mut output)).run(&compiler::compile(&program)).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "0 0 false\n1 1 false\n2 3 true\n3 6 true\ntrue\n");
+        assert_eq!(expected, b"0 0 false\n1 1 false\n2 3 true\n3 6 true\ntrue\n");
+    }
+
+    /// Tests that boolean operations skip the second value if the result is already known
+    #[test]
+    fn run_short_circuit()
+    {
+        let variables = run("a = false && 1 / 0 > 0; b = true || 1 / 0 > 0;").unwrap();
+
+        assert_eq!(variables["a"].vartype, VariableType::Boolean(false));
+        assert_eq!(variables["b"].vartype, VariableType::Boolean(true));
+    }
+
+    /// Tests that runtime errors are reported with the source location of the instruction
+    #[test]
+    fn run_errors()
+    {
+        assert_eq!(run("a = 1 / 0;").err(), Some(RuntimeError::DivisionByZero { span: Span::new(4, 9, 1, 5) }));
+        assert_eq!(run("a = 2147483647 + 1;").err(), Some(RuntimeError::IntegerOverflow { span: Span::new(4, 18, 1, 5) }));
+        assert_eq!(
+            run("if (false) { a = 1; } b = a;").err(),
+            Some(RuntimeError::UndefinedVariable { name: "a".to_string(), span: Span::new(26, 27, 1, 27) }));
+    }
+
+    /// Tests that chunks compiled from programs that were not type checked report mismatched
+    /// values and calls instead of panicking, as the interpreter does
+    #[test]
+    fn run_unchecked()
+    {
+        let mut interpreter = Interpreter::new();
+        interpreter.register("sub", |a: i32, b: i32| a - b);
+
+        let inputs = [
+            ("a = 1; a = true; b = a + 1;", "numeric"),
+            ("a = 1; a = true; b = -a;", "numeric"),
+            ("a = true; a = 2.5; if (a) { }", "boolean"),
+            ("a = true; a = 2.5; b = !a;", "boolean"),
+            ("s = \"x\"; s = 1; t = s + \"y\";", "string"),
+            ("b = true; c = sub(1, b);", "integer")
+        ];
+
+        for (input, expected) in inputs.iter()
+        {
+            let program = Program::parse_with(&mut Token::tokenize(input).unwrap(), HashMap::new(), &interpreter.functions).unwrap();
+            let result = Vm::with_functions(interpreter.functions.clone()).run(&compiler::compile(&program));
+
+            assert!(matches!(result, Err(RuntimeError::TypeMismatch { expected: e, .. }) if e == *expected), "{0:}: {1:?}", input, result);
+            assert!(matches!(interpreter.run(&program), Err(RuntimeError::TypeMismatch { .. })), "{0:}", input);
+        }
+
+        // Check that calls with a different number of arguments than the signature are not made
+        let program = Program::parse_with(&mut Token::tokenize("a = sub(1);").unwrap(), HashMap::new(), &interpreter.functions).unwrap();
+        let result = Vm::with_functions(interpreter.functions.clone()).run(&compiler::compile(&program));
+
+        assert!(matches!(result, Err(RuntimeError::UndefinedFunction { .. })), "{0:?}", result);
+        assert!(matches!(interpreter.run(&program), Err(RuntimeError::UndefinedFunction { .. })));
+    }
+
+    /// Compares the time taken to run a long loop with the tree-walking interpreter and the
+    /// virtual machine. This test is timing-dependent and is ignored by default; run it with
+    /// cargo test --release -- --ignored --nocapture vm_benchmark
+    #[test]
+    #[ignore]
+    fn vm_benchmark()
+    {
+        let program = checked(
+            "s = 0; t = 0.0; for (i = 0; i < 1000000; i += 1) { s = (s + i * 3) / 2 - 1; if (s > 1000 && i != 0) { s -= 1000; } t += 0.5; }");
+        let chunk = compiler::compile(&program);
+
+        // Take the best of several runs to reduce noise
+        let time = |f: &dyn Fn() -> HashMap<String, Variable>| -> std::time::Duration
+        {
+            return (0..3)
+                .map(|_|
+                {
+                    let start = std::time::Instant::now();
+                    let variables = f();
+                    assert_eq!(variables["i"].vartype, VariableType::Integer(1000000));
+                    start.elapsed()
+                })
+                .min()
+                .unwrap();
+        };
+
+        let ast = time(&|| Interpreter::new().run(&program).unwrap().clone());
+        let vm = time(&|| Vm::new().run(&chunk).unwrap());
+
+        println!("interpreter: {0:>10.3?}", ast);
+        println!("vm:          {0:>10.3?} ({1:.2}x)", vm, ast.as_secs_f64() / vm.as_secs_f64());
+
+        assert!(vm < ast);
+    }
+}
@@ -0,0 +1,601 @@
+use crate::lexer::boolean::{BoolExpression, BoolExpressionType};
+use crate::lexer::call::CallExpression;
+use crate::lexer::expression::Expression;
+use crate::lexer::numeric::{NumericExpression, NumericExpressionDataType, NumericExpressionType};
+use crate::lexer::program::Program;
+use crate::lexer::statement::{Statement, StatementType, VarStatement};
+use crate::lexer::string::{StringExpression, StringExpressionType};
+use crate::span::Span;
+use crate::variable::{Variable, VariableType};
+use std::collections::HashMap;
+
+pub mod error;
+
+use error::TypeError;
+
+/// Checks the types of every expression in the program, updating the data type of each numeric
+/// expression and recording the type of each variable in the program variables. A variable
+/// assigned both integer and float values is promoted to a float, with integer values converted
+/// as they are assigned. Mixing boolean and numeric values is reported as an error
+pub fn check(program: &mut Program) -> Result<(), TypeError>
+{
+    return check_with(program, &HashMap::new());
+}
+
+/// Checks the types of every expression in the program, where the provided variables are
+/// treated as assigned before the program starts, such as by a previous program in the same session
+pub fn check_with(program: &mut Program, variables: &HashMap<String, Variable>) -> Result<(), TypeError>
+{
+    let mut checker = TypeChecker
+    {
+        variables: variables.clone(),
+        promoted: false
+    };
+
+    // Check the program, repeating while variables are promoted to floats, as expressions that
+    // read the variable earlier in the program must also be updated
+    loop
+    {
+        checker.promoted = false;
+        checker.check_statement(&mut program.main)?;
+
+        if !checker.promoted
+        {
+            break;
+        }
+    }
+
+    program.variables = checker.variables;
+    return Ok(());
+}
+
+/// Defines the state of the type checker while walking a program
+struct TypeChecker
+{
+    /// Defines the variable types found so far, keyed by variable name
+    variables: HashMap<String, Variable>,
+
+    /// Indicates that a variable was promoted from an integer to a float in the current pass
+    promoted: bool
+}
+
+impl TypeChecker
+{
+    /// Checks the statement and each statement linked after it
+    fn check_statement(&mut self, statement: &mut Statement) -> Result<(), TypeError>
+    {
+        let mut current = Some(statement);
+
+        while let Some(s) = current
+        {
+            match &mut s.data
+            {
+                StatementType::Empty => (),
+                StatementType::If(v) =>
+                {
+                    self.check_bool(&mut v.boolexpr)?;
+                    self.check_statement(&mut v.statement)?;
+                    self.check_statement(&mut v.else_statement)?;
+                },
+                StatementType::While(v) =>
+                {
+                    self.check_bool(&mut v.boolexpr)?;
+                    self.check_statement(&mut v.statement)?;
+                },
+                StatementType::For(v) =>
+                {
+                    self.check_statement(&mut v.init)?;
+                    self.check_bool(&mut v.boolexpr)?;
+                    self.check_statement(&mut v.statement)?;
+                    self.check_statement(&mut v.step)?;
+                },
+                StatementType::Assign(v) => self.check_assignment(v)?,
+                StatementType::Print(v) =>
+                {
+                    for e in v.exprs.iter_mut()
+                    {
+                        self.check_expression(e)?;
+                    }
+                },
+                StatementType::Input(v) =>
+                {
+                    // Read values of the current variable type, recording the variable if new
+                    v.vartype = self.variables.entry(v.varname.clone()).or_insert(Variable
+                    {
+                        vartype: v.vartype.clone(),
+                        varname: v.varname.clone()
+                    }).vartype.clone();
+                },
+                StatementType::Expr(e) =>
+                {
+                    self.check_expression(e)?;
+                }
+            }
+
+            current = s.next.as_deref_mut();
+        }
+
+        return Ok(());
+    }
+
+    /// Checks an assignment against the type of any previous assignments to the same variable
+    fn check_assignment(&mut self, statement: &mut VarStatement) -> Result<(), TypeError>
+    {
+        let found = self.check_expression(&mut statement.expr)?;
+
+        // Record the type of the first assignment
+        let variable = match self.variables.get_mut(&statement.varname)
+        {
+            Some(v) => v,
+            None =>
+            {
+                self.variables.insert(statement.varname.clone(), Variable
+                {
+                    vartype: found,
+                    varname: statement.varname.clone()
+                });
+                return Ok(());
+            }
+        };
+
+        match (&variable.vartype, &found)
+        {
+            // Promote the variable if a float is assigned to an integer variable
+            (VariableType::Integer(_), VariableType::Float(_)) =>
+            {
+                variable.vartype = found;
+                self.promoted = true;
+            },
+
+            // Promote the value if an integer is assigned to a float variable
+            (VariableType::Float(_), VariableType::Integer(_)) =>
+            {
+                if let Expression::Numeric(e) = &mut statement.expr
+                {
+                    e.data_type = NumericExpressionDataType::Float;
+                }
+            },
+            (expected, found) if std::mem::discriminant(expected) == std::mem::discriminant(found) => (),
+            (expected, _) => return Err(TypeError::MismatchedAssignment
+            {
+                name: statement.varname.clone(),
+                expected: expected.clone(),
+                found,
+                span: statement.expr.span()
+            })
+        }
+
+        return Ok(());
+    }
+
+    /// Checks an expression, returning the type of value it provides
+    fn check_expression(&self, expr: &mut Expression) -> Result<VariableType, TypeError>
+    {
+        match expr
+        {
+            Expression::Numeric(e) => self.check_numeric(e)?,
+            Expression::Boolean(e) => self.check_bool(e)?,
+            Expression::String(e) => self.check_string(e)?
+        }
+
+        return Ok(expr.variable_type());
+    }
+
+    /// Checks a numeric expression, updating the data type of the expression and each value within it
+    fn check_numeric(&self, expr: &mut NumericExpression) -> Result<(), TypeError>
+    {
+        let data_type = match &mut expr.value
+        {
+            NumericExpressionType::IntConstant(_) => NumericExpressionDataType::Int,
+            NumericExpressionType::FloatConstant(_) => NumericExpressionDataType::Float,
+            NumericExpressionType::Variable(name) => match self.lookup(name, expr.span)?
+            {
+                VariableType::Integer(_) => NumericExpressionDataType::Int,
+                VariableType::Float(_) => NumericExpressionDataType::Float,
+                found => return Err(TypeError::MismatchedVariable
+                {
+                    name: name.clone(),
+                    expected: "numeric",
+                    found,
+                    span: expr.span
+                })
+            },
+            NumericExpressionType::Operation(op) =>
+            {
+                self.check_numeric(&mut op.a)?;
+                self.check_numeric(&mut op.b)?;
+                NumericExpressionDataType::promote(op.a.data_type, op.b.data_type)
+            },
+            NumericExpressionType::Call(call) =>
+            {
+                self.check_call(call, expr.span)?;
+
+                match &call.signature.result
+                {
+                    VariableType::Integer(_) => NumericExpressionDataType::Int,
+                    VariableType::Float(_) => NumericExpressionDataType::Float,
+                    found => return Err(TypeError::MismatchedResult
+                    {
+                        name: call.name.clone(),
+                        expected: "numeric",
+                        found: found.clone(),
+                        span: expr.span
+                    })
+                }
+            }
+        };
+
+        expr.data_type = data_type;
+        return Ok(());
+    }
+
+    /// Checks a boolean expression, ensuring that each variable read within it is a boolean
+    fn check_bool(&self, expr: &mut BoolExpression) -> Result<(), TypeError>
+    {
+        return match &mut expr.value
+        {
+            BoolExpressionType::Constant(_) => Ok(()),
+            BoolExpressionType::Variable(name) => match self.lookup(name, expr.span)?
+            {
+                VariableType::Boolean(_) => Ok(()),
+                found => Err(TypeError::MismatchedVariable
+                {
+                    name: name.clone(),
+                    expected: "boolean",
+                    found,
+                    span: expr.span
+                })
+            },
+            BoolExpressionType::Comparison(c) =>
+            {
+                self.check_numeric(&mut c.a)?;
+                self.check_numeric(&mut c.b)
+            },
+            BoolExpressionType::StringComparison(c) =>
+            {
+                self.check_string(&mut c.a)?;
+                self.check_string(&mut c.b)
+            },
+            BoolExpressionType::Operation(op) =>
+            {
+                self.check_bool(&mut op.a)?;
+                self.check_bool(&mut op.b)
+            },
+            BoolExpressionType::Call(call) =>
+            {
+                self.check_call(call, expr.span)?;
+                TypeChecker::check_result(call, VariableType::Boolean(false), expr.span)
+            }
+        };
+    }
+
+    /// Checks a string expression, ensuring that each variable read within it is a string
+    fn check_string(&self, expr: &mut StringExpression) -> Result<(), TypeError>
+    {
+        return match &mut expr.value
+        {
+            StringExpressionType::Constant(_) => Ok(()),
+            StringExpressionType::Variable(name) => match self.lookup(name, expr.span)?
+            {
+                VariableType::String(_) => Ok(()),
+                found => Err(TypeError::MismatchedVariable
+                {
+                    name: name.clone(),
+                    expected: "string",
+                    found,
+                    span: expr.span
+                })
+            },
+            StringExpressionType::Concat(c) =>
+            {
+                self.check_string(&mut c.a)?;
+                self.check_string(&mut c.b)
+            },
+            StringExpressionType::Call(call) =>
+            {
+                self.check_call(call, expr.span)?;
+                TypeChecker::check_result(call, VariableType::String(String::new()), expr.span)
+            }
+        };
+    }
+
+    /// Checks the arguments of a function call against the parameters of its signature,
+    /// promoting integer arguments passed to float parameters
+    fn check_call(&self, call: &mut CallExpression, span: Span) -> Result<(), TypeError>
+    {
+        if call.args.len() != call.signature.params.len()
+        {
+            return Err(TypeError::ArgumentCount
+            {
+                name: call.name.clone(),
+                expected: call.signature.params.len(),
+                found: call.args.len(),
+                span
+            });
+        }
+
+        for (index, (arg, expected)) in call.args.iter_mut().zip(call.signature.params.iter()).enumerate()
+        {
+            let found = self.check_expression(arg)?;
+
+            match (expected, found)
+            {
+                // Promote the value if an integer is passed to a float parameter
+                (VariableType::Float(_), VariableType::Integer(_)) =>
+                {
+                    if let Expression::Numeric(e) = arg
+                    {
+                        e.data_type = NumericExpressionDataType::Float;
+                    }
+                },
+                (expected, found) if std::mem::discriminant(expected) == std::mem::discriminant(&found) => (),
+                (expected, found) => return Err(TypeError::MismatchedArgument
+                {
+                    name: call.name.clone(),
+                    index,
+                    expected: expected.clone(),
+                    found,
+                    span: arg.span()
+                })
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Checks that the signature of a function call returns a value of the same type as the
+    /// expected boolean or string value
+    fn check_result(call: &CallExpression, expected: VariableType, span: Span) -> Result<(), TypeError>
+    {
+        if std::mem::discriminant(&expected) == std::mem::discriminant(&call.signature.result)
+        {
+            return Ok(());
+        }
+
+        return Err(TypeError::MismatchedResult
+        {
+            name: call.name.clone(),
+            expected: expected.type_name(),
+            found: call.signature.result.clone(),
+            span
+        });
+    }
+
+    /// Returns the type of the variable with the provided name
+    fn lookup(&self, name: &str, span: Span) -> Result<VariableType, TypeError>
+    {
+        return match self.variables.get(name)
+        {
+            Some(v) => Ok(v.vartype.clone()),
+            None => Err(TypeError::UnknownVariable
+            {
+                name: name.to_string(),
+                span
+            })
+        };
+    }
+}
+
+/// Defines tests of the type checker
+#[cfg(test)]
+mod tests
+{
+    use super::check;
+    use super::error::TypeError;
+    use crate::function::Functions;
+    use crate::lexer::boolean::{BoolExpression, BoolExpressionType};
+    use crate::lexer::expression::Expression;
+    use crate::lexer::numeric::NumericExpressionDataType;
+    use crate::lexer::program::Program;
+    use crate::lexer::statement::StatementType;
+    use crate::span::Span;
+    use crate::token::Token;
+    use crate::variable::VariableType;
+    use std::collections::HashMap;
+
+    /// Parses and type checks the provided input, which may call a clamp function on floats and
+    /// an even function on integers
+    fn checked(input: &str) -> Result<Program, TypeError>
+    {
+        let mut functions = Functions::new();
+        functions.register("clamp", |x: f32, lo: f32, hi: f32| x.max(lo).min(hi));
+        functions.register("even", |x: i32| x % 2 == 0);
+
+        let mut program = Program::parse_with(&mut Token::tokenize(input).unwrap(), HashMap::new(), &functions).unwrap();
+        check(&mut program)?;
+        return Ok(program);
+    }
+
+    /// Returns the data type of the numeric expression assigned by the statement at the index
+    fn assigned_type(program: &Program, index: usize) -> NumericExpressionDataType
+    {
+        let mut statement = &program.main;
+
+        for _ in 0..index
+        {
+            statement = statement.next.as_ref().unwrap();
+        }
+
+        return match &statement.data
+        {
+            StatementType::Assign(v) => match &v.expr
+            {
+                Expression::Numeric(e) => e.data_type,
+                _ => panic!("expected numeric expression")
+            },
+            _ => panic!("expected assignment")
+        };
+    }
+
+    /// Tests that variables assigned both integer and float values are promoted to floats,
+    /// including expressions that read the variable before the promotion
+    #[test]
+    fn check_promotion()
+    {
+        let program = checked("a = 1; b = a * 2; c = b < 3; a = 2.5; d = 4;").unwrap();
+
+        assert_eq!(program.variables["a"].vartype, VariableType::Float(0.0));
+        assert_eq!(program.variables["b"].vartype, VariableType::Float(0.0));
+        assert_eq!(program.variables["c"].vartype, VariableType::Boolean(false));
+        assert_eq!(program.variables["d"].vartype, VariableType::Integer(0));
+        assert_eq!(assigned_type(&program, 0), NumericExpressionDataType::Float);
+        assert_eq!(assigned_type(&program, 1), NumericExpressionDataType::Float);
+        assert_eq!(assigned_type(&program, 4), NumericExpressionDataType::Int);
+    }
+
+    /// Tests that promotions within loops propagate to every variable that depends on them
+    #[test]
+    fn check_loop_promotion()
+    {
+        let program = checked("x = 0; y = 0; for (i = 0; i < 3; i += 1) { y = x / 2; x += 0.5; }").unwrap();
+
+        assert_eq!(program.variables["x"].vartype, VariableType::Float(0.0));
+        assert_eq!(program.variables["y"].vartype, VariableType::Float(0.0));
+        assert_eq!(program.variables["i"].vartype, VariableType::Integer(0));
+    }
+
+    /// Tests that assigning boolean and numeric values to the same variable is rejected
+    #[test]
+    fn check_mismatch()
+    {
+        assert_eq!(
+            checked("a = 1;\na = 1 < 2;").unwrap_err(),
+            TypeError::MismatchedAssignment
+            {
+                name: "a".to_string(),
+                expected: VariableType::Integer(0),
+                found: VariableType::Boolean(false),
+                span: Span::new(11, 16, 2, 5)
+            });
+        assert!(matches!(checked("b = true; if (b) { b = 2.5; }"), Err(TypeError::MismatchedAssignment { .. })));
+
+        // Mixing within a single expression is rejected by the grammar before checking
+        assert!(Program::parse(&mut Token::tokenize("a = true + 1;").unwrap()).is_err());
+        assert!(Program::parse(&mut Token::tokenize("if (3) { }").unwrap()).is_err());
+    }
+
+    /// Tests that string variables may not hold or be used as other types of value
+    #[test]
+    fn check_strings()
+    {
+        let program = checked("s = \"a\"; s += \"b\"; same = s == \"ab\";").unwrap();
+        assert_eq!(program.variables["s"].vartype, VariableType::String(String::new()));
+        assert_eq!(program.variables["same"].vartype, VariableType::Boolean(false));
+
+        assert_eq!(
+            checked("s = \"a\"; s = 1;").unwrap_err(),
+            TypeError::MismatchedAssignment
+            {
+                name: "s".to_string(),
+                expected: VariableType::String(String::new()),
+                found: VariableType::Integer(0),
+                span: Span::new(13, 14, 1, 14)
+            });
+        assert!(matches!(checked("n = 1.5; n = \"a\";"), Err(TypeError::MismatchedAssignment { .. })));
+    }
+
+    /// Tests that input statements read values of the final variable type
+    #[test]
+    fn check_input()
+    {
+        let program = checked("x = 1; input x; x = 2.5; input y; b = true; input b;").unwrap();
+
+        let input_type = |index: usize|
+        {
+            let mut statement = &program.main;
+
+            for _ in 0..index
+            {
+                statement = statement.next.as_ref().unwrap();
+            }
+
+            return match &statement.data
+            {
+                StatementType::Input(v) => v.vartype.clone(),
+                _ => panic!("expected input")
+            };
+        };
+
+        assert_eq!(input_type(1), VariableType::Float(0.0));
+        assert_eq!(input_type(3), VariableType::Float(0.0));
+        assert_eq!(input_type(5), VariableType::Boolean(false));
+        assert_eq!(program.variables["y"].vartype, VariableType::Float(0.0));
+        assert!(matches!(checked("input x; x = true;"), Err(TypeError::MismatchedAssignment { .. })));
+    }
+
+    /// Tests that call arguments are checked against the function signature, with integer
+    /// arguments promoted to float parameters
+    #[test]
+    fn check_calls()
+    {
+        let program = checked("i = 1; a = clamp(i, 0, 2.5); b = even(i * 2);").unwrap();
+
+        assert_eq!(program.variables["a"].vartype, VariableType::Float(0.0));
+        assert_eq!(program.variables["b"].vartype, VariableType::Boolean(false));
+        assert_eq!(assigned_type(&program, 1), NumericExpressionDataType::Float);
+
+        assert_eq!(
+            checked("a = clamp(1, 2);").unwrap_err(),
+            TypeError::ArgumentCount
+            {
+                name: "clamp".to_string(),
+                expected: 3,
+                found: 2,
+                span: Span::new(4, 15, 1, 5)
+            });
+        assert_eq!(
+            checked("a = even(1.5);").unwrap_err(),
+            TypeError::MismatchedArgument
+            {
+                name: "even".to_string(),
+                index: 0,
+                expected: VariableType::Integer(0),
+                found: VariableType::Float(0.0),
+                span: Span::new(9, 12, 1, 10)
+            });
+        assert!(matches!(checked("a = clamp(1, true, 2);"), Err(TypeError::MismatchedArgument { index: 1, .. })));
+        assert!(matches!(checked("x = 1; a = even(x); x = 0.5;"), Err(TypeError::MismatchedArgument { .. })));
+    }
+
+    /// Tests that variables read as the wrong kind of value are rejected for programs that were
+    /// not produced by the parser
+    #[test]
+    fn check_variable_kind()
+    {
+        let source = "a = 1; b = a + 1;";
+
+        // Replace the first assignment with a boolean value
+        let mut program = Program::parse(&mut Token::tokenize(source).unwrap()).unwrap();
+        if let StatementType::Assign(v) = &mut program.main.data
+        {
+            v.expr = Expression::Boolean(BoolExpression
+            {
+                inverted: false,
+                value: BoolExpressionType::Constant(true),
+                span: Span::new(4, 5, 1, 5)
+            });
+        }
+
+        assert_eq!(
+            check(&mut program).unwrap_err(),
+            TypeError::MismatchedVariable
+            {
+                name: "a".to_string(),
+                expected: "numeric",
+                found: VariableType::Boolean(false),
+                span: Span::new(11, 12, 1, 12)
+            });
+
+        // Remove the first assignment entirely
+        let mut program = Program::parse(&mut Token::tokenize(source).unwrap()).unwrap();
+        program.main = *program.main.next.take().unwrap();
+
+        assert_eq!(
+            check(&mut program).unwrap_err(),
+            TypeError::UnknownVariable
+            {
+                name: "a".to_string(),
+                span: Span::new(11, 12, 1, 12)
+            });
+    }
+}
@@ -0,0 +1,106 @@
+use crate::span::Span;
+use crate::variable::VariableType;
+
+/// Defines the errors that may be raised while type checking a program
+#[derive(Debug, PartialEq, Clone)]
+pub enum TypeError
+{
+    /// Indicates that a variable was assigned a value of a different type than its other assignments
+    MismatchedAssignment
+    {
+        name: String,
+        expected: VariableType,
+        found: VariableType,
+        span: Span
+    },
+
+    /// Indicates that a variable was read as a different kind of value than it holds, such as a
+    /// boolean variable read as a numeric value, where the expected kind is numeric, boolean or
+    /// string
+    MismatchedVariable
+    {
+        name: String,
+        expected: &'static str,
+        found: VariableType,
+        span: Span
+    },
+
+    /// Indicates that a variable was read without any prior assignment
+    UnknownVariable
+    {
+        name: String,
+        span: Span
+    },
+
+    /// Indicates that a function was called with a different number of arguments than its
+    /// signature provides
+    ArgumentCount
+    {
+        name: String,
+        expected: usize,
+        found: usize,
+        span: Span
+    },
+
+    /// Indicates that a function argument provides a different type than its parameter, where
+    /// the index of the argument starts from zero
+    MismatchedArgument
+    {
+        name: String,
+        index: usize,
+        expected: VariableType,
+        found: VariableType,
+        span: Span
+    },
+
+    /// Indicates that a function call was read as a different kind of value than its signature
+    /// returns, where the expected kind is numeric, boolean or string
+    MismatchedResult
+    {
+        name: String,
+        expected: &'static str,
+        found: VariableType,
+        span: Span
+    }
+}
+
+impl TypeError
+{
+    /// Returns the source location associated with the error
+    pub fn span(&self) -> Span
+    {
+        return match self
+        {
+            TypeError::MismatchedAssignment { span, .. } => *span,
+            TypeError::MismatchedVariable { span, .. } => *span,
+            TypeError::UnknownVariable { span, .. } => *span,
+            TypeError::ArgumentCount { span, .. } => *span,
+            TypeError::MismatchedArgument { span, .. } => *span,
+            TypeError::MismatchedResult { span, .. } => *span
+        };
+    }
+}
+
+impl std::fmt::Display for TypeError
+{
+    /// Writes a human-readable description of the type error
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return match self
+        {
+            TypeError::MismatchedAssignment { name, expected, found, span } =>
+                write!(f, "cannot assign a {0:} value to {1:} variable {2:} at {3:}", found.type_name(), expected.type_name(), name, span),
+            TypeError::MismatchedVariable { name, expected, found, span } =>
+                write!(f, "{0:} variable {1:} used as a {2:} value at {3:}", found.type_name(), name, expected, span),
+            TypeError::UnknownVariable { name, span } => write!(f, "unknown variable {0:} at {1:}", name, span),
+            TypeError::ArgumentCount { name, expected, found, span } =>
+                write!(f, "function {0:} takes {1:} arguments but {2:} were provided at {3:}", name, expected, found, span),
+            TypeError::MismatchedArgument { name, index, expected, found, span } =>
+                write!(f, "argument {0:} of function {1:} expects a {2:} value, found {3:} at {4:}", index + 1, name, expected.type_name(), found.type_name(), span),
+            TypeError::MismatchedResult { name, expected, found, span } =>
+                write!(f, "function {0:} returns a {1:} value but is used as a {2:} value at {3:}", name, found.type_name(), expected, span)
+        };
+    }
+}
+
+impl std::error::Error for TypeError {}
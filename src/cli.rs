@@ -0,0 +1,641 @@
+use crate::repl;
+use zbasic::{Diagnostic, Error, Interpreter, Program, TokenList};
+use zbasic::lexer::statement::{Statement, StatementType};
+use zbasic::schema;
+
+use std::io::{Read, Write};
+
+/// Defines the usage text written for the help command or invalid arguments
+const USAGE: &str = "\
+usage: zbasic <command> [file]
+
+commands:
+  run [file]            run the program and print the final variables
+  tokens [--format text|json] [file]
+                        print the tokens of the program
+  ast [--format text|json|sexpr] [file]
+                        print the type checked syntax tree of the program
+  check [file]          lex, parse and type check the program without running it
+  fmt [--check] [file]  rewrite the file in the canonical style, or with --check
+                        only report whether it is already formatted
+  repl                  start an interactive session
+  help                  print this message
+
+The program is read from standard input if no file, or -, is given, with fmt
+writing the formatted program to standard output. Otherwise, input statements
+read from standard input. The json and sexpr formats follow the schema
+documented in the zbasic::schema module, including the span of each token and
+node and the type of each expression.
+
+exit codes:
+  0  success
+  1  runtime error
+  2  invalid arguments
+  3  input could not be read
+  4  lexer error
+  5  parse error
+  6  type error
+  7  program is not formatted
+";
+
+/// Defines the classes of failure, each reported with a separate exit code
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Failure
+{
+    /// Indicates that the program failed while running
+    Runtime,
+
+    /// Indicates that the command-line arguments were invalid
+    Usage,
+
+    /// Indicates that the input could not be read, or the output could not be written
+    Io,
+
+    /// Indicates that the program could not be tokenized
+    Lex,
+
+    /// Indicates that the program could not be parsed
+    Parse,
+
+    /// Indicates that the program failed type checking
+    Type,
+
+    /// Indicates that the program is not in the canonical style
+    Unformatted
+}
+
+impl Failure
+{
+    /// Returns the process exit code for the failure
+    pub fn code(&self) -> i32
+    {
+        return match self
+        {
+            Failure::Runtime => 1,
+            Failure::Usage => 2,
+            Failure::Io => 3,
+            Failure::Lex => 4,
+            Failure::Parse => 5,
+            Failure::Type => 6,
+            Failure::Unformatted => 7
+        };
+    }
+}
+
+impl From<std::io::Error> for Failure
+{
+    /// Treats any input or output error as an I/O failure
+    fn from(_: std::io::Error) -> Failure
+    {
+        return Failure::Io;
+    }
+}
+
+/// Defines the streams and settings used while running a command
+pub struct Context<'a>
+{
+    /// Defines the stream to read the program from if no file is given
+    pub input: &'a mut (dyn Read + Send),
+
+    /// Defines the stream for command results
+    pub out: &'a mut (dyn Write + Send),
+
+    /// Defines the stream for errors
+    pub err: &'a mut dyn Write,
+
+    /// Indicates that errors should be written with ANSI colors
+    pub color: bool
+}
+
+/// Runs the command given by the arguments, not including the program name, returning the
+/// process exit code
+pub fn run(args: &[String], ctx: &mut Context) -> i32
+{
+    return match dispatch(args, ctx)
+    {
+        Ok(()) => 0,
+        Err(f) => f.code()
+    };
+}
+
+/// Runs the command given by the arguments
+fn dispatch(args: &[String], ctx: &mut Context) -> Result<(), Failure>
+{
+    let command = match args.first()
+    {
+        Some(c) => c.as_str(),
+        None =>
+        {
+            write!(ctx.err, "{0:}", USAGE)?;
+            return Err(Failure::Usage);
+        }
+    };
+
+    // Handle the commands that do not read a program
+    match command
+    {
+        "help" | "--help" | "-h" =>
+        {
+            write!(ctx.out, "{0:}", USAGE)?;
+            return Ok(());
+        },
+        "repl" =>
+        {
+            return repl::run().map_err(|e|
+            {
+                let _ = writeln!(ctx.err, "error: {0:}", e);
+                Failure::Io
+            });
+        },
+        "run" | "tokens" | "ast" | "check" | "fmt" => (),
+        _ =>
+        {
+            writeln!(ctx.err, "error: unknown command `{0:}`", command)?;
+            write!(ctx.err, "{0:}", USAGE)?;
+            return Err(Failure::Usage);
+        }
+    }
+
+    // Separate the options of the command from the file
+    let mut check_only = false;
+    let mut format = "text";
+    let mut files: Vec<&String> = Vec::new();
+    let mut rest = args[1..].iter();
+
+    while let Some(arg) = rest.next()
+    {
+        match arg.as_str()
+        {
+            "--check" if command == "fmt" => check_only = true,
+            "--format" if command == "tokens" || command == "ast" => match rest.next()
+            {
+                Some(f) => format = f.as_str(),
+                None =>
+                {
+                    writeln!(ctx.err, "error: expected a format after --format")?;
+                    write!(ctx.err, "{0:}", USAGE)?;
+                    return Err(Failure::Usage);
+                }
+            },
+            _ => files.push(arg)
+        }
+    }
+
+    let formats: &[&str] = match command
+    {
+        "tokens" => &["text", "json"],
+        "ast" => &["text", "json", "sexpr"],
+        _ => &["text"]
+    };
+
+    if !formats.contains(&format)
+    {
+        writeln!(ctx.err, "error: unknown format `{0:}` for {1:}", format, command)?;
+        write!(ctx.err, "{0:}", USAGE)?;
+        return Err(Failure::Usage);
+    }
+
+    if files.len() > 1
+    {
+        writeln!(ctx.err, "error: expected at most one file")?;
+        write!(ctx.err, "{0:}", USAGE)?;
+        return Err(Failure::Usage);
+    }
+
+    // Read the program from the file or standard input
+    let path = files.first().map(|s| s.as_str()).filter(|s| *s != "-");
+
+    let (source, filename) = match path
+    {
+        None =>
+        {
+            let mut source = String::new();
+            if let Err(e) = ctx.input.read_to_string(&mut source)
+            {
+                writeln!(ctx.err, "error: cannot read standard input: {0:}", e)?;
+                return Err(Failure::Io);
+            }
+            (source, "<stdin>".to_string())
+        },
+        Some(path) => match std::fs::read_to_string(path)
+        {
+            Ok(s) => (s, path.to_string()),
+            Err(e) =>
+            {
+                writeln!(ctx.err, "error: cannot read {0:}: {1:}", path, e)?;
+                return Err(Failure::Io);
+            }
+        }
+    };
+
+    let source = Source
+    {
+        text: &source,
+        filename: &filename
+    };
+
+    return match command
+    {
+        "tokens" =>
+        {
+            let mut tokens = source.tokenize(ctx)?;
+
+            if format == "json"
+            {
+                writeln!(ctx.out, "{0:}", schema::tokens_to_json(&tokens).pretty())?;
+                return Ok(());
+            }
+
+            while let Some(token) = tokens.pop()
+            {
+                writeln!(ctx.out, "{0:}", token)?;
+            }
+
+            Ok(())
+        },
+        "ast" =>
+        {
+            let program = source.check(ctx)?;
+
+            if format == "text"
+            {
+                write_statements(ctx.out, &program.main, 0)?;
+                return Ok(());
+            }
+
+            let json = match schema::program_to_json(&program)
+            {
+                Ok(json) => json,
+                Err(e) =>
+                {
+                    writeln!(ctx.err, "error: cannot write the program as JSON: {0:}", e)?;
+                    return Err(Failure::Io);
+                }
+            };
+
+            match format
+            {
+                "json" => writeln!(ctx.out, "{0:}", json.pretty())?,
+                _ => writeln!(ctx.out, "{0:}", schema::to_sexpr(&json))?
+            }
+
+            Ok(())
+        },
+        "check" =>
+        {
+            source.check(ctx)?;
+            Ok(())
+        },
+        "fmt" =>
+        {
+            let formatted = source.format(ctx)?;
+
+            if check_only
+            {
+                if formatted != source.text
+                {
+                    writeln!(ctx.err, "{0:} is not formatted", source.filename)?;
+                    return Err(Failure::Unformatted);
+                }
+            }
+            else if let Some(path) = path
+            {
+                // Only rewrite the file if the formatting changed
+                if formatted != source.text
+                {
+                    if let Err(e) = std::fs::write(path, &formatted)
+                    {
+                        writeln!(ctx.err, "error: cannot write {0:}: {1:}", path, e)?;
+                        return Err(Failure::Io);
+                    }
+                }
+            }
+            else
+            {
+                write!(ctx.out, "{0:}", formatted)?;
+            }
+
+            Ok(())
+        },
+        _ =>
+        {
+            let program = source.check(ctx)?;
+
+            // Run the program with input statements reading from the input stream, and print
+            // statements writing to the output stream
+            let result =
+            {
+                let reader = std::io::BufReader::new(&mut *ctx.input);
+                let mut interpreter = Interpreter::with_io(Box::new(reader), Box::new(&mut *ctx.out));
+                interpreter.run(&program).cloned()
+            };
+
+            match result
+            {
+                Ok(variables) =>
+                {
+                    let mut names: Vec<&String> = variables.keys().collect();
+                    names.sort();
+
+                    for name in names
+                    {
+                        writeln!(ctx.out, "{0:} = {1:}", name, variables[name].vartype)?;
+                    }
+
+                    Ok(())
+                },
+                Err(e) => source.fail(Error::Runtime(e), ctx)
+            }
+        }
+    };
+}
+
+/// Writes each statement in the sequence on its own line at the indentation depth, starting
+/// from the first, with the statements of each nested block written below a label line at the
+/// next depth
+fn write_statements(out: &mut dyn Write, first: &Statement, depth: usize) -> std::io::Result<()>
+{
+    let indent = "    ".repeat(depth);
+    let mut current = Some(first);
+
+    while let Some(statement) = current
+    {
+        let blocks: Vec<(&str, &Statement)> = match &statement.data
+        {
+            StatementType::Empty => Vec::new(),
+            StatementType::If(i) =>
+            {
+                writeln!(out, "{0:}{1:} If {2:?}", indent, statement.span, i.boolexpr)?;
+                vec!{("then", &i.statement), ("else", &i.else_statement)}
+            },
+            StatementType::While(w) =>
+            {
+                writeln!(out, "{0:}{1:} While {2:?}", indent, statement.span, w.boolexpr)?;
+                vec!{("body", &w.statement)}
+            },
+            StatementType::For(f) =>
+            {
+                writeln!(out, "{0:}{1:} For {2:?}", indent, statement.span, f.boolexpr)?;
+                vec!{("init", &f.init), ("step", &f.step), ("body", &f.statement)}
+            },
+            data =>
+            {
+                writeln!(out, "{0:}{1:} {2:?}", indent, statement.span, data)?;
+                Vec::new()
+            }
+        };
+
+        for (label, block) in blocks
+        {
+            writeln!(out, "{0:}  {1:}:", indent, label)?;
+            write_statements(out, block, depth + 1)?;
+        }
+
+        current = statement.next.as_deref();
+    }
+
+    return Ok(());
+}
+
+/// Defines the program source text along with the name used to report errors
+struct Source<'a>
+{
+    text: &'a str,
+    filename: &'a str
+}
+
+impl Source<'_>
+{
+    /// Writes the diagnostic for the error to the error stream, returning the failure for the
+    /// stage that raised it
+    fn fail<T>(&self, err: Error, ctx: &mut Context) -> Result<T, Failure>
+    {
+        let failure = match err
+        {
+            Error::Lex(_) => Failure::Lex,
+            Error::Parse(_) => Failure::Parse,
+            Error::Type(_) => Failure::Type,
+            Error::Runtime(_) => Failure::Runtime
+        };
+
+        write!(ctx.err, "{0:}", Diagnostic::from(&err).render(self.text, self.filename, ctx.color))?;
+        return Err(failure);
+    }
+
+    /// Tokenizes the source
+    fn tokenize(&self, ctx: &mut Context) -> Result<TokenList, Failure>
+    {
+        return zbasic::tokenize(self.text).or_else(|e| self.fail(Error::Lex(e), ctx));
+    }
+
+    /// Tokenizes and parses the source, providing it in the canonical style
+    fn format(&self, ctx: &mut Context) -> Result<String, Failure>
+    {
+        return zbasic::format(self.text).or_else(|e| self.fail(e, ctx));
+    }
+
+    /// Tokenizes, parses and type checks the source
+    fn check(&self, ctx: &mut Context) -> Result<Program, Failure>
+    {
+        return zbasic::check(self.text).or_else(|e| self.fail(e, ctx));
+    }
+}
+
+/// Defines tests of the command-line driver
+#[cfg(test)]
+mod tests
+{
+    use super::{Context, Failure};
+
+    /// Runs the command with the provided standard input, returning the exit code along with
+    /// the output and error streams
+    fn run(args: &[&str], input: &str) -> (i32, String, String)
+    {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        let mut input = input.as_bytes();
+        let mut out: Vec<u8> = Vec::new();
+        let mut err: Vec<u8> = Vec::new();
+
+        let code = super::run(&args, &mut Context
+        {
+            input: &mut input,
+            out: &mut out,
+            err: &mut err,
+            color: false
+        });
+
+        return (code, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap());
+    }
+
+    /// Tests that each stage writes its results for a valid program
+    #[test]
+    fn commands()
+    {
+        let source = "b = 2; a = b * 1.5;";
+
+        assert_eq!(run(&["run"], source), (0, "a = 3.0\nb = 2\n".to_string(), String::new()));
+        assert_eq!(run(&["run", "-"], source).0, 0);
+        assert_eq!(run(&["check"], source), (0, String::new(), String::new()));
+        assert_eq!(
+            run(&["tokens"], "a = 1;"),
+            (0, "(variable a)\n(operator '=')\n(int 1)\n(operator ';')\n".to_string(), String::new()));
+
+        let (code, out, _) = run(&["ast"], source);
+        assert_eq!(code, 0);
+        assert!(out.starts_with("1:1 Assign(VarStatement { varname: \"b\""), "{0:}", out);
+    }
+
+    /// Tests that the text syntax tree is written with one line per statement, with nested blocks
+    /// indented below a label line, so that the size of the output grows with the program
+    #[test]
+    fn ast_text()
+    {
+        assert_eq!(
+            run(&["ast"], "if (true) { print; } else { print; }").1,
+            "1:1 If BoolExpression { inverted: false, value: Constant(true), span: Span { start: 4, end: 8, line: 1, column: 5 } }\n  \
+               then:\n    \
+                 1:13 Print(PrintStatement { exprs: [] })\n  \
+               else:\n    \
+                 1:29 Print(PrintStatement { exprs: [] })\n");
+
+        let (code, out, _) = run(&["ast"], &"print;\n".repeat(10_000));
+        assert_eq!(code, 0);
+        assert_eq!(out.lines().count(), 10_000);
+        assert_eq!(out.lines().last(), Some("10000:1 Print(PrintStatement { exprs: [] })"));
+    }
+
+    /// Tests that each class of failure provides a separate exit code with a diagnostic
+    #[test]
+    fn failures()
+    {
+        let check = |args: &[&str], input: &str, failure: Failure, message: &str|
+        {
+            let (code, _, err) = run(args, input);
+            assert_eq!(code, failure.code(), "{0:?}", args);
+            assert!(err.contains(message), "{0:}", err);
+        };
+
+        check(&["run"], "a = 1 / 0;", Failure::Runtime, "error[E0301]");
+        check(&[], "", Failure::Usage, "usage:");
+        check(&["compile"], "", Failure::Usage, "unknown command `compile`");
+        check(&["run", "a.zb", "b.zb"], "", Failure::Usage, "at most one file");
+        check(&["run", "/nonexistent/file.zb"], "", Failure::Io, "cannot read /nonexistent/file.zb");
+        check(&["tokens"], "a = $;", Failure::Lex, "error[E0003]");
+        check(&["ast"], "a = (1;", Failure::Parse, " --> <stdin>:1:7");
+        check(&["check"], "a = 1; a = true;", Failure::Type, "error[E0202]");
+
+        assert_eq!(run(&["help"], "").0, 0);
+    }
+
+    /// Tests that variables read as a different type of value are reported as type errors naming
+    /// the variable and both types
+    #[test]
+    fn type_mismatches()
+    {
+        let inputs = [
+            ("a = true; b = a + 1;", "boolean variable `a` used as a numeric value"),
+            ("a = 1; if (a) { }", "integer variable `a` used as a boolean value"),
+            ("a = 2.5; b = true; while (b && a) { }", "float variable `a` used as a boolean value"),
+            ("b = true; if (b < 1) { }", "boolean variable `b` used as a numeric value"),
+            ("s = \"a\"; n = s * 2;", "string variable `s` used as a numeric value"),
+            ("s = \"a\"; n = 1; print s + n;", "integer variable `n` used as a string value")
+        ];
+
+        for (input, message) in inputs.iter()
+        {
+            for command in ["check", "run", "ast"].iter()
+            {
+                let (code, out, err) = run(&[command], input);
+                assert_eq!(code, Failure::Type.code(), "{0:} {1:}", command, input);
+                assert!(out.is_empty(), "{0:}", out);
+                assert!(err.contains(message), "{0:}", err);
+            }
+        }
+    }
+
+    /// Tests that programs are read from the file given on the command line
+    #[test]
+    fn file_input()
+    {
+        let path = std::env::temp_dir().join(format!("zbasic_cli_{0:}.zb", std::process::id()));
+        std::fs::write(&path, "x = 4;\nx = x / 0;\n").unwrap();
+
+        let (code, _, err) = run(&["run", path.to_str().unwrap()], "");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(code, Failure::Runtime.code());
+        assert!(err.contains(&format!(" --> {0:}:2:5", path.display())), "{0:}", err);
+    }
+
+    /// Tests that programs read from a file print to the output and read input from stdin
+    #[test]
+    fn console_io()
+    {
+        let path = std::env::temp_dir().join(format!("zbasic_cli_io_{0:}.zb", std::process::id()));
+        std::fs::write(&path, "n = 0;\ninput n;\nprint n, n * n;\n").unwrap();
+
+        let (code, out, err) = run(&["run", path.to_str().unwrap()], "7\n");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(code, 0, "{0:}", err);
+        assert_eq!(out, "7 49\nn = 7\n");
+    }
+
+    /// Tests that the format command writes the formatted program, rewrites files, and reports
+    /// unformatted programs when checking
+    #[test]
+    fn format()
+    {
+        assert_eq!(run(&["fmt"], "a=1;// one\n"), (0, "a = 1; // one\n".to_string(), String::new()));
+        assert_eq!(run(&["fmt", "--check"], "a = 1;\n"), (0, String::new(), String::new()));
+        assert_eq!(run(&["fmt", "--check", "-"], "a=1;"), (Failure::Unformatted.code(), String::new(), "<stdin> is not formatted\n".to_string()));
+        assert_eq!(run(&["fmt", "--check"], "a = (1;").0, Failure::Parse.code());
+
+        let path = std::env::temp_dir().join(format!("zbasic_cli_fmt_{0:}.zb", std::process::id()));
+        std::fs::write(&path, "if(true){a=1;}").unwrap();
+
+        let check = run(&["fmt", "--check", path.to_str().unwrap()], "");
+        let rewrite = run(&["fmt", path.to_str().unwrap()], "");
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(check.0, Failure::Unformatted.code());
+        assert_eq!(rewrite, (0, String::new(), String::new()));
+        assert_eq!(text, "if (true) {\n    a = 1;\n}\n");
+    }
+
+    /// Tests that tokens and syntax trees are written in the requested format, with the JSON
+    /// tree loading back into the parsed program
+    #[test]
+    fn formats()
+    {
+        let source = "a = 1; print a + 2.5;";
+
+        let (code, out, err) = run(&["tokens", "--format", "json"], "a = 1;");
+        assert_eq!(code, 0, "{0:}", err);
+        assert!(out.starts_with("{\n  \"kind\": \"tokens\",\n  \"version\": 1,\n"), "{0:}", out);
+        assert!(out.contains("\"value\": \"=\",\n      \"span\": {\"start\": 2, \"end\": 3, \"line\": 1, \"column\": 3}\n"), "{0:}", out);
+
+        let (code, out, err) = run(&["ast", "--format", "json", "-"], source);
+        assert_eq!(code, 0, "{0:}", err);
+        let json = zbasic::Json::parse(&out).unwrap();
+        assert_eq!(zbasic::schema::program_from_json(&json).unwrap(), zbasic::check(source).unwrap());
+
+        let (code, out, err) = run(&["ast", "--format", "sexpr"], source);
+        assert_eq!(code, 0, "{0:}", err);
+        assert!(out.starts_with("(program\n  :version 1\n  :variables ((:name \"a\" :type \"integer\"))\n"), "{0:}", out);
+
+        let (code, out, err) = run(&["ast", "--format", "sexpr"], "a = 1; a = 2.5; b = a * 2;");
+        assert_eq!(code, 0, "{0:}", err);
+        assert!(out.contains(":variables ((:name \"a\" :type \"float\") (:name \"b\" :type \"float\"))"), "{0:}", out);
+
+        assert_eq!(run(&["ast", "--format", "text"], source).1, run(&["ast"], source).1);
+
+        let (code, out, err) = run(&["ast", "--format", "json"], &format!("a = 1{0:};", " + 1".repeat(600)));
+        assert_eq!((code, out), (Failure::Io.code(), String::new()));
+        assert!(err.starts_with("error: cannot write the program as JSON: expected at most 512 nested arrays and objects at $.statements[0].value.a"), "{0:}", err);
+        assert_eq!(run(&["tokens", "--format", "sexpr"], source).0, Failure::Usage.code());
+        assert_eq!(run(&["ast", "--format", "xml"], source).2.lines().next(), Some("error: unknown format `xml` for ast"));
+        assert_eq!(run(&["ast", "--format"], source).2.lines().next(), Some("error: expected a format after --format"));
+    }
+}
@@ -0,0 +1,397 @@
+use crate::checker::error::TypeError;
+use crate::error::Error;
+use crate::interpreter::error::RuntimeError;
+use crate::lexer::MAX_NESTING;
+use crate::lexer::error::ParseError;
+use crate::span::Span;
+use crate::token::error::LexError;
+
+use std::io::IsTerminal;
+
+/// Defines an error report that can be rendered alongside the source text it refers to
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic
+{
+    /// Defines the unique error code, such as E0001
+    pub code: &'static str,
+
+    /// Defines the main error message
+    pub message: String,
+
+    /// Defines the source location of the error
+    pub span: Span,
+
+    /// Defines an optional label written beside the underlined source
+    pub label: Option<String>,
+
+    /// Defines additional notes describing the error
+    pub notes: Vec<String>,
+
+    /// Defines suggestions that may resolve the error
+    pub help: Vec<String>
+}
+
+/// Defines the ANSI escape codes used for colored output
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+impl Diagnostic
+{
+    /// Returns a new diagnostic with the provided code, message and location
+    pub fn new(code: &'static str, message: &str, span: Span) -> Diagnostic
+    {
+        return Diagnostic
+        {
+            code,
+            message: message.to_string(),
+            span,
+            label: None,
+            notes: Vec::new(),
+            help: Vec::new()
+        };
+    }
+
+    /// Adds a label to write beside the underlined source
+    pub fn with_label(mut self, label: &str) -> Diagnostic
+    {
+        self.label = Some(label.to_string());
+        return self;
+    }
+
+    /// Adds a note line to the diagnostic
+    pub fn with_note(mut self, note: &str) -> Diagnostic
+    {
+        self.notes.push(note.to_string());
+        return self;
+    }
+
+    /// Adds a help line to the diagnostic
+    pub fn with_help(mut self, help: &str) -> Diagnostic
+    {
+        self.help.push(help.to_string());
+        return self;
+    }
+
+    /// Renders the diagnostic with the offending source line and an underline beneath the
+    /// error location. ANSI colors are included if requested
+    pub fn render(&self, source: &str, filename: &str, color: bool) -> String
+    {
+        // Define a helper to optionally wrap text in a color
+        let paint = |style: &str, text: &str| -> String
+        {
+            return if color
+            {
+                format!("{0:}{1:}{2:}", style, text, RESET)
+            }
+            else
+            {
+                text.to_string()
+            };
+        };
+
+        // Find the source line containing the start of the span
+        let line_start = source[..std::cmp::min(self.span.start, source.len())].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = source[line_start..].find('\n').map(|i| line_start + i).unwrap_or(source.len());
+        let line_text = source[line_start..line_end].trim_end_matches('\r');
+
+        // Determine the underline location, limiting the underline to the first line of the span
+        let line_number = std::cmp::max(self.span.line, 1).to_string();
+        let gutter = " ".repeat(line_number.len());
+        let offset = source[line_start..std::cmp::max(self.span.start, line_start).min(line_end)].chars().count();
+        let width = source[std::cmp::min(self.span.start, line_end)..std::cmp::min(self.span.end, line_end)].chars().count();
+        let underline = "^".repeat(std::cmp::max(width, 1));
+
+        // Write the header and location
+        let mut output = String::new();
+
+        output.push_str(&format!(
+            "{0:}{1:}\n",
+            paint(RED, &format!("error[{0:}]", self.code)),
+            paint(BOLD, &format!(": {0:}", self.message))));
+        output.push_str(&format!(
+            "{0:}{1:} {2:}:{3:}:{4:}\n",
+            gutter,
+            paint(BLUE, "-->"),
+            filename,
+            self.span.line,
+            self.span.column));
+
+        // Write the source line and underline
+        let bar = paint(BLUE, "|");
+        output.push_str(&format!("{0:} {1:}\n", gutter, bar));
+        output.push_str(&format!("{0:} {1:} {2:}\n", paint(BLUE, &line_number), bar, line_text));
+
+        let label = match &self.label
+        {
+            Some(l) => format!(" {0:}", l),
+            None => String::new()
+        };
+
+        output.push_str(&format!(
+            "{0:} {1:} {2:}{3:}\n",
+            gutter,
+            bar,
+            " ".repeat(offset),
+            paint(RED, &format!("{0:}{1:}", underline, label))));
+
+        // Write any notes and help lines
+        if !self.notes.is_empty() || !self.help.is_empty()
+        {
+            output.push_str(&format!("{0:} {1:}\n", gutter, bar));
+        }
+
+        for note in self.notes.iter()
+        {
+            output.push_str(&format!("{0:} {1:} {2:} {3:}\n", gutter, paint(BLUE, "="), paint(BOLD, "note:"), note));
+        }
+
+        for help in self.help.iter()
+        {
+            output.push_str(&format!("{0:} {1:} {2:} {3:}\n", gutter, paint(BLUE, "="), paint(BOLD, "help:"), help));
+        }
+
+        return output;
+    }
+}
+
+/// Returns true if colored output should be used for the standard error stream, being when the
+/// stream is a terminal and the NO_COLOR environment variable is not set
+pub fn use_color() -> bool
+{
+    return std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+}
+
+impl From<&LexError> for Diagnostic
+{
+    /// Creates a diagnostic describing the lexer error
+    fn from(err: &LexError) -> Diagnostic
+    {
+        return match err
+        {
+            LexError::InvalidNumber { text, span } =>
+                Diagnostic::new("E0001", &format!("invalid number `{0:}`", text), *span)
+                    .with_label("not a valid integer or float")
+                    .with_help("numbers are written as digits with an optional decimal point, such as 12 or 3.5"),
+            LexError::InvalidIdentifier { text, span } =>
+                Diagnostic::new("E0002", &format!("invalid identifier `{0:}`", text), *span)
+                    .with_label("not a valid variable name")
+                    .with_help("variable names must start with a letter and contain only letters and digits"),
+            LexError::UnknownCharacter { character, span } =>
+                Diagnostic::new("E0003", &format!("unknown character `{0:}`", character), *span)
+                    .with_label("not part of any token"),
+            LexError::IntegerOverflow { text, span } =>
+                Diagnostic::new("E0004", &format!("integer literal `{0:}` is out of range", text), *span)
+                    .with_label("does not fit in an integer")
+                    .with_note(&format!("integers must be between {0:} and {1:}", i32::MIN, i32::MAX))
+                    .with_help("use a float literal, such as 1.0, for larger values"),
+            LexError::FloatOverflow { text, span } =>
+                Diagnostic::new("E0009", &format!("float literal `{0:}` is out of range", text), *span)
+                    .with_label("does not fit in a float")
+                    .with_note(&format!("floats must be between {0:e} and {1:e}", f32::MIN, f32::MAX)),
+            LexError::AmbiguousOperator { text, span } =>
+                Diagnostic::new("E0005", &format!("ambiguous operator, could be any of {0:}", text), *span)
+                    .with_label("ambiguous operator"),
+            LexError::UnterminatedString { span } =>
+                Diagnostic::new("E0006", "unterminated string literal", *span)
+                    .with_label("string starts here")
+                    .with_help("add a closing `\"`"),
+            LexError::InvalidEscape { text, span } =>
+                Diagnostic::new("E0007", &format!("invalid escape sequence `{0:}`", text), *span)
+                    .with_label("unknown escape")
+                    .with_note("valid escapes are \\n, \\t, \\\", \\\\ and \\u{...}"),
+            LexError::UnterminatedComment { span } =>
+                Diagnostic::new("E0008", "unterminated block comment", *span)
+                    .with_label("comment starts here")
+                    .with_note("block comments may be nested, and each `/*` needs a matching `*/`")
+        };
+    }
+}
+
+impl From<&ParseError> for Diagnostic
+{
+    /// Creates a diagnostic describing the parse error
+    fn from(err: &ParseError) -> Diagnostic
+    {
+        return match err
+        {
+            ParseError::UnexpectedToken { expected, found, span } =>
+                Diagnostic::new("E0101", &format!("expected {0:}, found {1:}", expected, found), *span)
+                    .with_label(&format!("expected {0:}", expected)),
+            ParseError::UnexpectedEnd { expected, span } =>
+                Diagnostic::new("E0102", &format!("expected {0:}, found end of input", expected), *span)
+                    .with_label(&format!("expected {0:}", expected)),
+            ParseError::UnassignedVariable { name, span } =>
+                Diagnostic::new("E0201", &format!("use of unassigned variable `{0:}`", name), *span)
+                    .with_label("used before any assignment")
+                    .with_help(&format!("assign a value to `{0:}` before it is read", name)),
+            ParseError::UnknownFunction { name, span } =>
+                Diagnostic::new("E0205", &format!("unknown function `{0:}`", name), *span)
+                    .with_label("not a registered function")
+                    .with_note("functions are provided by the host program before the source is parsed"),
+            ParseError::IntegerOverflow { text, span } =>
+                Diagnostic::new("E0103", &format!("integer literal `{0:}` is out of range", text), *span)
+                    .with_label("does not fit in an integer")
+                    .with_note(&format!("integers must be between {0:} and {1:}", i32::MIN, i32::MAX))
+                    .with_help("use a float literal, such as 1.0, for larger values"),
+            ParseError::NestingLimit { span } =>
+                Diagnostic::new("E0104", "too many nested values or blocks", *span)
+                    .with_label("nested too deeply")
+                    .with_note(&format!("values and blocks may be nested at most {0:} levels deep", MAX_NESTING))
+                    .with_help("assign inner values to variables in separate statements")
+        };
+    }
+}
+
+impl From<&TypeError> for Diagnostic
+{
+    /// Creates a diagnostic describing the type error
+    fn from(err: &TypeError) -> Diagnostic
+    {
+        return match err
+        {
+            TypeError::MismatchedAssignment { name, expected, found, span } =>
+                Diagnostic::new("E0202", &format!("mismatched types assigned to `{0:}`", name), *span)
+                    .with_label(&format!("expected {0:} value, found {1:} value", expected.type_name(), found.type_name()))
+                    .with_note("a variable holds boolean, numeric or string values for the whole program"),
+            TypeError::MismatchedVariable { name, expected, found, span } =>
+                Diagnostic::new("E0203", &format!("{0:} variable `{1:}` used as a {2:} value", found.type_name(), name, expected), *span)
+                    .with_label(&format!("expected {0:} value", expected)),
+            TypeError::UnknownVariable { name, span } =>
+                Diagnostic::new("E0204", &format!("unknown variable `{0:}`", name), *span)
+                    .with_label("not assigned before being read"),
+            TypeError::ArgumentCount { name, expected, found, span } =>
+                Diagnostic::new("E0206", &format!("function `{0:}` takes {1:} arguments but {2:} were provided", name, expected, found), *span)
+                    .with_label(&format!("expected {0:} arguments", expected)),
+            TypeError::MismatchedArgument { name, index, expected, found, span } =>
+                Diagnostic::new("E0207", &format!("mismatched type for argument {0:} of `{1:}`", index + 1, name), *span)
+                    .with_label(&format!("expected {0:} value, found {1:} value", expected.type_name(), found.type_name())),
+            TypeError::MismatchedResult { name, expected, found, span } =>
+                Diagnostic::new("E0208", &format!("function `{0:}` returns a {1:} value but is used as a {2:} value", name, found.type_name(), expected), *span)
+                    .with_label(&format!("expected {0:} value", expected))
+        };
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic
+{
+    /// Creates a diagnostic describing the runtime error
+    fn from(err: &RuntimeError) -> Diagnostic
+    {
+        return match err
+        {
+            RuntimeError::DivisionByZero { span } =>
+                Diagnostic::new("E0301", "division by zero", *span)
+                    .with_label("divisor evaluated to zero"),
+            RuntimeError::IntegerOverflow { span } =>
+                Diagnostic::new("E0302", "integer overflow", *span)
+                    .with_label("result does not fit in an integer")
+                    .with_help("use float values for larger results"),
+            RuntimeError::UndefinedVariable { name, span } =>
+                Diagnostic::new("E0303", &format!("undefined variable `{0:}`", name), *span)
+                    .with_label("read before being assigned"),
+            RuntimeError::UndefinedFunction { name, span } =>
+                Diagnostic::new("E0304", &format!("undefined function `{0:}`", name), *span)
+                    .with_label("not registered with the signature used to parse the program"),
+            RuntimeError::InvalidInput { text, expected, span } =>
+                Diagnostic::new("E0305", &format!("invalid input `{0:}`", text), *span)
+                    .with_label(&format!("expected {0:} value", expected.type_name())),
+            RuntimeError::Io { message, span } =>
+                Diagnostic::new("E0306", message, *span)
+                    .with_label("while running this statement"),
+            RuntimeError::TypeMismatch { expected, found, span } =>
+                Diagnostic::new("E0307", &format!("{0:} value used as a {1:} value", found.type_name(), expected), *span)
+                    .with_label(&format!("expected {0:} value", expected))
+                    .with_help("type check the program before running it")
+        };
+    }
+}
+
+impl From<&Error> for Diagnostic
+{
+    /// Creates a diagnostic describing the underlying error
+    fn from(err: &Error) -> Diagnostic
+    {
+        return match err
+        {
+            Error::Lex(e) => Diagnostic::from(e),
+            Error::Parse(e) => Diagnostic::from(e),
+            Error::Type(e) => Diagnostic::from(e),
+            Error::Runtime(e) => Diagnostic::from(e)
+        };
+    }
+}
+
+/// Defines tests of the diagnostic renderer
+#[cfg(test)]
+mod tests
+{
+    use super::Diagnostic;
+    use crate::lexer::program::Program;
+    use crate::span::Span;
+    use crate::token::Token;
+
+    /// Tests that lexer errors are rendered with the source line and an underline
+    #[test]
+    fn render_lex_error()
+    {
+        let source = "a = 1;\nb = 234a34;\n";
+        let err = Token::tokenize(source).err().unwrap();
+        let rendered = Diagnostic::from(&err).render(source, "test.zb", false);
+
+        assert_eq!(
+            rendered,
+            "error[E0001]: invalid number `234a34`\n \
+              --> test.zb:2:5\n  \
+               |\n\
+             2 | b = 234a34;\n  \
+               |     ^^^^^^ not a valid integer or float\n  \
+               |\n  \
+               = help: numbers are written as digits with an optional decimal point, such as 12 or 3.5\n");
+    }
+
+    /// Tests that the range of floats is written in exponent form in the note of an overflow
+    #[test]
+    fn render_float_overflow()
+    {
+        let source = format!("a = {0:}.0;", "9".repeat(40));
+        let err = Token::tokenize(&source).err().unwrap();
+        let rendered = Diagnostic::from(&err).render(&source, "test.zb", false);
+
+        assert!(rendered.starts_with("error[E0009]: float literal `"), "{0:}", rendered);
+        assert!(rendered.ends_with("= note: floats must be between -3.4028235e38 and 3.4028235e38\n"), "{0:}", rendered);
+    }
+
+    /// Tests that parse errors at the end of the input point just past the last token
+    #[test]
+    fn render_parse_error()
+    {
+        let source = "a = (1 + 2";
+        let err = Program::parse(&mut Token::tokenize(source).unwrap()).err().unwrap();
+        let rendered = Diagnostic::from(&err).render(source, "<stdin>", false);
+
+        assert_eq!(
+            rendered,
+            "error[E0102]: expected ')', found end of input\n \
+              --> <stdin>:1:11\n  \
+               |\n\
+             1 | a = (1 + 2\n  \
+               |           ^ expected ')'\n");
+    }
+
+    /// Tests that colored output wraps the header in ANSI escape codes
+    #[test]
+    fn render_color()
+    {
+        let diagnostic = Diagnostic::new("E9999", "test", Span::new(0, 1, 1, 1)).with_note("a note");
+
+        let colored = diagnostic.render("x", "f", true);
+        assert!(colored.starts_with("\x1b[1;31merror[E9999]\x1b[0m"));
+        assert!(colored.contains("note:"));
+
+        let plain = diagnostic.render("x", "f", false);
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.ends_with("  = note: a note\n"));
+    }
+}
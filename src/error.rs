@@ -0,0 +1,104 @@
+use crate::checker::error::TypeError;
+use crate::interpreter::error::RuntimeError;
+use crate::lexer::error::ParseError;
+use crate::span::Span;
+use crate::token::error::LexError;
+
+/// Defines an error raised by any stage of processing a program, from splitting the source
+/// into tokens through to running it
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error
+{
+    /// Indicates that the source could not be split into tokens
+    Lex(LexError),
+
+    /// Indicates that the tokens do not match the language grammar
+    Parse(ParseError),
+
+    /// Indicates that the program failed type checking
+    Type(TypeError),
+
+    /// Indicates that the program failed while running
+    Runtime(RuntimeError)
+}
+
+impl Error
+{
+    /// Returns the source location associated with the error
+    pub fn span(&self) -> Span
+    {
+        return match self
+        {
+            Error::Lex(e) => e.span(),
+            Error::Parse(e) => e.span(),
+            Error::Type(e) => e.span(),
+            Error::Runtime(e) => e.span()
+        };
+    }
+}
+
+impl From<LexError> for Error
+{
+    /// Wraps the lexer error
+    fn from(err: LexError) -> Error
+    {
+        return Error::Lex(err);
+    }
+}
+
+impl From<ParseError> for Error
+{
+    /// Wraps the parse error
+    fn from(err: ParseError) -> Error
+    {
+        return Error::Parse(err);
+    }
+}
+
+impl From<TypeError> for Error
+{
+    /// Wraps the type error
+    fn from(err: TypeError) -> Error
+    {
+        return Error::Type(err);
+    }
+}
+
+impl From<RuntimeError> for Error
+{
+    /// Wraps the runtime error
+    fn from(err: RuntimeError) -> Error
+    {
+        return Error::Runtime(err);
+    }
+}
+
+impl std::fmt::Display for Error
+{
+    /// Writes the description of the underlying error
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return match self
+        {
+            Error::Lex(e) => e.fmt(f),
+            Error::Parse(e) => e.fmt(f),
+            Error::Type(e) => e.fmt(f),
+            Error::Runtime(e) => e.fmt(f)
+        };
+    }
+}
+
+impl std::error::Error for Error
+{
+    /// Returns the underlying error
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        return match self
+        {
+            Error::Lex(e) => Some(e),
+            Error::Parse(e) => Some(e),
+            Error::Type(e) => Some(e),
+            Error::Runtime(e) => Some(e)
+        };
+    }
+}
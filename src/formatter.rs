@@ -0,0 +1,709 @@
+use crate::lexer::boolean::{BoolExpression, BoolExpressionType};
+use crate::lexer::call::CallExpression;
+use crate::lexer::expression::Expression;
+use crate::lexer::numeric::{NumericExpression, NumericExpressionType};
+use crate::lexer::operator::OperatorRole;
+use crate::lexer::program::Program;
+use crate::lexer::statement::{Statement, StatementType, VarStatement};
+use crate::lexer::string::{StringExpression, StringExpressionType};
+use crate::token::{Token, TokenList};
+use crate::token::symbol::Operator;
+use crate::token::trivia::Comment;
+
+use std::collections::VecDeque;
+
+/// Defines the text written for each level of indentation
+const INDENT: &str = "    ";
+
+/// Provides the canonical source text of the program. Blocks open on the line of their statement
+/// and close on a line of their own, with each nested level indented by four spaces, a single
+/// space around each binary operator, and only the parentheses required by the grouping.
+///
+/// The tokens should be those the program was parsed from, split with Token::tokenize_with_trivia
+/// from the source text, so that each comment may be kept. Comments on their own lines are kept
+/// on their own lines before the statement they preceded, and comments after a statement are kept
+/// at the end of its line. Comments within a statement are moved to the nearest of the two. A
+/// single blank line is kept wherever the source has blank lines between statements or comments
+pub fn format(program: &Program, tokens: &TokenList, source: &str) -> String
+{
+    let mut printer = Printer::new(tokens, source);
+    printer.statements(&program.main);
+    return printer.finish();
+}
+
+/// Defines the state of the formatter while writing the statements of a program in order
+struct Printer<'a>
+{
+    /// Defines the tokens of the program, used to locate braces and comments
+    tokens: &'a TokenList,
+
+    /// Defines the source text of the program, used to locate blank lines
+    source: &'a str,
+
+    /// Defines the comments on their own lines not yet written, each with the starting location
+    /// of the token they precede
+    leading: VecDeque<(usize, &'a Comment)>,
+
+    /// Defines the comments at the end of a line not yet written, each with the starting
+    /// location of the token they follow
+    trailing: VecDeque<(usize, &'a Comment)>,
+
+    /// Defines the formatted text written so far
+    output: String,
+
+    /// Defines the number of blocks enclosing the current line
+    depth: usize,
+
+    /// Defines the source location of the end of the last statement or comment written
+    last_end: usize,
+
+    /// Indicates that nothing has been written since the start of the program or a block, where
+    /// blank lines are not kept
+    block_start: bool
+}
+
+impl<'a> Printer<'a>
+{
+    /// Returns a printer for the tokens, collecting their comments in source order
+    fn new(tokens: &'a TokenList, source: &'a str) -> Printer<'a>
+    {
+        let mut leading = VecDeque::new();
+        let mut trailing = VecDeque::new();
+
+        for (trivia, span) in tokens.trivia.iter().zip(tokens.spans.iter())
+        {
+            leading.extend(trivia.leading.iter().map(|c| (span.start, c)));
+            trailing.extend(trivia.trailing.iter().map(|c| (span.start, c)));
+        }
+
+        return Printer
+        {
+            tokens,
+            source,
+            leading,
+            trailing,
+            output: String::new(),
+            depth: 0,
+            last_end: 0,
+            block_start: true
+        };
+    }
+
+    /// Writes the remaining comments, returning the formatted text
+    fn finish(mut self) -> String
+    {
+        self.comments_before(usize::MAX);
+
+        let remaining: Vec<&Comment> = self.trailing
+            .drain(..)
+            .map(|(_, c)| c)
+            .chain(self.tokens.end_trivia.iter())
+            .collect();
+
+        for comment in remaining
+        {
+            self.comment(comment);
+        }
+
+        return self.output;
+    }
+
+    /// Writes each statement in the sequence, starting from the first
+    fn statements(&mut self, first: &Statement)
+    {
+        let mut current = Some(first);
+
+        while let Some(statement) = current
+        {
+            self.statement(statement);
+            current = statement.next.as_deref();
+        }
+    }
+
+    /// Writes a single statement, along with any nested blocks
+    fn statement(&mut self, statement: &Statement)
+    {
+        let start = statement.span.start;
+
+        match &statement.data
+        {
+            StatementType::Empty => return,
+            StatementType::Assign(v) => self.line(&format!("{0:};", assignment(v)), start, statement.span.end),
+            StatementType::Print(p) if p.exprs.is_empty() => self.line("print;", start, statement.span.end),
+            StatementType::Print(p) =>
+            {
+                let exprs: Vec<String> = p.exprs.iter().map(expression).collect();
+                self.line(&format!("print {0:};", exprs.join(", ")), start, statement.span.end);
+            },
+            StatementType::Input(i) => self.line(&format!("input {0:};", i.varname), start, statement.span.end),
+            StatementType::Expr(e) => self.line(&format!("{0:};", expression(e)), start, statement.span.end),
+            StatementType::If(i) =>
+            {
+                let open = self.find_brace(start);
+                self.line(&format!("if ({0:}) {{", boolean(&i.boolexpr)), start, self.tokens.spans[open].end);
+                let mut close = self.matching_brace(open);
+
+                // Write the else block only if it contains statements, which parses to the
+                // same program as an empty block
+                if i.else_statement.data != StatementType::Empty
+                {
+                    let open = self.find_brace(self.tokens.spans[close].end);
+
+                    // Join the closing brace to the else keyword, writing the comments between
+                    // them within the block, unless a comment follows the brace on its line
+                    if self.tokens.trivia[close].trailing.is_empty()
+                    {
+                        self.block(&i.statement, self.tokens.spans[open].end);
+                        self.close("} else {", self.tokens.spans[open].end);
+                    }
+                    else
+                    {
+                        self.block(&i.statement, self.tokens.spans[close].end);
+                        self.close("}", self.tokens.spans[close].end);
+                        self.close("else {", self.tokens.spans[open].end);
+                    }
+
+                    close = self.matching_brace(open);
+                    self.block(&i.else_statement, self.tokens.spans[close].end);
+                }
+                else
+                {
+                    self.block(&i.statement, self.tokens.spans[close].end);
+                }
+
+                self.close("}", self.tokens.spans[close].end);
+            },
+            StatementType::While(w) =>
+            {
+                let open = self.find_brace(start);
+                self.line(&format!("while ({0:}) {{", boolean(&w.boolexpr)), start, self.tokens.spans[open].end);
+                let close = self.matching_brace(open);
+                self.block(&w.statement, self.tokens.spans[close].end);
+                self.close("}", self.tokens.spans[close].end);
+            },
+            StatementType::For(f) =>
+            {
+                let open = self.find_brace(start);
+                let init = header_assignment(&f.init);
+                let step = header_assignment(&f.step);
+
+                let header = if step.is_empty()
+                {
+                    format!("for ({0:}; {1:};) {{", init, boolean(&f.boolexpr))
+                }
+                else
+                {
+                    format!("for ({0:}; {1:}; {2:}) {{", init, boolean(&f.boolexpr), step)
+                };
+
+                self.line(&header, start, self.tokens.spans[open].end);
+                let close = self.matching_brace(open);
+                self.block(&f.statement, self.tokens.spans[close].end);
+                self.close("}", self.tokens.spans[close].end);
+            }
+        }
+
+        self.last_end = std::cmp::max(self.last_end, statement.span.end);
+    }
+
+    /// Writes the statements of a block, along with the comments on their own lines before a
+    /// token starting before the end location, at the indentation of the block
+    fn block(&mut self, statement: &Statement, end: usize)
+    {
+        self.depth += 1;
+        self.block_start = true;
+        self.statements(statement);
+        self.comments_before(end);
+        self.depth -= 1;
+    }
+
+    /// Writes a line of text starting a statement, covering the source from the start location
+    /// to the end location. Comments on their own lines before any token of the line are
+    /// written first, and comments after any token of the line are written at the end
+    fn line(&mut self, text: &str, start: usize, end: usize)
+    {
+        self.comments_before(end);
+        self.blank_line(start);
+        self.write_line(text, end);
+    }
+
+    /// Writes a line of text continuing the current statement after a block, such as a closing
+    /// brace, covering the source up to the end location. No blank line is kept before the line
+    fn close(&mut self, text: &str, end: usize)
+    {
+        self.comments_before(end);
+        self.write_line(text, end);
+    }
+
+    /// Writes the line of text at the current indentation, followed by the comments after any
+    /// token before the end location
+    fn write_line(&mut self, text: &str, end: usize)
+    {
+        self.output.push_str(&INDENT.repeat(self.depth));
+        self.output.push_str(text);
+        self.last_end = std::cmp::max(self.last_end, end);
+
+        while let Some(&(anchor, comment)) = self.trailing.front()
+        {
+            if anchor >= end
+            {
+                break;
+            }
+
+            self.output.push(' ');
+            self.output.push_str(&comment.text);
+            self.last_end = std::cmp::max(self.last_end, comment.span.end);
+            self.trailing.pop_front();
+        }
+
+        self.output.push('\n');
+        self.block_start = false;
+    }
+
+    /// Writes each comment on its own line before a token starting before the end location
+    fn comments_before(&mut self, end: usize)
+    {
+        while let Some(&(anchor, comment)) = self.leading.front()
+        {
+            if anchor >= end
+            {
+                break;
+            }
+
+            self.comment(comment);
+            self.leading.pop_front();
+        }
+    }
+
+    /// Writes the comment on its own line
+    fn comment(&mut self, comment: &Comment)
+    {
+        self.blank_line(comment.span.start);
+
+        self.output.push_str(&INDENT.repeat(self.depth));
+        self.output.push_str(&comment.text);
+        self.output.push('\n');
+
+        self.last_end = std::cmp::max(self.last_end, comment.span.end);
+        self.block_start = false;
+    }
+
+    /// Writes a blank line if the source has a blank line between the last item written and the
+    /// start location, other than at the start of a block
+    fn blank_line(&mut self, start: usize)
+    {
+        if self.block_start || start <= self.last_end
+        {
+            return;
+        }
+
+        let gap = self.source.get(self.last_end..start).unwrap_or("");
+
+        if gap.matches('\n').count() >= 2
+        {
+            self.output.push('\n');
+        }
+    }
+
+    /// Returns the index of the first opening brace token starting at or after the location
+    fn find_brace(&self, start: usize) -> usize
+    {
+        let mut index = self.tokens.spans.partition_point(|s| s.start < start);
+
+        while self.tokens.tokens[index] != Token::Operator(Operator::LeftBrace)
+        {
+            index += 1;
+        }
+
+        return index;
+    }
+
+    /// Returns the index of the closing brace token matching the opening brace token at the index
+    fn matching_brace(&self, open: usize) -> usize
+    {
+        let mut depth = 0;
+        let mut index = open;
+
+        loop
+        {
+            match self.tokens.tokens[index]
+            {
+                Token::Operator(Operator::LeftBrace) => depth += 1,
+                Token::Operator(Operator::RightBrace) => depth -= 1,
+                _ => ()
+            }
+
+            if depth == 0
+            {
+                return index;
+            }
+
+            index += 1;
+        }
+    }
+}
+
+/// Provides the text of an assignment, without the terminating semicolon. Assignments of an
+/// operation between the variable and a value are written as compound assignments, which parse
+/// to the same expression
+fn assignment(statement: &VarStatement) -> String
+{
+    let name = &statement.varname;
+
+    return match &statement.expr
+    {
+        Expression::Numeric(NumericExpression { inverted: false, value: NumericExpressionType::Operation(o), .. })
+            if !o.a.inverted && matches!(&o.a.value, NumericExpressionType::Variable(n) if n == name) =>
+            format!("{0:} {1:} {2:}", name, OperatorRole::CompoundAssignment(o.op).symbol(), numeric(&o.b)),
+        Expression::String(StringExpression { value: StringExpressionType::Concat(c), .. })
+            if matches!(&c.a.value, StringExpressionType::Variable(n) if n == name) =>
+            format!("{0:} += {1:}", name, string(&c.b)),
+        expr => format!("{0:} = {1:}", name, expression(expr))
+    };
+}
+
+/// Provides the text of the optional assignment within a for statement header
+fn header_assignment(statement: &Statement) -> String
+{
+    return match &statement.data
+    {
+        StatementType::Assign(v) => assignment(v),
+        _ => String::new()
+    };
+}
+
+/// Provides the text of an expression
+fn expression(expr: &Expression) -> String
+{
+    return match expr
+    {
+        Expression::Numeric(e) => numeric(e),
+        Expression::Boolean(e) => boolean(e),
+        Expression::String(e) => string(e)
+    };
+}
+
+/// Provides the text of a call, with the arguments separated by commas
+fn call(call: &CallExpression) -> String
+{
+    let args: Vec<String> = call.args.iter().map(expression).collect();
+    return format!("{0:}({1:})", call.name, args.join(", "));
+}
+
+/// Provides the text of a numeric expression
+fn numeric(expr: &NumericExpression) -> String
+{
+    let text = match &expr.value
+    {
+        NumericExpressionType::Operation(o) =>
+        {
+            let power = o.op.binding_power();
+
+            // Group operands that bind less tightly, or equally tightly on the right as
+            // operations are left-associative
+            let operand = |e: &NumericExpression, right: bool| match &e.value
+            {
+                NumericExpressionType::Operation(inner) if !e.inverted &&
+                    (inner.op.binding_power() < power || (right && inner.op.binding_power() == power)) =>
+                    format!("({0:})", numeric(e)),
+                _ => numeric(e)
+            };
+
+            format!("{0:} {1:} {2:}", operand(&o.a, false), OperatorRole::Numeric(o.op).symbol(), operand(&o.b, true))
+        },
+        NumericExpressionType::IntConstant(v) => v.to_string(),
+        NumericExpressionType::FloatConstant(v) => float(*v),
+        NumericExpressionType::Variable(name) => name.clone(),
+        NumericExpressionType::Call(c) => call(c)
+    };
+
+    if !expr.inverted
+    {
+        return text;
+    }
+
+    return match expr.value
+    {
+        NumericExpressionType::Operation(_) => format!("-({0:})", text),
+        _ => format!("-{0:}", text)
+    };
+}
+
+/// Provides the text of a float constant, always including a decimal point so that it is read
+/// back as a float
+fn float(value: f32) -> String
+{
+    let text = value.to_string();
+
+    if text.contains('.')
+    {
+        return text;
+    }
+
+    return format!("{0:}.0", text);
+}
+
+/// Provides the text of a boolean expression
+fn boolean(expr: &BoolExpression) -> String
+{
+    let text = match &expr.value
+    {
+        BoolExpressionType::Operation(o) =>
+        {
+            let power = o.op.binding_power();
+
+            // Group operands that bind less tightly, or equally tightly on the right as
+            // operations are left-associative
+            let operand = |e: &BoolExpression, right: bool| match &e.value
+            {
+                BoolExpressionType::Operation(inner) if !e.inverted &&
+                    (inner.op.binding_power() < power || (right && inner.op.binding_power() == power)) =>
+                    format!("({0:})", boolean(e)),
+                _ => boolean(e)
+            };
+
+            format!("{0:} {1:} {2:}", operand(&o.a, false), OperatorRole::Boolean(o.op).symbol(), operand(&o.b, true))
+        },
+        BoolExpressionType::Comparison(c) =>
+            format!("{0:} {1:} {2:}", numeric(&c.a), OperatorRole::Comparison(c.op).symbol(), numeric(&c.b)),
+        BoolExpressionType::StringComparison(c) =>
+            format!("{0:} {1:} {2:}", string(&c.a), OperatorRole::Comparison(c.op).symbol(), string(&c.b)),
+        BoolExpressionType::Constant(v) => v.to_string(),
+        BoolExpressionType::Variable(name) => name.clone(),
+        BoolExpressionType::Call(c) => call(c)
+    };
+
+    if !expr.inverted
+    {
+        return text;
+    }
+
+    // Inversion applies to a single value, so operations and comparisons must be grouped
+    return match expr.value
+    {
+        BoolExpressionType::Operation(_) | BoolExpressionType::Comparison(_) | BoolExpressionType::StringComparison(_) =>
+            format!("!({0:})", text),
+        _ => format!("!{0:}", text)
+    };
+}
+
+/// Provides the text of a string expression
+fn string(expr: &StringExpression) -> String
+{
+    return match &expr.value
+    {
+        StringExpressionType::Concat(c) =>
+        {
+            // Group a concatenation on the right, as concatenation is left-associative
+            let b = match c.b.value
+            {
+                StringExpressionType::Concat(_) => format!("({0:})", string(&c.b)),
+                _ => string(&c.b)
+            };
+
+            format!("{0:} + {1:}", string(&c.a), b)
+        },
+        StringExpressionType::Constant(v) => Token::quote(v),
+        StringExpressionType::Variable(name) => name.clone(),
+        StringExpressionType::Call(c) => call(c)
+    };
+}
+
+/// Defines tests of the source formatter
+#[cfg(test)]
+mod tests
+{
+    use crate::function::Functions;
+    use crate::lexer::boolean::BoolExpression;
+    use crate::lexer::numeric::NumericExpression;
+    use crate::lexer::program::Program;
+    use crate::lexer::statement::Statement;
+    use crate::lexer::string::StringExpression;
+    use crate::lexer::visitor::{self, VisitorMut};
+    use crate::span::Span;
+
+    /// Provides the functions that may be called by the test programs
+    fn functions() -> Functions
+    {
+        let mut functions = Functions::new();
+        functions.register("max", |a: f32, b: f32| a.max(b));
+        functions.register("even", |a: i32| a % 2 == 0);
+        functions.register("upper", |s: String| s.to_uppercase());
+        return functions;
+    }
+
+    /// Clears the location of each statement and expression
+    struct ClearSpans;
+
+    impl VisitorMut for ClearSpans
+    {
+        fn visit_statement_mut(&mut self, statement: &mut Statement)
+        {
+            statement.span = Span::default();
+            visitor::walk_statement_mut(self, statement);
+        }
+
+        fn visit_numeric_mut(&mut self, expr: &mut NumericExpression)
+        {
+            expr.span = Span::default();
+            visitor::walk_numeric_mut(self, expr);
+        }
+
+        fn visit_boolean_mut(&mut self, expr: &mut BoolExpression)
+        {
+            expr.span = Span::default();
+            visitor::walk_boolean_mut(self, expr);
+        }
+
+        fn visit_string_mut(&mut self, expr: &mut StringExpression)
+        {
+            expr.span = Span::default();
+            visitor::walk_string_mut(self, expr);
+        }
+    }
+
+    /// Parses the source, clearing the location of each statement and expression so that
+    /// programs may be compared by their structure alone
+    fn parse(source: &str) -> Program
+    {
+        let mut program = crate::parse_with(source, &functions()).unwrap();
+        ClearSpans.visit_program_mut(&mut program);
+        return program;
+    }
+
+    /// Formats the source, checking that the result parses to the same program and is not
+    /// changed by formatting it again
+    fn round_trip(source: &str) -> String
+    {
+        let formatted = crate::format_with(source, &functions()).unwrap();
+
+        assert!(parse(source) == parse(&formatted), "{0:}", formatted);
+        assert_eq!(crate::format_with(&formatted, &functions()).unwrap(), formatted);
+
+        return formatted;
+    }
+
+    /// Tests that statements are written one per line, with indented blocks and a single space
+    /// around each operator
+    #[test]
+    fn format_layout()
+    {
+        let source = "a=1;b=a*2;if(a<b){a+=1;}else{b=b-a;}while(a<10&&true){a*=2;}\n\
+                      for(i=0;i<3;i+=1){if(even(i)){print i,a;}}for(;false;){}\n\
+                      input a;print;s=\"x\";s=s+upper(\"y\");";
+
+        assert_eq!(
+            round_trip(source),
+            "a = 1;\n\
+             b = a * 2;\n\
+             if (a < b) {\n    a += 1;\n} else {\n    b -= a;\n}\n\
+             while (a < 10 && true) {\n    a *= 2;\n}\n\
+             for (i = 0; i < 3; i += 1) {\n    if (even(i)) {\n        print i, a;\n    }\n}\n\
+             for (; false;) {\n}\n\
+             input a;\n\
+             print;\n\
+             s = \"x\";\n\
+             s += upper(\"y\");\n");
+
+        assert_eq!(round_trip(""), "");
+        assert_eq!(round_trip("if (true) { a = 1; } else { }"), "if (true) {\n    a = 1;\n}\n");
+    }
+
+    /// Tests that only the parentheses required by the grouping of operations are kept
+    #[test]
+    fn format_grouping()
+    {
+        let check = |source: &str, expected: &str|
+        {
+            assert_eq!(round_trip(&format!("a = 1.5; b = true; s = \"\"; {0:}", source)), format!("a = 1.5;\nb = true;\ns = \"\";\n{0:}\n", expected));
+        };
+
+        check("x = ((1)) + (2 * 3);", "x = 1 + 2 * 3;");
+        check("x = (1 + 2) * 3 - (4 - 5) - -a;", "x = (1 + 2) * 3 - (4 - 5) - -a;");
+        check("x = 1 - (2 + 3) / (4 * a);", "x = 1 - (2 + 3) / (4 * a);");
+        check("x = -(a + 1) * -(2) + --a;", "x = -(a + 1) * -2 + a;");
+        check("x = 2. * .5 + 100000000000000000000.0;", "x = 2.0 * 0.5 + 100000000000000000000.0;");
+        check("c = !(a < 1) || (b && !b) && (b || false);", "c = !(a < 1) || b && !b && (b || false);");
+        check("c = (a + 1) * 2 >= max(a, 2) && !!b;", "c = (a + 1) * 2 >= max(a, 2) && b;");
+        check("c = !(s == \"a\" + s) && (s + \"b\") != \"c\";", "c = !(s == \"a\" + s) && s + \"b\" != \"c\";");
+        check("t = \"a\" + (s + \"\\n\") + (s);", "t = \"a\" + (s + \"\\n\") + s;");
+        check("a = a - (1 - 2); s = s + \"a\" + \"b\"; s += \"a\" + \"b\";", "a -= 1 - 2;\ns = s + \"a\" + \"b\";\ns += \"a\" + \"b\";");
+        check("a = -a + 1; a = 1 + a; b = !b;", "a = -a + 1;\na = 1 + a;\nb = !b;");
+    }
+
+    /// Tests that comments are kept on their own lines or at the end of the line of the
+    /// statement they follow, with a single blank line kept between groups of statements
+    #[test]
+    fn format_comments()
+    {
+        let source = "// header\n\n\n\
+                      a = 1; // one\n\
+                      /* about b */ b = a /* inline */ + 2;\n\n\
+                      if (a < b) { // check\n\
+                      \x20 REM inside\n\
+                      a = 2; /* two */\n\
+                      \x20 // last\n\
+                      }\n\
+                      else { b = 1; }\n\
+                      // footer";
+
+        assert_eq!(
+            round_trip(source),
+            "// header\n\
+             \n\
+             a = 1; // one\n\
+             /* about b */\n\
+             b = a + 2; /* inline */\n\
+             \n\
+             if (a < b) { // check\n\
+             \x20   REM inside\n\
+             \x20   a = 2; /* two */\n\
+             \x20   // last\n\
+             } else {\n\
+             \x20   b = 1;\n\
+             }\n\
+             // footer\n");
+
+        assert_eq!(
+            round_trip("if (true) { a = 1; }\n// before else\nelse { a = 2; }"),
+            "if (true) {\n    a = 1;\n    // before else\n} else {\n    a = 2;\n}\n");
+        assert_eq!(
+            round_trip("if (true) { a = 1; } // after if\nelse { a = 2; }"),
+            "if (true) {\n    a = 1;\n} // after if\nelse {\n    a = 2;\n}\n");
+        assert_eq!(
+            round_trip("if (true) { a = 1; } // after if\n// before else\nelse // on else\n{ a = 2; }"),
+            "if (true) {\n    a = 1;\n} // after if\n// before else\nelse { // on else\n    a = 2;\n}\n");
+        assert_eq!(round_trip("/* only */"), "/* only */\n");
+        assert_eq!(round_trip("a = 1;\n\n\n\na = 2;\n"), "a = 1;\n\na = 2;\n");
+    }
+
+    /// Tests that a selection of programs are formatted to programs with the same structure,
+    /// which are then unchanged by formatting again
+    #[test]
+    fn format_round_trip()
+    {
+        let programs = [
+            "n = 0; input n; total = 0.0; for (i = 1; i <= n; i += 1) { total += i / 2.0; } print total;",
+            "a = 3; b = 0; while (a > 0) { a -= 1; if (a == 1 || a == 2 && !(b > 0)) { b += a; } else { b = b * (2 - a); } }",
+            "x = 1; x = x - (x - 1) * 2 / (x + x) - -(x * x);",
+            "x = max(1, 2 * max(3, 4.5)) > 2; y = even(2 + 1) || x;",
+            "s = \"tab\\there \\\"q\\\" \\\\ \\u{1}\"; t = upper(s + \"!\") + s; ok = s != t;",
+            "f = 0.1; g = -0.5; h = 3.0 / 7.0; i = 16777216.0; j = 0.000001;",
+            "REM counter\nc = 0; // start\nwhile (c < 3) {\n  /* step */\n  c += 1;\n}\n\n// done\n",
+            "a = 1; a + 2; a < 2; \"x\" + \"y\";"
+        ];
+
+        for program in programs.iter()
+        {
+            round_trip(program);
+        }
+    }
+
+    /// Tests that programs that cannot be parsed are not formatted
+    #[test]
+    fn format_invalid()
+    {
+        assert!(matches!(crate::format("a = (1;"), Err(crate::Error::Parse(_))));
+        assert!(matches!(crate::format("a = 1; /* open"), Err(crate::Error::Lex(_))));
+    }
+}
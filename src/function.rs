@@ -0,0 +1,365 @@
+use crate::interpreter::error::RuntimeError;
+use crate::span::Span;
+use crate::variable::VariableType;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Defines the parameter and result types of a function, with each type given as a zero value
+#[derive(Debug, PartialEq, Clone)]
+pub struct Signature
+{
+    /// Defines the type of each parameter, in order
+    pub params: Vec<VariableType>,
+
+    /// Defines the type of the returned value
+    pub result: VariableType
+}
+
+impl std::fmt::Display for Signature
+{
+    /// Writes the signature as the parameter type names followed by the result type name
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let params: Vec<&str> = self.params.iter().map(|p| p.type_name()).collect();
+        return write!(f, "({0:}) -> {1:}", params.join(", "), self.result.type_name());
+    }
+}
+
+/// Defines a Rust type that may be passed to or returned from a host function
+pub trait HostValue: Sized
+{
+    /// Returns the ZBasic type of the value, as a zero value
+    fn zero() -> VariableType;
+
+    /// Converts a ZBasic value of the matching type into the Rust value, or returns None if the
+    /// value has another type
+    fn from_value(value: VariableType) -> Option<Self>;
+
+    /// Converts the Rust value into a ZBasic value
+    fn into_value(self) -> VariableType;
+}
+
+impl HostValue for bool
+{
+    fn zero() -> VariableType
+    {
+        return VariableType::Boolean(false);
+    }
+
+    fn from_value(value: VariableType) -> Option<bool>
+    {
+        return match value
+        {
+            VariableType::Boolean(v) => Some(v),
+            _ => None
+        };
+    }
+
+    fn into_value(self) -> VariableType
+    {
+        return VariableType::Boolean(self);
+    }
+}
+
+impl HostValue for i32
+{
+    fn zero() -> VariableType
+    {
+        return VariableType::Integer(0);
+    }
+
+    fn from_value(value: VariableType) -> Option<i32>
+    {
+        return match value
+        {
+            VariableType::Integer(v) => Some(v),
+            _ => None
+        };
+    }
+
+    fn into_value(self) -> VariableType
+    {
+        return VariableType::Integer(self);
+    }
+}
+
+impl HostValue for f32
+{
+    fn zero() -> VariableType
+    {
+        return VariableType::Float(0.0);
+    }
+
+    /// Converts the value, also accepting integers as they are promoted to floats
+    fn from_value(value: VariableType) -> Option<f32>
+    {
+        return match value
+        {
+            VariableType::Float(v) => Some(v),
+            VariableType::Integer(v) => Some(v as f32),
+            _ => None
+        };
+    }
+
+    fn into_value(self) -> VariableType
+    {
+        return VariableType::Float(self);
+    }
+}
+
+impl HostValue for String
+{
+    fn zero() -> VariableType
+    {
+        return VariableType::String(String::new());
+    }
+
+    fn from_value(value: VariableType) -> Option<String>
+    {
+        return match value
+        {
+            VariableType::String(v) => Some(v),
+            _ => None
+        };
+    }
+
+    fn into_value(self) -> VariableType
+    {
+        return VariableType::String(self);
+    }
+}
+
+/// Defines a Rust closure that may be registered as a host function, where the type parameter
+/// is the tuple of argument types used to distinguish implementations by arity
+pub trait HostFunction<Args>
+{
+    /// Returns the signature of the function
+    fn signature() -> Signature;
+
+    /// Calls the function with arguments matching the signature, or returns None if the number
+    /// or types of the arguments do not match
+    fn call(&self, args: &[VariableType]) -> Option<VariableType>;
+}
+
+/// Implements HostFunction for closures taking the provided argument type parameters
+macro_rules! host_function
+{
+    ($($arg:ident),*) =>
+    {
+        impl<Func, R, $($arg),*> HostFunction<($($arg,)*)> for Func
+        where
+            Func: Fn($($arg),*) -> R,
+            R: HostValue,
+            $($arg: HostValue),*
+        {
+            fn signature() -> Signature
+            {
+                return Signature
+                {
+                    params: vec![$($arg::zero()),*],
+                    result: R::zero()
+                };
+            }
+
+            fn call(&self, args: &[VariableType]) -> Option<VariableType>
+            {
+                let mut args = args.iter();
+                let result = (self)($($arg::from_value(args.next()?.clone())?),*);
+
+                if args.next().is_some()
+                {
+                    return None;
+                }
+
+                return Some(result.into_value());
+            }
+        }
+    };
+}
+
+host_function!();
+host_function!(A);
+host_function!(A, B);
+host_function!(A, B, C);
+host_function!(A, B, C, D);
+host_function!(A, B, C, D, E);
+host_function!(A, B, C, D, E, F);
+
+/// Defines the wrapped form of a host function, returning None for arguments that do not match
+/// its signature. Functions may be shared between threads, so that interpreters are Send
+type NativeFunction = dyn Fn(&[VariableType]) -> Option<VariableType> + Send + Sync;
+
+/// Defines a registered host function
+#[derive(Clone)]
+pub struct Function
+{
+    /// Defines the parameter and result types of the function
+    pub signature: Signature,
+
+    /// Defines the wrapped closure, returning None for arguments that do not match the signature
+    call: Arc<NativeFunction>
+}
+
+impl Function
+{
+    /// Calls the function registered with the provided name, with integer arguments accepted for
+    /// float parameters. The span of the call is used to report arguments that do not match the
+    /// signature, which the type checker rejects before programs are run
+    pub fn call(&self, name: &str, args: &[VariableType], span: Span) -> Result<VariableType, RuntimeError>
+    {
+        if args.len() != self.signature.params.len()
+        {
+            return Err(RuntimeError::UndefinedFunction
+            {
+                name: name.to_string(),
+                span
+            });
+        }
+
+        for (arg, param) in args.iter().zip(self.signature.params.iter())
+        {
+            let matches = match (arg, param)
+            {
+                (VariableType::Integer(_), VariableType::Float(_)) => true,
+                (a, p) => std::mem::discriminant(a) == std::mem::discriminant(p)
+            };
+
+            if !matches
+            {
+                return Err(RuntimeError::mismatch(param.type_name(), arg.clone(), span));
+            }
+        }
+
+        return (self.call)(args).ok_or_else(|| RuntimeError::UndefinedFunction
+        {
+            name: name.to_string(),
+            span
+        });
+    }
+}
+
+/// Defines the host functions that programs may call, keyed by name
+#[derive(Clone, Default)]
+pub struct Functions
+{
+    functions: HashMap<String, Function>
+}
+
+impl Functions
+{
+    /// Returns a new table with no functions registered
+    pub fn new() -> Functions
+    {
+        return Functions::default();
+    }
+
+    /// Registers the closure as a function with the provided name, replacing any function
+    /// already registered with the name. The signature is taken from the closure argument and
+    /// result types, which may each be bool, i32, f32 or String. The closure must be Send and
+    /// Sync, as the table may be shared between threads
+    pub fn register<Args, F>(&mut self, name: &str, f: F)
+    where
+        F: HostFunction<Args> + Send + Sync + 'static
+    {
+        self.functions.insert(name.to_string(), Function
+        {
+            signature: F::signature(),
+            call: Arc::new(move |args| f.call(args))
+        });
+    }
+
+    /// Returns the function with the provided name, if any
+    pub fn get(&self, name: &str) -> Option<&Function>
+    {
+        return self.functions.get(name);
+    }
+
+    /// Returns the signature of the function with the provided name, if any
+    pub fn signature(&self, name: &str) -> Option<&Signature>
+    {
+        return self.get(name).map(|f| &f.signature);
+    }
+}
+
+/// Defines tests of the host function table
+#[cfg(test)]
+mod tests
+{
+    use super::{Functions, HostFunction, Signature};
+    use crate::interpreter::error::RuntimeError;
+    use crate::span::Span;
+    use crate::variable::VariableType;
+
+    /// Tests that signatures are taken from the closure types and arguments are converted
+    #[test]
+    fn register()
+    {
+        let mut functions = Functions::new();
+        functions.register("clamp", |x: f32, lo: f32, hi: f32| x.max(lo).min(hi));
+        functions.register("even", |x: i32| x % 2 == 0);
+        functions.register("seven", || 7);
+        functions.register("greet", |name: String, loud: bool| format!("hello {0:}{1:}", name, if loud { "!" } else { "" }));
+
+        assert_eq!(
+            functions.signature("clamp"),
+            Some(&Signature
+            {
+                params: vec![VariableType::Float(0.0); 3],
+                result: VariableType::Float(0.0)
+            }));
+        assert_eq!(functions.signature("even").unwrap().to_string(), "(integer) -> boolean");
+        assert_eq!(functions.signature("seven").unwrap().to_string(), "() -> integer");
+        assert_eq!(functions.signature("greet").unwrap().to_string(), "(string, boolean) -> string");
+        assert_eq!(functions.signature("missing"), None);
+
+        let clamp = functions.get("clamp").unwrap();
+        let args = [VariableType::Integer(5), VariableType::Float(0.0), VariableType::Float(2.5)];
+        assert_eq!(clamp.call("clamp", &args, Span::default()), Ok(VariableType::Float(2.5)));
+        assert_eq!(functions.get("even").unwrap().call("even", &[VariableType::Integer(4)], Span::default()), Ok(VariableType::Boolean(true)));
+        assert_eq!(functions.get("seven").unwrap().call("seven", &[], Span::default()), Ok(VariableType::Integer(7)));
+        assert_eq!(
+            functions.get("greet").unwrap().call("greet", &[VariableType::String(String::from("world")), VariableType::Boolean(true)], Span::default()),
+            Ok(VariableType::String(String::from("hello world!"))));
+    }
+
+    /// Tests that arguments not matching the signature are reported as errors rather than
+    /// passed to the closure
+    #[test]
+    fn call_mismatched()
+    {
+        let mut functions = Functions::new();
+        functions.register("even", |x: i32| x % 2 == 0);
+        let even = functions.get("even").unwrap();
+        let span = Span::new(0, 7, 1, 1);
+
+        assert_eq!(
+            even.call("even", &[VariableType::Float(1.5)], span),
+            Err(RuntimeError::mismatch("integer", VariableType::Float(1.5), span)));
+        assert_eq!(
+            even.call("even", &[], span),
+            Err(RuntimeError::UndefinedFunction
+            {
+                name: String::from("even"),
+                span
+            }));
+        assert_eq!(
+            even.call("even", &[VariableType::Integer(1), VariableType::Integer(2)], span),
+            Err(RuntimeError::UndefinedFunction
+            {
+                name: String::from("even"),
+                span
+            }));
+    }
+
+    /// Tests that closures reject arguments of the wrong number or type when called directly
+    #[test]
+    fn host_call_mismatched()
+    {
+        let even = |x: i32| x % 2 == 0;
+
+        assert_eq!(HostFunction::<(i32,)>::call(&even, &[VariableType::Integer(2)]), Some(VariableType::Boolean(true)));
+        assert_eq!(HostFunction::<(i32,)>::call(&even, &[VariableType::String(String::new())]), None);
+        assert_eq!(HostFunction::<(i32,)>::call(&even, &[]), None);
+        assert_eq!(HostFunction::<(i32,)>::call(&even, &[VariableType::Integer(2), VariableType::Integer(3)]), None);
+    }
+}
@@ -0,0 +1,621 @@
+use crate::function::{Functions, HostFunction};
+use crate::lexer::boolean::{BoolExpression, BoolExpressionType, BooleanOpType};
+use crate::lexer::call::CallExpression;
+use crate::lexer::expression::Expression;
+use crate::lexer::numeric::{NumericExpression, NumericExpressionDataType, NumericExpressionType, NumericOpType};
+use crate::lexer::program::Program;
+use crate::lexer::statement::{Statement, StatementType};
+use crate::lexer::string::{StringExpression, StringExpressionType};
+use crate::span::Span;
+use crate::variable::{Variable, VariableType};
+use std::collections::HashMap;
+
+pub mod error;
+pub mod io;
+
+use error::RuntimeError;
+use io::{Reader, StdinReader, Writer};
+
+/// Defines a tree-walking interpreter that executes programs, storing the resulting variable
+/// values. Input and print statements use the reader and writer, which may borrow for the
+/// lifetime of the interpreter
+pub struct Interpreter<'io>
+{
+    /// Defines the runtime variable values, keyed by variable name
+    pub variables: HashMap<String, Variable>,
+
+    /// Defines the host functions that programs may call
+    pub functions: Functions,
+
+    /// Defines the source of lines read by input statements
+    pub reader: Box<dyn Reader + Send + 'io>,
+
+    /// Defines the destination of lines written by print statements
+    pub writer: Box<dyn Writer + Send + 'io>
+}
+
+impl Default for Interpreter<'static>
+{
+    fn default() -> Interpreter<'static>
+    {
+        return Interpreter::new();
+    }
+}
+
+impl Interpreter<'static>
+{
+    /// Returns a new interpreter with no variables or functions defined, reading from standard
+    /// input and writing to standard output
+    pub fn new() -> Interpreter<'static>
+    {
+        return Interpreter::with_io(Box::new(StdinReader), Box::new(std::io::stdout()));
+    }
+}
+
+impl<'io> Interpreter<'io>
+{
+    /// Returns a new interpreter with no variables or functions defined, using the provided
+    /// reader and writer for input and print statements. The reader and writer must be Send, so
+    /// that the interpreter may be moved to another thread
+    pub fn with_io(reader: Box<dyn Reader + Send + 'io>, writer: Box<dyn Writer + Send + 'io>) -> Interpreter<'io>
+    {
+        return Interpreter
+        {
+            variables: HashMap::new(),
+            functions: Functions::new(),
+            reader,
+            writer
+        };
+    }
+
+    /// Registers the closure as a host function that programs may call by name. The signature is
+    /// taken from the closure argument and result types, which may each be bool, i32, f32 or
+    /// String. Programs must be parsed with the registered functions, such as by using
+    /// Program::parse_with with the functions of the interpreter
+    pub fn register<Args, F>(&mut self, name: &str, f: F)
+    where
+        F: HostFunction<Args> + Send + Sync + 'static
+    {
+        self.functions.register(name, f);
+    }
+
+    /// Executes the program, returning the final variable state. Programs should be type checked
+    /// first, as a value used as a different kind of value is only reported when it is reached
+    pub fn run(&mut self, program: &Program) -> Result<&HashMap<String, Variable>, RuntimeError>
+    {
+        self.execute(&program.main)?;
+        return Ok(&self.variables);
+    }
+
+    /// Returns the current value of the variable with the provided name
+    pub fn get(&self, name: &str) -> Result<VariableType, RuntimeError>
+    {
+        return self.read(name, Span::default());
+    }
+
+    /// Returns the current value of the variable with the provided name, using the span of the
+    /// reading expression to report a variable that has not been assigned at runtime
+    fn read(&self, name: &str, span: Span) -> Result<VariableType, RuntimeError>
+    {
+        return match self.variables.get(name)
+        {
+            Some(v) => Ok(v.vartype.clone()),
+            None => Err(RuntimeError::UndefinedVariable
+            {
+                name: name.to_string(),
+                span
+            })
+        };
+    }
+
+    /// Executes the statement and each statement linked after it
+    pub fn execute(&mut self, statement: &Statement) -> Result<(), RuntimeError>
+    {
+        let mut current = Some(statement);
+
+        while let Some(s) = current
+        {
+            match &s.data
+            {
+                StatementType::Empty => (),
+                StatementType::If(v) =>
+                {
+                    if self.evaluate_bool(&v.boolexpr)?
+                    {
+                        self.execute(&v.statement)?;
+                    }
+                    else
+                    {
+                        self.execute(&v.else_statement)?;
+                    }
+                },
+                StatementType::While(v) =>
+                {
+                    while self.evaluate_bool(&v.boolexpr)?
+                    {
+                        self.execute(&v.statement)?;
+                    }
+                },
+                StatementType::For(v) =>
+                {
+                    self.execute(&v.init)?;
+
+                    while self.evaluate_bool(&v.boolexpr)?
+                    {
+                        self.execute(&v.statement)?;
+                        self.execute(&v.step)?;
+                    }
+                },
+                StatementType::Assign(v) =>
+                {
+                    let vartype = self.evaluate(&v.expr)?;
+                    self.variables.insert(v.varname.clone(), Variable
+                    {
+                        vartype,
+                        varname: v.varname.clone()
+                    });
+                },
+                StatementType::Print(v) =>
+                {
+                    let values = v.exprs
+                        .iter()
+                        .map(|e| self.evaluate(e))
+                        .collect::<Result<Vec<VariableType>, RuntimeError>>()?;
+
+                    io::print_values(&mut *self.writer, &values, s.span)?;
+                },
+                StatementType::Input(v) =>
+                {
+                    let vartype = io::read_input(&mut *self.reader, &v.vartype, s.span)?;
+                    self.variables.insert(v.varname.clone(), Variable
+                    {
+                        vartype,
+                        varname: v.varname.clone()
+                    });
+                },
+                StatementType::Expr(e) =>
+                {
+                    self.evaluate(e)?;
+                }
+            }
+
+            current = s.next.as_deref();
+        }
+
+        return Ok(());
+    }
+
+    /// Evaluates an expression to a resulting value
+    pub fn evaluate(&self, expr: &Expression) -> Result<VariableType, RuntimeError>
+    {
+        return match expr
+        {
+            Expression::Numeric(e) => self.evaluate_numeric(e),
+            Expression::Boolean(e) => Ok(VariableType::Boolean(self.evaluate_bool(e)?)),
+            Expression::String(e) => Ok(VariableType::String(self.evaluate_string(e)?))
+        };
+    }
+
+    /// Evaluates a numeric expression, providing an integer or float value
+    pub fn evaluate_numeric(&self, expr: &NumericExpression) -> Result<VariableType, RuntimeError>
+    {
+        let value = match &expr.value
+        {
+            NumericExpressionType::IntConstant(v) => VariableType::Integer(*v),
+            NumericExpressionType::FloatConstant(v) => VariableType::Float(*v),
+            NumericExpressionType::Variable(name) => Interpreter::numeric(self.read(name, expr.span)?, expr.span)?,
+            NumericExpressionType::Operation(op) =>
+            {
+                let a = self.evaluate_numeric(&op.a)?;
+                let b = self.evaluate_numeric(&op.b)?;
+                Interpreter::numeric_operation(op.op, a, b, expr.span)?
+            },
+            NumericExpressionType::Call(call) => Interpreter::numeric(self.call(call, expr.span)?, expr.span)?
+        };
+
+        // Negate the value if the expression is inverted
+        let value = if expr.inverted
+        {
+            match value
+            {
+                VariableType::Integer(v) => VariableType::Integer(v.checked_neg().ok_or(RuntimeError::IntegerOverflow { span: expr.span })?),
+                VariableType::Float(v) => VariableType::Float(-v),
+                v => return Err(RuntimeError::mismatch("numeric", v, expr.span))
+            }
+        }
+        else
+        {
+            value
+        };
+
+        // Promote integer values if the expression was given a float type by the type checker
+        return if expr.data_type == NumericExpressionDataType::Float
+        {
+            Ok(VariableType::Float(Interpreter::as_float(value, expr.span)?))
+        }
+        else
+        {
+            Ok(value)
+        };
+    }
+
+    /// Performs a numeric operation, using integer arithmetic if both values are integers and
+    /// promoting to float arithmetic otherwise. The span of the operation is used to report errors
+    pub fn numeric_operation(op: NumericOpType, a: VariableType, b: VariableType, span: Span) -> Result<VariableType, RuntimeError>
+    {
+        if let (&VariableType::Integer(a), &VariableType::Integer(b)) = (&a, &b)
+        {
+            if op == NumericOpType::Divide && b == 0
+            {
+                return Err(RuntimeError::DivisionByZero { span });
+            }
+
+            let result = match op
+            {
+                NumericOpType::Add => a.checked_add(b),
+                NumericOpType::Subtract => a.checked_sub(b),
+                NumericOpType::Multiply => a.checked_mul(b),
+                NumericOpType::Divide => a.checked_div(b)
+            };
+
+            return result.map(VariableType::Integer).ok_or(RuntimeError::IntegerOverflow { span });
+        }
+
+        let a = Interpreter::as_float(a, span)?;
+        let b = Interpreter::as_float(b, span)?;
+
+        if op == NumericOpType::Divide && b == 0.0
+        {
+            return Err(RuntimeError::DivisionByZero { span });
+        }
+
+        return Ok(VariableType::Float(match op
+        {
+            NumericOpType::Add => a + b,
+            NumericOpType::Subtract => a - b,
+            NumericOpType::Multiply => a * b,
+            NumericOpType::Divide => a / b
+        }));
+    }
+
+    /// Converts a numeric value into a float value. The span is used to report a value that is
+    /// not numeric
+    pub fn as_float(value: VariableType, span: Span) -> Result<f32, RuntimeError>
+    {
+        return match value
+        {
+            VariableType::Integer(v) => Ok(v as f32),
+            VariableType::Float(v) => Ok(v),
+            v => Err(RuntimeError::mismatch("numeric", v, span))
+        };
+    }
+
+    /// Provides the value if it is an integer or float value. The span is used to report a value
+    /// that is not numeric
+    fn numeric(value: VariableType, span: Span) -> Result<VariableType, RuntimeError>
+    {
+        return match value
+        {
+            v @ VariableType::Integer(_) | v @ VariableType::Float(_) => Ok(v),
+            v => Err(RuntimeError::mismatch("numeric", v, span))
+        };
+    }
+
+    /// Evaluates the arguments of a function call and calls the registered function. The span of
+    /// the call is used to report a function that is not registered with the parsed signature
+    fn call(&self, call: &CallExpression, span: Span) -> Result<VariableType, RuntimeError>
+    {
+        let function = match self.functions.get(&call.name)
+        {
+            Some(f) if f.signature == call.signature => f,
+            _ => return Err(RuntimeError::UndefinedFunction
+            {
+                name: call.name.clone(),
+                span
+            })
+        };
+
+        let args = call.args
+            .iter()
+            .map(|a| self.evaluate(a))
+            .collect::<Result<Vec<VariableType>, RuntimeError>>()?;
+
+        return function.call(&call.name, &args, span);
+    }
+
+    /// Evaluates a boolean expression
+    pub fn evaluate_bool(&self, expr: &BoolExpression) -> Result<bool, RuntimeError>
+    {
+        let value = match &expr.value
+        {
+            BoolExpressionType::Constant(v) => *v,
+            BoolExpressionType::Variable(name) => match self.read(name, expr.span)?
+            {
+                VariableType::Boolean(v) => v,
+                v => return Err(RuntimeError::mismatch("boolean", v, expr.span))
+            },
+            BoolExpressionType::Operation(op) =>
+            {
+                // Short-circuit the second value if the result is already known
+                let a = self.evaluate_bool(&op.a)?;
+
+                match op.op
+                {
+                    BooleanOpType::And => a && self.evaluate_bool(&op.b)?,
+                    BooleanOpType::Or => a || self.evaluate_bool(&op.b)?
+                }
+            },
+            BoolExpressionType::Call(call) => match self.call(call, expr.span)?
+            {
+                VariableType::Boolean(v) => v,
+                v => return Err(RuntimeError::mismatch("boolean", v, expr.span))
+            },
+            BoolExpressionType::Comparison(c) =>
+            {
+                let a = self.evaluate_numeric(&c.a)?;
+                let b = self.evaluate_numeric(&c.b)?;
+
+                match (c.operand_type(), a, b)
+                {
+                    (NumericExpressionDataType::Int, VariableType::Integer(a), VariableType::Integer(b)) => c.op.compare(a, b),
+                    (_, a, b) => c.op.compare(Interpreter::as_float(a, expr.span)?, Interpreter::as_float(b, expr.span)?)
+                }
+            },
+            BoolExpressionType::StringComparison(c) => c.op.compare(self.evaluate_string(&c.a)?, self.evaluate_string(&c.b)?)
+        };
+
+        return Ok(value != expr.inverted);
+    }
+
+    /// Evaluates a string expression
+    pub fn evaluate_string(&self, expr: &StringExpression) -> Result<String, RuntimeError>
+    {
+        return match &expr.value
+        {
+            StringExpressionType::Constant(v) => Ok(v.clone()),
+            StringExpressionType::Variable(name) => match self.read(name, expr.span)?
+            {
+                VariableType::String(v) => Ok(v),
+                v => Err(RuntimeError::mismatch("string", v, expr.span))
+            },
+            StringExpressionType::Call(call) => match self.call(call, expr.span)?
+            {
+                VariableType::String(v) => Ok(v),
+                v => Err(RuntimeError::mismatch("string", v, expr.span))
+            },
+            StringExpressionType::Concat(c) => Ok(self.evaluate_string(&c.a)? + &self.evaluate_string(&c.b)?)
+        };
+    }
+}
+
+/// Defines tests of the interpreter
+#[cfg(test)]
+mod tests
+{
+    use super::Interpreter;
+    use crate::checker;
+    use super::error::RuntimeError;
+    use crate::lexer::program::Program;
+    use crate::span::Span;
+    use crate::token::Token;
+    use crate::variable::VariableType;
+    use std::collections::HashMap;
+
+    /// Parses, type checks and runs the provided input, returning the interpreter state
+    fn run(input: &str) -> Result<Interpreter<'static>, RuntimeError>
+    {
+        let mut program = Program::parse(&mut Token::tokenize(input).unwrap()).unwrap();
+        checker::check(&mut program).unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.run(&program)?;
+        return Ok(interpreter);
+    }
+
+    /// Tests that assignments store the evaluated expression values
+    #[test]
+    fn run_assignments()
+    {
+        let interp = run("a = 1 + 2 * 3; b = 7 / 2; c = 7 / 2.0; d = 1 < 2 && !(2.5 >= 3); e = -4 - -2; f = -(1 + 2) * 2; g = --3; h = false && true || true;").unwrap();
+
+        assert_eq!(interp.get("a"), Ok(VariableType::Integer(7)));
+        assert_eq!(interp.get("b"), Ok(VariableType::Integer(3)));
+        assert_eq!(interp.get("c"), Ok(VariableType::Float(3.5)));
+        assert_eq!(interp.get("d"), Ok(VariableType::Boolean(true)));
+        assert_eq!(interp.get("e"), Ok(VariableType::Integer(-2)));
+        assert_eq!(interp.get("f"), Ok(VariableType::Integer(-6)));
+        assert_eq!(interp.get("g"), Ok(VariableType::Integer(3)));
+        assert_eq!(interp.get("h"), Ok(VariableType::Boolean(true)));
+        assert_eq!(interp.get("z"), Err(RuntimeError::UndefinedVariable { name: "z".to_string(), span: Span::default() }));
+    }
+
+    /// Tests that if and else blocks are selected by the condition
+    #[test]
+    fn run_if()
+    {
+        let interp = run("if (1 == 1.0) { a = 1; } else { a = 2; } if (false || 2 < 1) { b = 1; } else { b = 2; }").unwrap();
+
+        assert_eq!(interp.get("a"), Ok(VariableType::Integer(1)));
+        assert_eq!(interp.get("b"), Ok(VariableType::Integer(2)));
+    }
+
+    /// Tests that while blocks are not entered if the condition is initially false
+    #[test]
+    fn run_while()
+    {
+        let interp = run("while (false) { a = 1; } b = 2;").unwrap();
+
+        assert!(interp.get("a").is_err());
+        assert_eq!(interp.get("b"), Ok(VariableType::Integer(2)));
+    }
+
+    /// Tests that for statements run the initial assignment before checking the condition
+    #[test]
+    fn run_for()
+    {
+        let interp = run("for (i = 3; 2 < 1; j = 1) { k = 1; }").unwrap();
+
+        assert_eq!(interp.get("i"), Ok(VariableType::Integer(3)));
+        assert!(interp.get("j").is_err());
+        assert!(interp.get("k").is_err());
+    }
+
+    /// Tests that print statements write to the writer and input statements read from the reader
+    #[test]
+    fn run_print_input()
+    {
+        let mut program = Program::parse(&mut Token::tokenize("n = 0; b = false; input n; input f; input b; print n * 2, f, !b; print; input n;").unwrap()).unwrap();
+        checker::check(&mut program).unwrap();
+
+        let mut output: Vec<u8> = Vec::new();
+        let result =
+        {
+            let input: &[u8] = b"21\n 1.5\ntrue\n";
+            let mut interp = Interpreter::with_io(Box::new(input), Box::new(&mut output));
+            interp.run(&program).cloned()
+        };
+
+        assert_eq!(String::from_utf8(output).unwrap(), "42 1.5 false\n\n");
+        assert_eq!(
+            result.unwrap_err(),
+            RuntimeError::Io
+            {
+                message: "unexpected end of input".to_string(),
+                span: Span::new(72, 80, 1, 73)
+            });
+
+        let input: &[u8] = b"1.5\n";
+        let mut interp = Interpreter::with_io(Box::new(input), Box::new(Vec::new()));
+        assert!(matches!(interp.run(&program), Err(RuntimeError::InvalidInput { .. })));
+    }
+
+    /// Tests that strings are joined and compared, and printed as their text
+    #[test]
+    fn run_strings()
+    {
+        let source = "t = \"\"; s = \"a\"; for (i = 0; i < 2; i += 1) { s += \"\\\"b\"; } input t; same = t + s == \"x a\\\"b\\\"b\"; print s, same, t;";
+        let mut program = Program::parse(&mut Token::tokenize(source).unwrap()).unwrap();
+        checker::check(&mut program).unwrap();
+
+        let mut output: Vec<u8> = Vec::new();
+        let variables =
+        {
+            let input: &[u8] = b"x \n";
+            let mut interp = Interpreter::with_io(Box::new(input), Box::new(&mut output));
+            interp.run(&program).cloned().unwrap()
+        };
+
+        assert_eq!(variables["s"].vartype, VariableType::String(String::from("a\"b\"b")));
+        assert_eq!(variables["t"].vartype, VariableType::String(String::from("x ")));
+        assert_eq!(variables["same"].vartype, VariableType::Boolean(true));
+        assert_eq!(variables["s"].vartype.to_string(), "\"a\\\"b\\\"b\"");
+        assert_eq!(String::from_utf8(output).unwrap(), "a\"b\"b true x \n");
+    }
+
+    /// Tests that calls evaluate their arguments and pass them to the registered function
+    #[test]
+    fn run_calls()
+    {
+        let mut interp = Interpreter::new();
+        interp.register("clamp", |x: f32, lo: f32, hi: f32| x.max(lo).min(hi));
+        interp.register("even", |x: i32| x % 2 == 0);
+
+        let mut program = Program::parse_with(
+            &mut Token::tokenize("a = clamp(7, 0, 2.5); n = 0; for (i = 0; i < 5; i += 1) { if (even(i)) { n += 1; } }").unwrap(),
+            HashMap::new(),
+            &interp.functions).unwrap();
+        checker::check(&mut program).unwrap();
+        interp.run(&program).unwrap();
+
+        assert_eq!(interp.get("a"), Ok(VariableType::Float(2.5)));
+        assert_eq!(interp.get("n"), Ok(VariableType::Integer(3)));
+
+        // Functions must be registered with the same signature when the program is run
+        assert_eq!(
+            Interpreter::new().run(&program).unwrap_err(),
+            RuntimeError::UndefinedFunction
+            {
+                name: "clamp".to_string(),
+                span: Span::new(4, 20, 1, 5)
+            });
+    }
+
+    /// Tests that loops read and update variables between iterations
+    #[test]
+    fn run_variables()
+    {
+        let interp = run("s = 0; for (i = 1; i <= 4; i = i + 1) { s = s + i * i; } f = 1.5; f = f * s; n = 0; done = false; while (!done) { n = n - 1; done = n < -2; }").unwrap();
+
+        assert_eq!(interp.get("s"), Ok(VariableType::Integer(30)));
+        assert_eq!(interp.get("i"), Ok(VariableType::Integer(5)));
+        assert_eq!(interp.get("f"), Ok(VariableType::Float(45.0)));
+        assert_eq!(interp.get("n"), Ok(VariableType::Integer(-3)));
+        assert_eq!(interp.get("done"), Ok(VariableType::Boolean(true)));
+    }
+
+    /// Tests that compound assignments update the current value
+    #[test]
+    fn run_compound_assignment()
+    {
+        let interp = run("a = 10; a -= 1 + 2; b = 3; b *= a; c = 7.0; c /= 2; for (i = 0; i < 3; i += 1) { a += i; }").unwrap();
+
+        assert_eq!(interp.get("a"), Ok(VariableType::Integer(10)));
+        assert_eq!(interp.get("b"), Ok(VariableType::Integer(21)));
+        assert_eq!(interp.get("c"), Ok(VariableType::Float(3.5)));
+        assert_eq!(interp.get("i"), Ok(VariableType::Integer(3)));
+    }
+
+    /// Tests that integer values assigned to promoted float variables are stored as floats
+    #[test]
+    fn run_promotion()
+    {
+        let interp = run("a = 7 / 2; b = a; a += 0.5; c = 1; for (i = 0; i < 2; i += 1) { c = c / 2; c = c * 1.0; }").unwrap();
+
+        assert_eq!(interp.get("a"), Ok(VariableType::Float(3.5)));
+        assert_eq!(interp.get("b"), Ok(VariableType::Float(3.0)));
+        assert_eq!(interp.get("c"), Ok(VariableType::Float(0.25)));
+    }
+
+    /// Tests that the lowest integer may be written as a negated literal
+    #[test]
+    fn run_lowest_integer()
+    {
+        let interp = run("a = -2147483648; b = a + 1;").unwrap();
+        assert_eq!(interp.get("a"), Ok(VariableType::Integer(i32::MIN)));
+        assert_eq!(interp.get("b"), Ok(VariableType::Integer(-2147483647)));
+        assert_eq!(run("a = -2147483648; b = -a;").err(), Some(RuntimeError::IntegerOverflow { span: Span::new(21, 23, 1, 22) }));
+    }
+
+    /// Tests that runtime errors are reported instead of panicking
+    #[test]
+    fn run_errors()
+    {
+        assert_eq!(run("a = 1 / 0;").err(), Some(RuntimeError::DivisionByZero { span: Span::new(4, 9, 1, 5) }));
+        assert_eq!(run("a = 1.0 / (2 - 2);").err(), Some(RuntimeError::DivisionByZero { span: Span::new(4, 17, 1, 5) }));
+        assert_eq!(run("a = 2147483647 + 1;").err(), Some(RuntimeError::IntegerOverflow { span: Span::new(4, 18, 1, 5) }));
+        assert_eq!(
+            run("if (false) { a = 1; } b = a;").err(),
+            Some(RuntimeError::UndefinedVariable { name: "a".to_string(), span: Span::new(26, 27, 1, 27) }));
+    }
+
+    /// Tests that programs that were parsed but not type checked report mismatched values
+    /// instead of panicking
+    #[test]
+    fn run_unchecked()
+    {
+        let run_unchecked = |input: &str|
+        {
+            let program = Program::parse(&mut Token::tokenize(input).unwrap()).unwrap();
+            return Interpreter::new().run(&program).map(|_| ()).err();
+        };
+
+        assert_eq!(
+            run_unchecked("a = 1; a = true; b = a + 1;"),
+            Some(RuntimeError::TypeMismatch { expected: "numeric", found: VariableType::Boolean(true), span: Span::new(21, 22, 1, 22) }));
+        assert_eq!(
+            run_unchecked("a = true; a = 2.5; if (a) { }"),
+            Some(RuntimeError::TypeMismatch { expected: "boolean", found: VariableType::Float(2.5), span: Span::new(23, 24, 1, 24) }));
+        assert_eq!(
+            run_unchecked("s = \"x\"; s = 1; t = s + \"y\";").map(|e| e.to_string()),
+            Some(String::from("integer value used as a string value at 1:21")));
+    }
+}
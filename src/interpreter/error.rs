@@ -0,0 +1,108 @@
+use crate::span::Span;
+use crate::variable::VariableType;
+
+/// Defines the errors that may be raised while executing a program
+#[derive(Debug, PartialEq, Clone)]
+pub enum RuntimeError
+{
+    /// Indicates that a numeric value was divided by zero
+    DivisionByZero
+    {
+        span: Span
+    },
+
+    /// Indicates that an integer operation overflowed the allowed integer range
+    IntegerOverflow
+    {
+        span: Span
+    },
+
+    /// Indicates that a variable was read before being assigned
+    UndefinedVariable
+    {
+        name: String,
+        span: Span
+    },
+
+    /// Indicates that a called function is not registered with the signature it was parsed with
+    UndefinedFunction
+    {
+        name: String,
+        span: Span
+    },
+
+    /// Indicates that the text read by an input statement is not a value of the variable type
+    InvalidInput
+    {
+        text: String,
+        expected: VariableType,
+        span: Span
+    },
+
+    /// Indicates that reading input or writing output failed, including reaching the end of input
+    Io
+    {
+        message: String,
+        span: Span
+    },
+
+    /// Indicates that a value was used as a different kind of value than it holds, which is only
+    /// possible for programs that were not type checked, where the expected kind is numeric,
+    /// boolean or string
+    TypeMismatch
+    {
+        expected: &'static str,
+        found: VariableType,
+        span: Span
+    }
+}
+
+impl RuntimeError
+{
+    /// Returns the error for a value used as a different kind of value, which the type checker
+    /// rejects before programs are run
+    pub fn mismatch(expected: &'static str, found: VariableType, span: Span) -> RuntimeError
+    {
+        return RuntimeError::TypeMismatch
+        {
+            expected,
+            found,
+            span
+        };
+    }
+
+    /// Returns the source location associated with the error
+    pub fn span(&self) -> Span
+    {
+        return match self
+        {
+            RuntimeError::DivisionByZero { span } => *span,
+            RuntimeError::IntegerOverflow { span } => *span,
+            RuntimeError::UndefinedVariable { span, .. } => *span,
+            RuntimeError::UndefinedFunction { span, .. } => *span,
+            RuntimeError::InvalidInput { span, .. } => *span,
+            RuntimeError::Io { span, .. } => *span,
+            RuntimeError::TypeMismatch { span, .. } => *span
+        };
+    }
+}
+
+impl std::fmt::Display for RuntimeError
+{
+    /// Writes a human-readable description of the runtime error
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return match self
+        {
+            RuntimeError::DivisionByZero { span } => write!(f, "division by zero at {0:}", span),
+            RuntimeError::IntegerOverflow { span } => write!(f, "integer overflow at {0:}", span),
+            RuntimeError::UndefinedVariable { name, span } => write!(f, "undefined variable {0:} at {1:}", name, span),
+            RuntimeError::UndefinedFunction { name, span } => write!(f, "undefined function {0:} at {1:}", name, span),
+            RuntimeError::InvalidInput { text, expected, span } => write!(f, "invalid {0:} input {1:?} at {2:}", expected.type_name(), text, span),
+            RuntimeError::Io { message, span } => write!(f, "{0:} at {1:}", message, span),
+            RuntimeError::TypeMismatch { expected, found, span } => write!(f, "{0:} value used as a {1:} value at {2:}", found.type_name(), expected, span)
+        };
+    }
+}
+
+impl std::error::Error for RuntimeError {}
@@ -0,0 +1,178 @@
+use crate::span::Span;
+use crate::variable::VariableType;
+use super::error::RuntimeError;
+use std::io::{BufRead, Write};
+
+/// Defines a source of lines read by input statements. Any buffered reader may be used, such as
+/// a locked standard input or a byte slice
+pub trait Reader
+{
+    /// Returns the next line without the line ending, or None at the end of the input
+    fn read_line(&mut self) -> std::io::Result<Option<String>>;
+}
+
+/// Defines a destination for lines written by print statements. Any writer may be used, such
+/// as standard output or a byte vector
+pub trait Writer
+{
+    /// Writes the line, followed by a line ending
+    fn write_line(&mut self, line: &str) -> std::io::Result<()>;
+}
+
+impl<R: BufRead> Reader for R
+{
+    fn read_line(&mut self) -> std::io::Result<Option<String>>
+    {
+        let mut line = String::new();
+
+        if BufRead::read_line(self, &mut line)? == 0
+        {
+            return Ok(None);
+        }
+
+        // Remove the line ending
+        let length = line.trim_end_matches(&['\n', '\r'][..]).len();
+        line.truncate(length);
+        return Ok(Some(line));
+    }
+}
+
+impl<W: Write> Writer for W
+{
+    fn write_line(&mut self, line: &str) -> std::io::Result<()>
+    {
+        writeln!(self, "{0:}", line)?;
+        return self.flush();
+    }
+}
+
+/// Defines a reader for the standard input stream, which locks the stream only while each line
+/// is read so that it may be shared with other readers, such as a line editor
+pub struct StdinReader;
+
+impl Reader for StdinReader
+{
+    fn read_line(&mut self) -> std::io::Result<Option<String>>
+    {
+        return Reader::read_line(&mut std::io::stdin().lock());
+    }
+}
+
+/// Reads a line from the reader and converts it to a value of the expected type, using the span
+/// of the input statement to report errors
+pub fn read_input(reader: &mut dyn Reader, expected: &VariableType, span: Span) -> Result<VariableType, RuntimeError>
+{
+    let line = match reader.read_line()
+    {
+        Ok(Some(line)) => line,
+        Ok(None) => return Err(RuntimeError::Io
+        {
+            message: "unexpected end of input".to_string(),
+            span
+        }),
+        Err(e) => return Err(RuntimeError::Io
+        {
+            message: e.to_string(),
+            span
+        })
+    };
+
+    return match read_value(&line, expected)
+    {
+        Some(v) => Ok(v),
+        None => Err(RuntimeError::InvalidInput
+        {
+            text: line,
+            expected: expected.clone(),
+            span
+        })
+    };
+}
+
+/// Writes the values on a single line separated by spaces, using the span of the print statement
+/// to report errors
+pub fn print_values(writer: &mut dyn Writer, values: &[VariableType], span: Span) -> Result<(), RuntimeError>
+{
+    // Write strings as their text, rather than as quoted literals
+    let text: Vec<String> = values
+        .iter()
+        .map(|v| match v
+        {
+            VariableType::String(v) => v.clone(),
+            v => v.to_string()
+        })
+        .collect();
+
+    return writer.write_line(&text.join(" ")).map_err(|e| RuntimeError::Io
+    {
+        message: e.to_string(),
+        span
+    });
+}
+
+/// Converts the text entered for an input statement into a value of the expected type, being
+/// true or false for booleans, or a number for numeric values. Integers are accepted for float
+/// values, and surrounding whitespace is ignored for all but string values, which take the
+/// whole line
+pub fn read_value(text: &str, expected: &VariableType) -> Option<VariableType>
+{
+    if let VariableType::String(_) = expected
+    {
+        return Some(VariableType::String(text.to_string()));
+    }
+
+    let text = text.trim();
+
+    return match expected
+    {
+        VariableType::Boolean(_) => match text
+        {
+            "true" => Some(VariableType::Boolean(true)),
+            "false" => Some(VariableType::Boolean(false)),
+            _ => None
+        },
+        VariableType::Integer(_) => text.parse::<i32>().ok().map(VariableType::Integer),
+        VariableType::Float(_) => text.parse::<f32>().ok().filter(|v| v.is_finite()).map(VariableType::Float),
+        VariableType::String(_) => Some(VariableType::String(text.to_string()))
+    };
+}
+
+/// Defines tests of the console input and output
+#[cfg(test)]
+mod tests
+{
+    use super::{Reader, Writer, read_value};
+    use crate::variable::VariableType;
+
+    /// Tests that lines are read without line endings until the input ends
+    #[test]
+    fn read_lines()
+    {
+        let mut input: &[u8] = b"1\r\ntwo\n\nlast";
+
+        assert_eq!(input.read_line().unwrap(), Some("1".to_string()));
+        assert_eq!(input.read_line().unwrap(), Some("two".to_string()));
+        assert_eq!(input.read_line().unwrap(), Some(String::new()));
+        assert_eq!(input.read_line().unwrap(), Some("last".to_string()));
+        assert_eq!(input.read_line().unwrap(), None);
+
+        let mut output: Vec<u8> = Vec::new();
+        output.write_line("a b").unwrap();
+        output.write_line("").unwrap();
+        assert_eq!(output, b"a b\n\n");
+    }
+
+    /// Tests that entered text is converted to the expected type of value
+    #[test]
+    fn read_values()
+    {
+        assert_eq!(read_value(" 12 ", &VariableType::Integer(0)), Some(VariableType::Integer(12)));
+        assert_eq!(read_value("-3", &VariableType::Float(0.0)), Some(VariableType::Float(-3.0)));
+        assert_eq!(read_value("2.5", &VariableType::Float(0.0)), Some(VariableType::Float(2.5)));
+        assert_eq!(read_value("true", &VariableType::Boolean(false)), Some(VariableType::Boolean(true)));
+        assert_eq!(read_value("2.5", &VariableType::Integer(0)), None);
+        assert_eq!(read_value("inf", &VariableType::Float(0.0)), None);
+        assert_eq!(read_value("1", &VariableType::Boolean(false)), None);
+        assert_eq!(read_value("", &VariableType::Integer(0)), None);
+    }
+}
@@ -0,0 +1,769 @@
+//! Provides a minimal JSON value, with a writer and a reader, used to exchange tokens and
+//! programs with external tools. Numbers keep their source text, so that integer and float
+//! values are written and read back without any loss of precision.
+
+/// Defines a JSON value
+#[derive(Debug, PartialEq, Clone)]
+pub enum Json
+{
+    Null,
+    Bool(bool),
+
+    /// Defines a number by its text, being a valid JSON number
+    Number(String),
+
+    String(String),
+    Array(Vec<Json>),
+
+    /// Defines an object by its fields, in the order they are written
+    Object(Vec<(String, Json)>)
+}
+
+/// Defines the errors that may be raised while reading JSON text, or reading a value in the
+/// expected schema
+#[derive(Debug, PartialEq, Clone)]
+pub enum JsonError
+{
+    /// Indicates that the text is not valid JSON
+    Syntax
+    {
+        expected: String,
+        offset: usize
+    },
+
+    /// Indicates that a value does not match the expected schema
+    Schema
+    {
+        expected: String,
+        path: String
+    }
+}
+
+impl JsonError
+{
+    /// Returns a schema error for the value at the path
+    pub fn schema(expected: &str, path: &str) -> JsonError
+    {
+        return JsonError::Schema
+        {
+            expected: expected.to_string(),
+            path: path.to_string()
+        };
+    }
+}
+
+impl std::fmt::Display for JsonError
+{
+    /// Writes a human-readable description of the JSON error
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return match self
+        {
+            JsonError::Syntax { expected, offset } => write!(f, "expected {0:} at offset {1:}", expected, offset),
+            JsonError::Schema { expected, path } => write!(f, "expected {0:} at {1:}", expected, path)
+        };
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// Defines the greatest number of arrays and objects that may enclose a value, so that deeply
+/// nested input is reported as an error rather than overflowing the stack. The limit is checked
+/// both when reading text and before writing values that are read back, such as programs
+pub const MAX_DEPTH: usize = 512;
+
+/// Returns the description of values within the depth limit, used for errors
+fn depth_limit() -> String
+{
+    return format!("at most {0:} nested arrays and objects", MAX_DEPTH);
+}
+
+impl Json
+{
+    /// Returns a number holding the integer
+    pub fn int(value: i32) -> Json
+    {
+        return Json::Number(value.to_string());
+    }
+
+    /// Returns a number holding the float, written with the fewest digits that read back as the
+    /// same value. Values that are not finite cannot be written as JSON numbers, and are null,
+    /// though float literals are always finite as larger values are rejected by the tokenizer
+    pub fn float(value: f32) -> Json
+    {
+        if !value.is_finite()
+        {
+            return Json::Null;
+        }
+
+        return Json::Number(value.to_string());
+    }
+
+    /// Returns an object with the fields, in order
+    pub fn object(fields: Vec<(&str, Json)>) -> Json
+    {
+        return Json::Object(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect());
+    }
+
+    /// Returns the value of the field of an object, if present
+    pub fn get(&self, key: &str) -> Option<&Json>
+    {
+        return match self
+        {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None
+        };
+    }
+
+    /// Returns the text of a string value
+    pub fn as_str(&self) -> Option<&str>
+    {
+        return match self
+        {
+            Json::String(v) => Some(v),
+            _ => None
+        };
+    }
+
+    /// Returns the value of a boolean
+    pub fn as_bool(&self) -> Option<bool>
+    {
+        return match self
+        {
+            Json::Bool(v) => Some(*v),
+            _ => None
+        };
+    }
+
+    /// Returns the value of a number that is an integer within the range of i32
+    pub fn as_i32(&self) -> Option<i32>
+    {
+        return match self
+        {
+            Json::Number(v) => v.parse().ok(),
+            _ => None
+        };
+    }
+
+    /// Returns the value of a number that is a non-negative integer
+    pub fn as_usize(&self) -> Option<usize>
+    {
+        return match self
+        {
+            Json::Number(v) => v.parse().ok(),
+            _ => None
+        };
+    }
+
+    /// Returns the value of a number as the nearest float, if it is within the range of finite
+    /// floats
+    pub fn as_f32(&self) -> Option<f32>
+    {
+        return match self
+        {
+            Json::Number(v) => v.parse().ok().filter(|v: &f32| v.is_finite()),
+            _ => None
+        };
+    }
+
+    /// Returns the items of an array
+    pub fn as_array(&self) -> Option<&[Json]>
+    {
+        return match self
+        {
+            Json::Array(v) => Some(v),
+            _ => None
+        };
+    }
+
+    /// Returns true if the value is an array or object holding another array or object
+    fn is_nested(&self) -> bool
+    {
+        return match self
+        {
+            Json::Array(items) => items.iter().any(|v| matches!(v, Json::Array(_) | Json::Object(_))),
+            Json::Object(fields) => fields.iter().any(|(_, v)| matches!(v, Json::Array(_) | Json::Object(_))),
+            _ => false
+        };
+    }
+
+    /// Checks that no array or object is enclosed by more than MAX_DEPTH others, so that the
+    /// written text may be read back. A schema error is returned at the path of the first array
+    /// or object past the limit
+    pub fn check_depth(&self) -> Result<(), JsonError>
+    {
+        return self.check_nested(&mut String::from("$"), 0);
+    }
+
+    /// Checks the depth of the value, which is enclosed by the depth number of arrays and
+    /// objects. The path is restored before returning
+    fn check_nested(&self, path: &mut String, depth: usize) -> Result<(), JsonError>
+    {
+        if !matches!(self, Json::Array(_) | Json::Object(_))
+        {
+            return Ok(());
+        }
+
+        if depth == MAX_DEPTH
+        {
+            return Err(JsonError::schema(&depth_limit(), path));
+        }
+
+        let length = path.len();
+
+        match self
+        {
+            Json::Array(items) => for (i, value) in items.iter().enumerate()
+            {
+                path.push_str(&format!("[{0:}]", i));
+                value.check_nested(path, depth + 1)?;
+                path.truncate(length);
+            },
+            Json::Object(fields) => for (key, value) in fields
+            {
+                path.push_str(&format!(".{0:}", key));
+                value.check_nested(path, depth + 1)?;
+                path.truncate(length);
+            },
+            _ => ()
+        }
+
+        return Ok(());
+    }
+
+    /// Provides the text of the value indented by two spaces for each level. Arrays and objects
+    /// holding only other values are written on a single line
+    pub fn pretty(&self) -> String
+    {
+        let mut output = String::new();
+        self.write_pretty(&mut output, 0);
+        return output;
+    }
+
+    /// Writes the indented text of the value, where the current line is at the depth
+    fn write_pretty(&self, output: &mut String, depth: usize)
+    {
+        if !self.is_nested()
+        {
+            match self
+            {
+                Json::Array(items) =>
+                {
+                    let items: Vec<String> = items.iter().map(|v| v.to_string()).collect();
+                    output.push_str(&format!("[{0:}]", items.join(", ")));
+                },
+                Json::Object(fields) =>
+                {
+                    let fields: Vec<String> = fields.iter().map(|(k, v)| format!("{0:}: {1:}", Json::quote(k), v)).collect();
+                    output.push_str(&format!("{{{0:}}}", fields.join(", ")));
+                },
+                _ => output.push_str(&self.to_string())
+            }
+
+            return;
+        }
+
+        let indent = "  ".repeat(depth + 1);
+
+        let (open, close, items): (char, char, Vec<(Option<&str>, &Json)>) = match self
+        {
+            Json::Array(items) => ('[', ']', items.iter().map(|v| (None, v)).collect()),
+            Json::Object(fields) => ('{', '}', fields.iter().map(|(k, v)| (Some(k.as_str()), v)).collect()),
+            _ => return
+        };
+
+        output.push(open);
+
+        for (i, (key, value)) in items.iter().enumerate()
+        {
+            output.push_str(if i == 0 { "\n" } else { ",\n" });
+            output.push_str(&indent);
+
+            if let Some(key) = key
+            {
+                output.push_str(&format!("{0:}: ", Json::quote(key)));
+            }
+
+            value.write_pretty(output, depth + 1);
+        }
+
+        output.push('\n');
+        output.push_str(&"  ".repeat(depth));
+        output.push(close);
+    }
+
+    /// Provides the text as a JSON string literal, escaping quotes, backslashes and control
+    /// characters
+    pub fn quote(text: &str) -> String
+    {
+        let mut output = String::with_capacity(text.len() + 2);
+        output.push('"');
+
+        for c in text.chars()
+        {
+            match c
+            {
+                '"' => output.push_str("\\\""),
+                '\\' => output.push_str("\\\\"),
+                '\n' => output.push_str("\\n"),
+                '\r' => output.push_str("\\r"),
+                '\t' => output.push_str("\\t"),
+                c if c.is_control() => output.push_str(&format!("\\u{0:04x}", c as u32)),
+                c => output.push(c)
+            }
+        }
+
+        output.push('"');
+        return output;
+    }
+
+    /// Reads a single JSON value from the text, which may be surrounded by whitespace
+    pub fn parse(text: &str) -> Result<Json, JsonError>
+    {
+        let mut reader = Reader
+        {
+            text,
+            offset: 0,
+            depth: 0
+        };
+
+        let value = reader.value()?;
+        reader.skip_whitespace();
+
+        if reader.offset < text.len()
+        {
+            return Err(reader.expected("end of input"));
+        }
+
+        return Ok(value);
+    }
+}
+
+impl std::fmt::Display for Json
+{
+    /// Writes the value as compact JSON text, without any whitespace
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return match self
+        {
+            Json::Null => write!(f, "null"),
+            Json::Bool(v) => write!(f, "{0:}", v),
+            Json::Number(v) => write!(f, "{0:}", v),
+            Json::String(v) => write!(f, "{0:}", Json::quote(v)),
+            Json::Array(items) =>
+            {
+                let items: Vec<String> = items.iter().map(|v| v.to_string()).collect();
+                write!(f, "[{0:}]", items.join(","))
+            },
+            Json::Object(fields) =>
+            {
+                let fields: Vec<String> = fields.iter().map(|(k, v)| format!("{0:}:{1:}", Json::quote(k), v)).collect();
+                write!(f, "{{{0:}}}", fields.join(","))
+            }
+        };
+    }
+}
+
+
+/// Defines the location of a reader within the JSON text
+struct Reader<'a>
+{
+    text: &'a str,
+    offset: usize,
+
+    /// Defines the number of arrays and objects enclosing the current location
+    depth: usize
+}
+
+impl Reader<'_>
+{
+    /// Returns a syntax error at the current location
+    fn expected(&self, expected: &str) -> JsonError
+    {
+        return JsonError::Syntax
+        {
+            expected: expected.to_string(),
+            offset: self.offset
+        };
+    }
+
+    /// Returns the next character without consuming it
+    fn peek(&self) -> Option<char>
+    {
+        return self.text[self.offset..].chars().next();
+    }
+
+    /// Consumes the text if it is next, returning true if it was found
+    fn consume(&mut self, text: &str) -> bool
+    {
+        if self.text[self.offset..].starts_with(text)
+        {
+            self.offset += text.len();
+            return true;
+        }
+
+        return false;
+    }
+
+    /// Skips any whitespace before the next value or punctuation
+    fn skip_whitespace(&mut self)
+    {
+        while let Some(c) = self.peek().filter(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+        {
+            self.offset += c.len_utf8();
+        }
+    }
+
+    /// Reads a single value, after any whitespace
+    fn value(&mut self) -> Result<Json, JsonError>
+    {
+        self.skip_whitespace();
+
+        return match self.peek()
+        {
+            Some('{') => self.nested(Reader::object),
+            Some('[') => self.nested(Reader::array),
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ if self.consume("null") => Ok(Json::Null),
+            _ if self.consume("true") => Ok(Json::Bool(true)),
+            _ if self.consume("false") => Ok(Json::Bool(false)),
+            _ => Err(self.expected("value"))
+        };
+    }
+
+    /// Reads an array or object with the function, failing if it would be enclosed by too many
+    /// other arrays and objects
+    fn nested(&mut self, read: fn(&mut Self) -> Result<Json, JsonError>) -> Result<Json, JsonError>
+    {
+        if self.depth == MAX_DEPTH
+        {
+            return Err(self.expected(&depth_limit()));
+        }
+
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+
+        return value;
+    }
+
+    /// Reads an object, starting from the opening brace
+    fn object(&mut self) -> Result<Json, JsonError>
+    {
+        self.consume("{");
+        let mut fields = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.consume("}")
+        {
+            return Ok(Json::Object(fields));
+        }
+
+        loop
+        {
+            self.skip_whitespace();
+
+            if self.peek() != Some('"')
+            {
+                return Err(self.expected("field name"));
+            }
+
+            let key = self.string()?;
+            self.skip_whitespace();
+
+            if !self.consume(":")
+            {
+                return Err(self.expected("':'"));
+            }
+
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+
+            if self.consume("}")
+            {
+                return Ok(Json::Object(fields));
+            }
+
+            if !self.consume(",")
+            {
+                return Err(self.expected("',' or '}'"));
+            }
+        }
+    }
+
+    /// Reads an array, starting from the opening bracket
+    fn array(&mut self) -> Result<Json, JsonError>
+    {
+        self.consume("[");
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.consume("]")
+        {
+            return Ok(Json::Array(items));
+        }
+
+        loop
+        {
+            items.push(self.value()?);
+            self.skip_whitespace();
+
+            if self.consume("]")
+            {
+                return Ok(Json::Array(items));
+            }
+
+            if !self.consume(",")
+            {
+                return Err(self.expected("',' or ']'"));
+            }
+        }
+    }
+
+    /// Reads a number, checking that it matches the JSON number grammar
+    fn number(&mut self) -> Result<Json, JsonError>
+    {
+        let start = self.offset;
+
+        // Reads a sequence of digits, returning the number read
+        fn digits(reader: &mut Reader) -> usize
+        {
+            let start = reader.offset;
+
+            while reader.peek().is_some_and(|c| c.is_ascii_digit())
+            {
+                reader.offset += 1;
+            }
+
+            return reader.offset - start;
+        }
+
+        self.consume("-");
+
+        // Read the whole part, which may only start with zero if it is a single digit
+        if self.consume("0")
+        {
+            if self.peek().is_some_and(|c| c.is_ascii_digit())
+            {
+                return Err(self.expected("'.', 'e' or end of number"));
+            }
+        }
+        else if digits(self) == 0
+        {
+            return Err(self.expected("digit"));
+        }
+
+        if self.consume(".") && digits(self) == 0
+        {
+            return Err(self.expected("digit"));
+        }
+
+        if self.consume("e") || self.consume("E")
+        {
+            if !self.consume("+")
+            {
+                self.consume("-");
+            }
+
+            if digits(self) == 0
+            {
+                return Err(self.expected("digit"));
+            }
+        }
+
+        return Ok(Json::Number(self.text[start..self.offset].to_string()));
+    }
+
+    /// Reads a string, starting from the opening quote, replacing each escape sequence
+    fn string(&mut self) -> Result<String, JsonError>
+    {
+        self.consume("\"");
+        let mut output = String::new();
+
+        loop
+        {
+            let c = match self.peek()
+            {
+                Some(c) => c,
+                None => return Err(self.expected("'\"'"))
+            };
+
+            if c == '"'
+            {
+                self.offset += 1;
+                return Ok(output);
+            }
+
+            if c.is_control()
+            {
+                return Err(self.expected("escape sequence for control character"));
+            }
+
+            if c != '\\'
+            {
+                output.push(c);
+                self.offset += c.len_utf8();
+                continue;
+            }
+
+            self.offset += 1;
+
+            let escaped = match self.peek()
+            {
+                Some('"') => '"',
+                Some('\\') => '\\',
+                Some('/') => '/',
+                Some('b') => '\u{8}',
+                Some('f') => '\u{c}',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('u') =>
+                {
+                    self.offset += 1;
+                    let unit = self.hex()?;
+
+                    // Join a surrogate pair into a single character
+                    let code = if (0xd800..0xdc00).contains(&unit)
+                    {
+                        if !self.consume("\\u")
+                        {
+                            return Err(self.expected("low surrogate"));
+                        }
+
+                        let low = self.hex()?;
+
+                        if !(0xdc00..0xe000).contains(&low)
+                        {
+                            return Err(self.expected("low surrogate"));
+                        }
+
+                        0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00)
+                    }
+                    else
+                    {
+                        unit
+                    };
+
+                    match char::from_u32(code)
+                    {
+                        Some(c) => output.push(c),
+                        None => return Err(self.expected("valid character"))
+                    }
+
+                    continue;
+                },
+                _ => return Err(self.expected("escape sequence"))
+            };
+
+            output.push(escaped);
+            self.offset += 1;
+        }
+    }
+
+    /// Reads the four hexadecimal digits of a unicode escape
+    fn hex(&mut self) -> Result<u32, JsonError>
+    {
+        let digits = self.text.get(self.offset..self.offset + 4).filter(|d| d.chars().all(|c| c.is_ascii_hexdigit()));
+
+        return match digits.and_then(|d| u32::from_str_radix(d, 16).ok())
+        {
+            Some(v) =>
+            {
+                self.offset += 4;
+                Ok(v)
+            },
+            None => Err(self.expected("four hexadecimal digits"))
+        };
+    }
+}
+
+/// Defines tests of the JSON reader and writer
+#[cfg(test)]
+mod tests
+{
+    use super::{Json, JsonError, MAX_DEPTH};
+
+    /// Tests that values are written as compact or indented text and read back unchanged
+    #[test]
+    fn write_read()
+    {
+        let value = Json::object(vec!{
+            ("name", Json::String(String::from("a \"b\"\n\u{1}é"))),
+            ("values", Json::Array(vec!{Json::int(-3), Json::float(0.1), Json::Bool(true), Json::Null})),
+            ("nested", Json::Array(vec!{Json::object(vec!{("x", Json::Array(Vec::new()))})}))
+        });
+
+        let compact = value.to_string();
+        assert_eq!(compact, "{\"name\":\"a \\\"b\\\"\\n\\u0001é\",\"values\":[-3,0.1,true,null],\"nested\":[{\"x\":[]}]}");
+
+        let pretty = value.pretty();
+        assert_eq!(
+            pretty,
+            "{\n  \"name\": \"a \\\"b\\\"\\n\\u0001é\",\n  \"values\": [-3, 0.1, true, null],\n  \"nested\": [\n    {\n      \"x\": []\n    }\n  ]\n}");
+
+        assert_eq!(Json::parse(&compact).unwrap(), value);
+        assert_eq!(Json::parse(&pretty).unwrap(), value);
+        assert_eq!(Json::parse("\"\\ud83d\\ude00 \\/\"").unwrap(), Json::String(String::from("😀 /")));
+        assert_eq!(Json::parse(" 1.5e-3 ").unwrap().as_f32(), Some(0.0015));
+        assert_eq!(Json::float(f32::NAN), Json::Null);
+        assert_eq!(Json::parse("1e39").unwrap().as_f32(), None);
+    }
+
+    /// Tests that invalid text is reported with the location of the error
+    #[test]
+    fn read_invalid()
+    {
+        let offset = |text: &str| match Json::parse(text)
+        {
+            Err(JsonError::Syntax { offset, .. }) => offset,
+            v => panic!("expected syntax error, found {0:?}", v)
+        };
+
+        assert_eq!(offset(""), 0);
+        assert_eq!(offset("[1, 2"), 5);
+        assert_eq!(offset("{\"a\" 1}"), 5);
+        assert_eq!(offset("01"), 1);
+        assert_eq!(offset("1."), 2);
+        assert_eq!(offset("[1] x"), 4);
+        assert_eq!(offset("\"\\x\""), 2);
+        assert_eq!(offset("\"\\ud800\""), 7);
+        assert_eq!(offset("nul"), 0);
+    }
+
+    /// Tests that deeply nested values are reported as an error rather than overflowing the stack
+    #[test]
+    fn read_nested()
+    {
+        assert_eq!(
+            Json::parse(&"[".repeat(100_000)),
+            Err(JsonError::Syntax { expected: String::from("at most 512 nested arrays and objects"), offset: 512 }));
+
+        assert!(Json::parse(&format!("{0:}{1:}", "[".repeat(512), "]".repeat(512))).is_ok());
+        assert!(Json::parse(&format!("{0:}1{1:}", "{\"a\":".repeat(512), "}".repeat(512))).is_ok());
+        assert_eq!(
+            Json::parse(&format!("{0:}{1:}", "[".repeat(513), "]".repeat(513))).err().map(|e| e.to_string()),
+            Some(String::from("expected at most 512 nested arrays and objects at offset 512")));
+    }
+
+    /// Tests that values are checked against the same depth limit as the reader before they are
+    /// written
+    #[test]
+    fn write_nested()
+    {
+        let nest = |depth: usize| (0..depth).fold(Json::Null, |v, _| Json::object(vec!{("a", Json::Array(vec!{v}))}));
+
+        let value = nest(MAX_DEPTH / 2);
+        assert_eq!(value.check_depth(), Ok(()));
+        assert_eq!(Json::parse(&value.to_string()), Ok(value));
+
+        let value = Json::Array(vec!{Json::Null, nest(MAX_DEPTH / 2)});
+        assert_eq!(
+            value.check_depth(),
+            Err(JsonError::schema("at most 512 nested arrays and objects", &format!("$[1]{0:}.a", ".a[0]".repeat(255)))));
+        assert!(Json::parse(&value.to_string()).is_err());
+    }
+}
@@ -1,6 +1,11 @@
 /*
 ZBasic Grammar
 
+Comments are skipped by the tokenizer and may appear between any tokens:
+    // to the end of the line
+    /* to the matching close */, which may be nested
+    REM to the end of the line, as the first word of a line
+
 Program ->
     <>
     <Statement>
@@ -11,26 +16,64 @@ Statement ->
     if (<BoolExpr>) { <Statement> }
     if (<BoolExpr>) { <Statement> } else { <Statement> }
     while (<BoolExpr>) { <Statement> }
+    for (<ForAssign>; <BoolExpr>; <ForAssign>) { <Statement> }
     Var = <Expr>;
+    Var <CompoundOp> <NumExpr>;
+    Var += <StrExpr>;    (appends to a string variable)
+    print;
+    print <PrintArgs>;
+    input Var;    (reads a value of the variable type, or a float for a new variable)
+    <Expr>;
+
+PrintArgs ->    (values are written on a single line, separated by spaces)
+    <Expr>
+    <Expr>, <PrintArgs>
+
+ForAssign ->
+    <>
+    Var = <Expr>
+    Var <CompoundOp> <NumExpr>
 
 Expr ->
     <NumExpr>
     <BoolExpr>
+    <StrExpr>
 
 NumExpr ->
     Float
     Int
-    -Float
-    -Int
-    <NumExpr> <NumericOp> <NumExpr>
+    Var    (assigned a numeric value earlier in the program)
+    <Call>    (to a function returning a numeric value)
+    -<NumExpr>    (binds tighter than any NumericOp)
+    <NumExpr> <NumericOp> <NumExpr>    (* and / bind tighter than + and -, all left-associative)
     (<NumExpr>)
 
 BoolExpr ->
     Bool
-    !<BoolExpr>
-    <BoolExpr> <BoolOp> <BoolExpr>
+    Var    (assigned a boolean value earlier in the program)
+    <Call>    (to a function returning a boolean value)
+    <NumExpr> <CompareOp> <NumExpr>
+    <StrExpr> == <StrExpr>
+    <StrExpr> != <StrExpr>
+    !<BoolExpr>    (binds tighter than any BoolOp)
+    <BoolExpr> <BoolOp> <BoolExpr>    (&& binds tighter than ||, all left-associative)
     (<BoolExpr>)
 
+StrExpr ->
+    Str    (a double-quoted literal, with escapes \n, \t, \", \\ and \u{...})
+    Var    (assigned a string value earlier in the program)
+    <Call>    (to a function returning a string value)
+    <StrExpr> + <StrExpr>    (left-associative)
+    (<StrExpr>)
+
+Call ->    (the function is registered by the host program before parsing)
+    Var()
+    Var(<Args>)
+
+Args ->
+    <Expr>
+    <Expr>, <Args>
+
 NumericOp ->
     +
     -
@@ -40,10 +83,56 @@ NumericOp ->
 BoolOp ->
     &&
     ||
+
+CompoundOp ->    (Var += <NumExpr> is equivalent to Var = Var + (<NumExpr>))
+    +=
+    -=
+    *=
+    /=
+
+CompareOp ->
+    ==
+    !=
+    <
+    >
+    <=
+    >=
  */
 
-mod boolean;
-mod expression;
-mod numeric;
-mod program;
-mod statement;
+pub mod boolean;
+pub mod call;
+pub mod error;
+pub mod expression;
+pub mod numeric;
+pub mod operator;
+pub mod program;
+pub mod statement;
+pub mod string;
+pub mod visitor;
+
+use crate::token::TokenList;
+use error::ParseError;
+
+/// Defines the greatest number of parenthesized values, negations, calls and blocks that may
+/// enclose a value, so that deeply nested source is reported as an error rather than
+/// overflowing the stack
+pub const MAX_NESTING: usize = 32;
+
+/// Parses a value nested within another with the function, such as the contents of parentheses
+/// or a block. An error is raised at the next token if the value would be enclosed by more than
+/// MAX_NESTING others
+pub fn nested<T>(tokens: &mut TokenList, parse: impl FnOnce(&mut TokenList) -> Result<T, ParseError>) -> Result<T, ParseError>
+{
+    let depth = tokens.depth();
+
+    if depth == MAX_NESTING
+    {
+        return Err(ParseError::NestingLimit { span: tokens.peek_span() });
+    }
+
+    tokens.set_depth(depth + 1);
+    let result = parse(tokens);
+    tokens.set_depth(depth);
+
+    return result;
+}
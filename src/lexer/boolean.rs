@@ -1,77 +1,527 @@
+use crate::function::Functions;
+use crate::span::Span;
 use crate::token::{Token, TokenList};
+use crate::token::symbol::Operator;
 
+use super::call::CallExpression;
+use super::error::ParseError;
+use super::numeric::{NumericExpression, NumericExpressionDataType};
+use super::operator::OperatorRole;
+use super::string::StringExpression;
+use crate::variable::{Variable, VariableType};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
 pub struct OperationBoolExpression
 {
-    op: BooleanOpType,
-    a: Box<BoolExpression>,
-    b: Box<BoolExpression>
+    pub op: BooleanOpType,
+    pub a: Box<BoolExpression>,
+    pub b: Box<BoolExpression>
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ComparisonBoolExpression
+{
+    pub op: ComparisonOpType,
+    pub a: NumericExpression,
+    pub b: NumericExpression
+}
+
+impl ComparisonBoolExpression
+{
+    /// Returns the data type that both values are compared as, promoting to a float comparison
+    /// if either value is a float
+    pub fn operand_type(&self) -> NumericExpressionDataType
+    {
+        return NumericExpressionDataType::promote(self.a.data_type, self.b.data_type);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StringComparisonBoolExpression
+{
+    pub op: ComparisonOpType,
+    pub a: StringExpression,
+    pub b: StringExpression
 }
 
+#[derive(Debug, PartialEq)]
 pub enum BoolExpressionType
 {
     Operation(OperationBoolExpression),
-    Constant(bool)
+    Comparison(ComparisonBoolExpression),
+    StringComparison(StringComparisonBoolExpression),
+    Constant(bool),
+    Variable(String),
+    Call(CallExpression)
 }
 
+#[derive(Debug, PartialEq)]
 pub struct BoolExpression
 {
-    inverted: bool,
-    value: BoolExpressionType
+    pub inverted: bool,
+    pub value: BoolExpressionType,
+    pub span: Span
 }
 
-pub enum BooleanOpType
+impl BoolExpression
 {
-    And,
-    Or
-}
+    /// Parses a boolean expression from the token list, applying operator precedence such that
+    /// and operations are grouped before or operations
+    pub fn parse(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<BoolExpression, ParseError>
+    {
+        return BoolExpression::parse_binary(tokens, variables, functions, 0);
+    }
 
-impl BooleanOpType
-{
-    pub fn parse(tokens: &mut TokenList) -> Option<BooleanOpType>
+    /// Parses a boolean expression using precedence climbing, only consuming operations with a
+    /// binding power of at least the provided minimum
+    fn parse_binary(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions, min_power: u8) -> Result<BoolExpression, ParseError>
     {
-        // Check to see if a token is available
-        if !tokens.available()
+        // Parse the left-hand value
+        let mut a = BoolExpression::parse_value(tokens, variables, functions)?;
+
+        // Continue to read operations while they bind at least as tightly as the minimum
+        while let Some(op) = BooleanOpType::peek(tokens)
         {
-            return None;
+            let power = op.binding_power();
+
+            if power < min_power
+            {
+                break;
+            }
+
+            tokens.pop();
+
+            // Parse the right-hand side with a higher minimum to provide left-associativity
+            let b = BoolExpression::parse_binary(tokens, variables, functions, power + 1)?;
+            let span = a.span.merge(&b.span);
+
+            a = BoolExpression
+            {
+                inverted: false,
+                value: BoolExpressionType::Operation(OperationBoolExpression
+                {
+                    op,
+                    a: Box::new(a),
+                    b: Box::new(b)
+                }),
+                span
+            };
         }
 
-        // Peek the next token
-        let next = tokens.peek().unwrap();
+        return Ok(a);
+    }
 
-        // Iterate over the possible operations
-        let op_type: Option<BooleanOpType> = match next
+    /// Parses a single boolean value, being a constant, a variable, a function call, an inverted
+    /// value, a parenthesized expression, or a comparison between two numeric or string expressions.
+    /// Inversion applies to the following value only, binding tighter than any boolean operation
+    fn parse_value(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<BoolExpression, ParseError>
+    {
+        // Save the starting location of the value
+        let start_span = tokens.peek_span();
+
+        // Check for an inverted value
+        if tokens.consume_operator(Operator::Not)
+        {
+            let mut expr = super::nested(tokens, |tokens| BoolExpression::parse_value(tokens, variables, functions))?;
+            expr.inverted = !expr.inverted;
+            expr.span = start_span.merge(&expr.span);
+            return Ok(expr);
+        }
+
+        // Check for a constant value
+        if let Some(Token::Bool(v)) = tokens.peek()
+        {
+            tokens.pop();
+            return Ok(BoolExpression
+            {
+                inverted: false,
+                value: BoolExpressionType::Constant(v),
+                span: start_span
+            });
+        }
+
+        // Check for a call to a function returning a boolean value, leaving other calls to be
+        // read as part of a comparison
+        if let Some(name) = CallExpression::peek(tokens)
+        {
+            if let Some(VariableType::Boolean(_)) = functions.signature(&name).map(|s| &s.result)
+            {
+                let (call, span) = CallExpression::parse(tokens, variables, functions)?;
+
+                return Ok(BoolExpression
+                {
+                    inverted: false,
+                    value: BoolExpressionType::Call(call),
+                    span
+                });
+            }
+        }
+        // Check for a boolean variable, leaving numeric variables, and boolean variables followed by
+        // a numeric or comparison operator, to be read as part of a comparison
+        else if let Some(Token::Variable(name)) = tokens.peek()
         {
-            Token::Operator(s) =>
+            let operand = BoolExpression::continues_operand(tokens.peek_nth(1));
+
+            match variables.get(&name).map(|v| &v.vartype)
+            {
+                Some(VariableType::Boolean(_)) if !operand =>
                 {
-                    // Check if the token matches the possible operation
-                    if s == "&&"
+                    tokens.pop();
+                    return Ok(BoolExpression
                     {
-                        Some(BooleanOpType::And)
-                    }
-                    else if s == "||"
+                        inverted: false,
+                        value: BoolExpressionType::Variable(name),
+                        span: start_span
+                    });
+                },
+                Some(_) => (),
+                None => return Err(ParseError::UnassignedVariable { name, span: start_span })
+            }
+        }
+
+        // Save the starting location, as a parenthesis may start either a boolean expression or
+        // the numeric expression of a comparison
+        let start = tokens.position();
+
+        let paren_err = if tokens.consume_operator(Operator::LeftParen)
+        {
+            match super::nested(tokens, |tokens| BoolExpression::parse(tokens, variables, functions))
+            {
+                Ok(mut expr) if tokens.consume_operator(Operator::RightParen) =>
+                {
+                    expr.span = tokens.span_from(start_span);
+                    return Ok(expr);
+                },
+                Ok(_) => ParseError::expected("')'", tokens),
+                Err(e) => e
+            }
+        }
+        else
+        {
+            ParseError::expected("boolean expression", tokens)
+        };
+        let paren_end = tokens.position();
+
+        // Rewind and attempt to parse as a comparison
+        tokens.set_position(start);
+
+        return match BoolExpression::parse_comparison(tokens, variables, functions)
+        {
+            Ok(expr) => Ok(expr),
+            Err(_) if paren_end > tokens.position() => Err(paren_err),
+            Err(e) =>
+            {
+                // Read a variable that is not followed by a numeric or comparison operator as a
+                // boolean variable, leaving its type for the type checker to report
+                let end = tokens.position();
+                let operator = BoolExpression::continues_operand(tokens.peek());
+                tokens.set_position(start);
+
+                match tokens.pop()
+                {
+                    Some(Token::Variable(name)) if end == start + 1 && !operator => Ok(BoolExpression
                     {
-                        Some(BooleanOpType::Or)
-                    }
-                    else
+                        inverted: false,
+                        value: BoolExpressionType::Variable(name),
+                        span: start_span
+                    }),
+                    _ =>
                     {
-                        None
+                        tokens.set_position(end);
+                        Err(e)
                     }
-                },
+                }
+            }
+        };
+    }
+
+    /// Returns true if the token is a numeric or comparison operator, continuing the operand of a
+    /// comparison
+    fn continues_operand(token: Option<Token>) -> bool
+    {
+        return match token
+        {
+            Some(Token::Operator(op)) => matches!(OperatorRole::of(op), OperatorRole::Numeric(_) | OperatorRole::Comparison(_)),
+            _ => false
+        };
+    }
+
+    /// Parses a comparison between two numeric expressions, or an equality comparison between
+    /// two string expressions if a string value is next
+    fn parse_comparison(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<BoolExpression, ParseError>
+    {
+        if StringExpression::starts(tokens, variables, functions)
+        {
+            return BoolExpression::parse_string_comparison(tokens, variables, functions);
+        }
+
+        let a = NumericExpression::parse(tokens, variables, functions)?;
+
+        let op = match ComparisonOpType::parse(tokens)
+        {
+            Some(op) => op,
+            None => return Err(ParseError::expected("comparison operator", tokens))
+        };
+
+        let b = NumericExpression::parse(tokens, variables, functions)?;
+        let span = a.span.merge(&b.span);
+
+        return Ok(BoolExpression
+        {
+            inverted: false,
+            value: BoolExpressionType::Comparison(ComparisonBoolExpression
+            {
+                op,
+                a,
+                b
+            }),
+            span
+        });
+    }
+
+    /// Parses an equality comparison between two string expressions
+    fn parse_string_comparison(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<BoolExpression, ParseError>
+    {
+        let a = StringExpression::parse(tokens, variables, functions)?;
+
+        let op = match tokens.peek()
+        {
+            Some(Token::Operator(Operator::Equal)) => ComparisonOpType::Equal,
+            Some(Token::Operator(Operator::NotEqual)) => ComparisonOpType::NotEqual,
+            _ => return Err(ParseError::expected("'==' or '!='", tokens))
+        };
+        tokens.pop();
+
+        let b = StringExpression::parse(tokens, variables, functions)?;
+        let span = a.span.merge(&b.span);
+
+        return Ok(BoolExpression
+        {
+            inverted: false,
+            value: BoolExpressionType::StringComparison(StringComparisonBoolExpression
+            {
+                op,
+                a,
+                b
+            }),
+            span
+        });
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BooleanOpType
+{
+    And,
+    Or
+}
+
+impl BooleanOpType
+{
+    /// Defines the binding power of each boolean operation. Operations with a higher binding
+    /// power are grouped first, and all are left-associative
+    const BINDINGS: [(BooleanOpType, u8); 2] = [
+        (BooleanOpType::Or, 10),
+        (BooleanOpType::And, 20)
+    ];
+
+    /// Returns the binding power of the operation
+    pub fn binding_power(&self) -> u8
+    {
+        return BooleanOpType::BINDINGS
+            .iter()
+            .find(|(op, _)| op == self)
+            .map(|(_, power)| *power)
+            .unwrap();
+    }
+
+    /// Returns the boolean operation associated with the next token, if any, without removing it from the list
+    pub fn peek(tokens: &TokenList) -> Option<BooleanOpType>
+    {
+        return match tokens.peek()
+        {
+            Some(Token::Operator(op)) => match OperatorRole::of(op)
+            {
+                OperatorRole::Boolean(v) => Some(v),
+                _ => None
+            },
             _ => None
         };
+    }
+}
 
-        // Return the result
-        return match op_type
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ComparisonOpType
+{
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual
+}
+
+impl ComparisonOpType
+{
+    /// Returns the comparison associated with the next token, if any, removing it from the list
+    pub fn parse(tokens: &mut TokenList) -> Option<ComparisonOpType>
+    {
+        let op_type = match tokens.peek()
         {
-            None => None,
-            Some(op) =>
-                {
-                    // Pop the current token
-                    tokens.pop();
+            Some(Token::Operator(op)) => match OperatorRole::of(op)
+            {
+                OperatorRole::Comparison(v) => Some(v),
+                _ => None
+            },
+            _ => None
+        };
+
+        if op_type.is_some()
+        {
+            tokens.pop();
+        }
+
+        return op_type;
+    }
+
+    /// Compares the two values using the current comparison
+    pub fn compare<T: PartialOrd>(&self, a: T, b: T) -> bool
+    {
+        return match self
+        {
+            ComparisonOpType::Equal => a == b,
+            ComparisonOpType::NotEqual => a != b,
+            ComparisonOpType::Less => a < b,
+            ComparisonOpType::Greater => a > b,
+            ComparisonOpType::LessEqual => a <= b,
+            ComparisonOpType::GreaterEqual => a >= b
+        };
+    }
+}
+
+/// Defines tests of the boolean expression parser
+#[cfg(test)]
+mod tests
+{
+    use super::{BoolExpression, BoolExpressionType, BooleanOpType, ComparisonOpType};
+    use crate::lexer::numeric::NumericExpressionDataType;
+    use crate::token::Token;
+    use crate::function::Functions;
+    use std::collections::HashMap;
+
+    /// Parses the input as a boolean expression, ensuring that all tokens are consumed
+    fn parse(input: &str) -> BoolExpression
+    {
+        let mut tokens = Token::tokenize(input).unwrap();
+        let expr = BoolExpression::parse(&mut tokens, &HashMap::new(), &Functions::new()).unwrap();
+        assert!(!tokens.available());
+        return expr;
+    }
+
+    /// Tests that each comparison operator is parsed between two numeric expressions
+    #[test]
+    fn parse_comparison()
+    {
+        let cases = [
+            ("1 == 2", ComparisonOpType::Equal),
+            ("1 != 2", ComparisonOpType::NotEqual),
+            ("1 < 2", ComparisonOpType::Less),
+            ("1 > 2", ComparisonOpType::Greater),
+            ("1 <= 2", ComparisonOpType::LessEqual),
+            ("1 >= 2", ComparisonOpType::GreaterEqual)
+        ];
+
+        for (input, op) in cases.iter()
+        {
+            match parse(input).value
+            {
+                BoolExpressionType::Comparison(c) => assert_eq!(c.op, *op),
+                _ => panic!("expected comparison for {0:}", input)
+            }
+        }
+    }
 
-                    // Return the result
-                    Some(op)
+    /// Tests that comparisons combine with parentheses and boolean operations
+    #[test]
+    fn parse_nested_comparison()
+    {
+        match parse("(1 + 2) * 3 < 4 && ((2) >= 1.5 || !(3 == 3))").value
+        {
+            BoolExpressionType::Operation(op) =>
+            {
+                assert_eq!(op.op, BooleanOpType::And);
+                assert!(matches!(op.a.value, BoolExpressionType::Comparison(_)));
+                assert!(matches!(op.b.value, BoolExpressionType::Operation(_)));
+            },
+            _ => panic!("expected operation")
+        }
+
+        let mut tokens = Token::tokenize("1 + 2").unwrap();
+        assert!(BoolExpression::parse(&mut tokens, &HashMap::new(), &Functions::new()).is_err());
+    }
+
+    /// Tests that and binds tighter than or, with inversion applying to a single value
+    #[test]
+    fn parse_precedence()
+    {
+        fn grouping(expr: &BoolExpression) -> String
+        {
+            let value = match &expr.value
+            {
+                BoolExpressionType::Constant(v) => v.to_string(),
+                BoolExpressionType::Variable(name) => name.clone(),
+                BoolExpressionType::Call(call) => format!("{0:}(..)", call.name),
+                BoolExpressionType::Comparison(_) | BoolExpressionType::StringComparison(_) => String::from("cmp"),
+                BoolExpressionType::Operation(op) =>
+                {
+                    let symbol = match op.op
+                    {
+                        BooleanOpType::And => "&&",
+                        BooleanOpType::Or => "||"
+                    };
+                    format!("({0:} {1:} {2:})", grouping(&op.a), symbol, grouping(&op.b))
                 }
+            };
+
+            return if expr.inverted
+            {
+                format!("!{0:}", value)
+            }
+            else
+            {
+                value
+            };
         }
+
+        assert_eq!(grouping(&parse("true && false || true")), "((true && false) || true)");
+        assert_eq!(grouping(&parse("true || false && true")), "(true || (false && true))");
+        assert_eq!(grouping(&parse("true || false || true")), "((true || false) || true)");
+        assert_eq!(grouping(&parse("!(true && false)")), "!(true && false)");
+        assert_eq!(grouping(&parse("!true && false")), "(!true && false)");
+        assert_eq!(grouping(&parse("!!true")), "true");
+        assert_eq!(grouping(&parse("!1 < 2 || -1 > 2")), "(!cmp || cmp)");
+    }
+
+    /// Tests that mixed integer and float comparisons are promoted to float comparisons
+    #[test]
+    fn comparison_operand_type()
+    {
+        let check = |input: &str, data_type: NumericExpressionDataType|
+        {
+            match parse(input).value
+            {
+                BoolExpressionType::Comparison(c) => assert_eq!(c.operand_type(), data_type),
+                _ => panic!("expected comparison")
+            }
+        };
+
+        check("1 < 2", NumericExpressionDataType::Int);
+        check("1 < 2.0", NumericExpressionDataType::Float);
+        check("1.5 < 2", NumericExpressionDataType::Float);
+
+        assert!(ComparisonOpType::Less.compare(1.0, 1.5));
+        assert!(ComparisonOpType::GreaterEqual.compare(2, 2));
+        assert!(!ComparisonOpType::NotEqual.compare(3, 3));
     }
 }
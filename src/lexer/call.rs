@@ -0,0 +1,91 @@
+use crate::function::{Functions, Signature};
+use crate::span::Span;
+use crate::token::{Token, TokenList};
+use crate::token::symbol::Operator;
+
+use super::error::ParseError;
+use super::expression::Expression;
+use crate::variable::Variable;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+pub struct CallExpression
+{
+    /// Defines the name of the called host function
+    pub name: String,
+
+    /// Defines the argument expressions, in order
+    pub args: Vec<Expression>,
+
+    /// Defines the signature of the function when the call was parsed
+    pub signature: Signature
+}
+
+impl CallExpression
+{
+    /// Returns the name of the called function if the next tokens start a call, being a name
+    /// followed by an opening parenthesis
+    pub fn peek(tokens: &TokenList) -> Option<String>
+    {
+        return match (tokens.peek(), tokens.peek_nth(1))
+        {
+            (Some(Token::Variable(name)), Some(Token::Operator(Operator::LeftParen))) => Some(name),
+            _ => None
+        };
+    }
+
+    /// Parses a call to a registered function, returning the call along with its source
+    /// location. Each argument may be any expression, and is checked against the signature by
+    /// the type checker
+    pub fn parse(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<(CallExpression, Span), ParseError>
+    {
+        let start = tokens.peek_span();
+
+        let name = match CallExpression::peek(tokens)
+        {
+            Some(name) => name,
+            None => return Err(ParseError::expected("function call", tokens))
+        };
+
+        // Take the signature from the registered function
+        let signature = match functions.signature(&name)
+        {
+            Some(s) => s.clone(),
+            None => return Err(ParseError::UnknownFunction { name, span: start })
+        };
+
+        // Pop the name and opening parenthesis
+        tokens.pop();
+        tokens.pop();
+
+        // Read the comma-separated arguments until the closing parenthesis
+        let mut args = Vec::new();
+
+        if !tokens.consume_operator(Operator::RightParen)
+        {
+            loop
+            {
+                args.push(super::nested(tokens, |tokens| Expression::parse(tokens, variables, functions))?);
+
+                if tokens.consume_operator(Operator::RightParen)
+                {
+                    break;
+                }
+
+                if !tokens.consume_operator(Operator::Comma)
+                {
+                    return Err(ParseError::expected("',' or ')'", tokens));
+                }
+            }
+        }
+
+        let call = CallExpression
+        {
+            name,
+            args,
+            signature
+        };
+
+        return Ok((call, tokens.span_from(start)));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::span::Span;
+use crate::token::{Token, TokenList};
+
+use super::MAX_NESTING;
+
+/// Defines the errors that may be raised while parsing a token list into a program
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseError
+{
+    /// Indicates that a token was found that does not fit the grammar at the current location
+    UnexpectedToken
+    {
+        expected: String,
+        found: Token,
+        span: Span
+    },
+
+    /// Indicates that the token list ended while more tokens were expected
+    UnexpectedEnd
+    {
+        expected: String,
+        span: Span
+    },
+
+    /// Indicates the semantic error of a variable being read before any assignment to it
+    /// earlier in the program
+    UnassignedVariable
+    {
+        name: String,
+        span: Span
+    },
+
+    /// Indicates the semantic error of a call to a function that has not been registered
+    UnknownFunction
+    {
+        name: String,
+        span: Span
+    },
+
+    /// Indicates that an integer literal is out of range once any negation before it is applied
+    IntegerOverflow
+    {
+        text: String,
+        span: Span
+    },
+
+    /// Indicates that a value or block is enclosed by more than the allowed number of others
+    NestingLimit
+    {
+        span: Span
+    }
+}
+
+impl ParseError
+{
+    /// Creates an error for the next token in the list, which did not match the expected value
+    pub fn expected(expected: &str, tokens: &TokenList) -> ParseError
+    {
+        let span = tokens.peek_span();
+
+        return match tokens.peek()
+        {
+            Some(found) => ParseError::UnexpectedToken
+            {
+                expected: expected.to_string(),
+                found,
+                span
+            },
+            None => ParseError::UnexpectedEnd
+            {
+                expected: expected.to_string(),
+                span
+            }
+        };
+    }
+
+    /// Returns the source location associated with the error
+    pub fn span(&self) -> Span
+    {
+        return match self
+        {
+            ParseError::UnexpectedToken { span, .. } => *span,
+            ParseError::UnexpectedEnd { span, .. } => *span,
+            ParseError::UnassignedVariable { span, .. } => *span,
+            ParseError::UnknownFunction { span, .. } => *span,
+            ParseError::IntegerOverflow { span, .. } => *span,
+            ParseError::NestingLimit { span } => *span
+        };
+    }
+}
+
+impl std::fmt::Display for ParseError
+{
+    /// Writes a human-readable description of the parse error
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return match self
+        {
+            ParseError::UnexpectedToken { expected, found, span } => write!(f, "expected {0:}, found {1:} at {2:}", expected, found, span),
+            ParseError::UnexpectedEnd { expected, span } => write!(f, "expected {0:}, found end of input at {1:}", expected, span),
+            ParseError::UnassignedVariable { name, span } => write!(f, "variable {0:} is used before being assigned at {1:}", name, span),
+            ParseError::UnknownFunction { name, span } => write!(f, "function {0:} is not registered at {1:}", name, span),
+            ParseError::IntegerOverflow { text, span } => write!(f, "integer {0:} is out of range at {1:}", text, span),
+            ParseError::NestingLimit { span } => write!(f, "nesting is deeper than {0:} levels at {1:}", MAX_NESTING, span)
+        };
+    }
+}
+
+impl std::error::Error for ParseError {}
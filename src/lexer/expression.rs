@@ -1,10 +1,118 @@
-use crate::token::Token;
+use crate::function::Functions;
+use crate::span::Span;
+use crate::token::TokenList;
 
-use super::boolean::BoolExpression;
-use super::numeric::NumericExpression;
+use super::boolean::{BoolExpression, BoolExpressionType};
+use super::error::ParseError;
+use super::numeric::{NumericExpression, NumericExpressionDataType};
+use super::string::StringExpression;
+use crate::variable::{Variable, VariableType};
+use std::collections::HashMap;
 
+#[derive(Debug, PartialEq)]
 pub enum Expression
 {
     Numeric(NumericExpression),
     Boolean(BoolExpression),
+    String(StringExpression)
+}
+
+impl Expression
+{
+    /// Returns the source location of the expression
+    pub fn span(&self) -> Span
+    {
+        return match self
+        {
+            Expression::Numeric(e) => e.span,
+            Expression::Boolean(e) => e.span,
+            Expression::String(e) => e.span
+        };
+    }
+
+    /// Returns the variable type stored by assigning the expression, holding a default value
+    pub fn variable_type(&self) -> VariableType
+    {
+        return match self
+        {
+            Expression::Boolean(_) => VariableType::Boolean(false),
+            Expression::String(_) => VariableType::String(String::new()),
+            Expression::Numeric(e) => match e.data_type
+            {
+                NumericExpressionDataType::Int => VariableType::Integer(0),
+                NumericExpressionDataType::Float => VariableType::Float(0.0)
+            }
+        };
+    }
+
+    /// Parses a boolean, string or numeric expression from the token list. The boolean form is
+    /// attempted first, and the token list is rewound to try the string form if a string value
+    /// is next, or the numeric form otherwise. The form that reads the most tokens is kept, with
+    /// a lone variable read in the form of its type
+    pub fn parse(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<Expression, ParseError>
+    {
+        // Save the starting location to allow for backtracking
+        let start = tokens.position();
+
+        // Attempt to parse as a boolean expression
+        let boolean = BoolExpression::parse(tokens, variables, functions);
+        let bool_end = tokens.position();
+
+        // Rewind and attempt to parse as a string or numeric expression
+        tokens.set_position(start);
+
+        let other = if StringExpression::starts(tokens, variables, functions)
+        {
+            StringExpression::parse(tokens, variables, functions).map(Expression::String)
+        }
+        else
+        {
+            NumericExpression::parse(tokens, variables, functions).map(Expression::Numeric)
+        };
+        let other_end = tokens.position();
+
+        // Keep the boolean form unless the other form reads further, or reads the same lone
+        // variable that is not a boolean
+        let boolean = match boolean
+        {
+            Ok(v) if other.is_err() || bool_end > other_end => Ok(v),
+            Ok(v) if bool_end == other_end && !Expression::is_mismatched(&v, variables) => Ok(v),
+            Ok(_) => Err(None),
+            Err(e) => Err(Some(e))
+        };
+
+        // Report the error from the attempt that progressed the furthest, such that a value
+        // followed by an invalid comparison reports the comparison rather than the missing end
+        // of the statement
+        return match (boolean, other)
+        {
+            (Ok(v), _) =>
+            {
+                tokens.set_position(bool_end);
+                Ok(Expression::Boolean(v))
+            },
+            (Err(Some(e)), Ok(_)) if bool_end > other_end =>
+            {
+                tokens.set_position(bool_end);
+                Err(e)
+            },
+            (_, Ok(v)) => Ok(v),
+            (Err(Some(e)), Err(_)) if bool_end > other_end =>
+            {
+                tokens.set_position(bool_end);
+                Err(e)
+            },
+            (_, Err(e)) => Err(e)
+        };
+    }
+
+    /// Returns true if the boolean expression is a lone variable that does not hold a boolean
+    fn is_mismatched(expr: &BoolExpression, variables: &HashMap<String, Variable>) -> bool
+    {
+        return match &expr.value
+        {
+            BoolExpressionType::Variable(name) => !matches!(variables.get(name).map(|v| &v.vartype), Some(VariableType::Boolean(_))),
+            _ => false
+        };
+    }
 }
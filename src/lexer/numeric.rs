@@ -1,5 +1,16 @@
+use crate::function::Functions;
+use crate::span::Span;
 use crate::token::{Token, TokenList};
+use crate::token::symbol::Operator;
 
+use super::call::CallExpression;
+use super::error::ParseError;
+use super::operator::OperatorRole;
+use crate::variable::{Variable, VariableType};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[derive(Debug, PartialEq)]
 pub struct OperationNumericExpression
 {
     pub op: NumericOpType,
@@ -7,26 +18,231 @@ pub struct OperationNumericExpression
     pub b: Box<NumericExpression>
 }
 
+#[derive(Debug, PartialEq)]
 pub enum NumericExpressionType
 {
     Operation(OperationNumericExpression),
     IntConstant(i32),
-    FloatConstant(f32)
+    FloatConstant(f32),
+    Variable(String),
+    Call(CallExpression)
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum NumericExpressionDataType
 {
     Float,
     Int
 }
 
+impl NumericExpressionDataType
+{
+    /// Returns the data type resulting from combining values of the two data types, providing an
+    /// integer only if both values are integers and promoting to a float otherwise
+    pub fn promote(a: NumericExpressionDataType, b: NumericExpressionDataType) -> NumericExpressionDataType
+    {
+        return if a == NumericExpressionDataType::Int && b == NumericExpressionDataType::Int
+        {
+            NumericExpressionDataType::Int
+        }
+        else
+        {
+            NumericExpressionDataType::Float
+        };
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct NumericExpression
 {
     pub inverted: bool,
     pub value: NumericExpressionType,
-    pub data_type: NumericExpressionDataType
+    pub data_type: NumericExpressionDataType,
+    pub span: Span
+}
+
+impl NumericExpression
+{
+    /// Parses a numeric expression from the token list, applying operator precedence such that
+    /// operations with a higher binding power are grouped first
+    pub fn parse(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<NumericExpression, ParseError>
+    {
+        return NumericExpression::parse_binary(tokens, variables, functions, 0);
+    }
+
+    /// Parses a numeric expression using precedence climbing, only consuming operations with a
+    /// binding power of at least the provided minimum
+    fn parse_binary(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions, min_power: u8) -> Result<NumericExpression, ParseError>
+    {
+        // Parse the left-hand value
+        let mut a = NumericExpression::parse_value(tokens, variables, functions)?;
+
+        // Continue to read operations while they bind at least as tightly as the minimum
+        while let Some(op) = NumericOpType::peek(tokens)
+        {
+            let power = op.binding_power();
+
+            if power < min_power
+            {
+                break;
+            }
+
+            tokens.pop();
+
+            // Parse the right-hand side with a higher minimum to provide left-associativity
+            let b = NumericExpression::parse_binary(tokens, variables, functions, power + 1)?;
+            a = NumericExpression::operation(op, a, b);
+        }
+
+        return Ok(a);
+    }
+
+    /// Creates an operation expression, providing an integer result only if both inputs are integers
+    pub fn operation(op: NumericOpType, a: NumericExpression, b: NumericExpression) -> NumericExpression
+    {
+        let data_type = NumericExpressionDataType::promote(a.data_type, b.data_type);
+        let span = a.span.merge(&b.span);
+
+        return NumericExpression
+        {
+            inverted: false,
+            value: NumericExpressionType::Operation(OperationNumericExpression
+            {
+                op,
+                a: Box::new(a),
+                b: Box::new(b)
+            }),
+            data_type,
+            span
+        };
+    }
+
+    /// Parses a single numeric value, being a constant, a variable, a function call, a negated
+    /// value, or a parenthesized expression. Negation binds tighter than any numeric operation
+    pub fn parse_value(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<NumericExpression, ParseError>
+    {
+        // Save the starting location of the value
+        let start = tokens.peek_span();
+
+        // Check for a negated value, folding the negation into constants and otherwise
+        // toggling the inverted flag
+        if tokens.consume_operator(Operator::Minus)
+        {
+            // Negate integer literals before range-checking them, so that the lowest integer
+            // may be written
+            if let Some(Token::Int(v)) = tokens.peek()
+            {
+                let span = start.merge(&tokens.peek_span());
+                tokens.pop();
+
+                return Ok(NumericExpression
+                {
+                    inverted: false,
+                    value: NumericExpressionType::IntConstant(-i64::from(v) as i32),
+                    data_type: NumericExpressionDataType::Int,
+                    span
+                });
+            }
+
+            let mut expr = super::nested(tokens, |tokens| NumericExpression::parse_value(tokens, variables, functions))?;
+
+            match &mut expr.value
+            {
+                NumericExpressionType::IntConstant(v) if *v != i32::MIN => *v = -*v,
+                NumericExpressionType::FloatConstant(v) => *v = -*v,
+                _ => expr.inverted = !expr.inverted
+            }
+
+            expr.span = start.merge(&expr.span);
+            return Ok(expr);
+        }
+
+        // Check for a parenthesized expression
+        if tokens.consume_operator(Operator::LeftParen)
+        {
+            let mut expr = super::nested(tokens, |tokens| NumericExpression::parse(tokens, variables, functions))?;
+
+            if !tokens.consume_operator(Operator::RightParen)
+            {
+                return Err(ParseError::expected("')'", tokens));
+            }
+
+            expr.span = tokens.span_from(start);
+            return Ok(expr);
+        }
+
+        // Check for a call to a function returning a numeric value
+        if let Some(name) = CallExpression::peek(tokens)
+        {
+            let data_type = match functions.signature(&name).map(|s| &s.result)
+            {
+                Some(VariableType::Boolean(_)) | Some(VariableType::String(_)) => return Err(ParseError::expected("numeric expression", tokens)),
+                Some(VariableType::Float(_)) => NumericExpressionDataType::Float,
+                _ => NumericExpressionDataType::Int
+            };
+
+            let (call, span) = CallExpression::parse(tokens, variables, functions)?;
+
+            return Ok(NumericExpression
+            {
+                inverted: false,
+                value: NumericExpressionType::Call(call),
+                data_type,
+                span
+            });
+        }
+
+        // Read the constant or variable value
+        let expr = match tokens.peek()
+        {
+            Some(Token::Int(v)) => NumericExpression
+            {
+                inverted: false,
+                value: match i32::try_from(v)
+                {
+                    Ok(v) => NumericExpressionType::IntConstant(v),
+                    Err(_) => return Err(ParseError::IntegerOverflow { text: v.to_string(), span: start })
+                },
+                data_type: NumericExpressionDataType::Int,
+                span: start
+            },
+            Some(Token::Float(v)) => NumericExpression
+            {
+                inverted: false,
+                value: NumericExpressionType::FloatConstant(v),
+                data_type: NumericExpressionDataType::Float,
+                span: start
+            },
+            Some(Token::Variable(name)) =>
+            {
+                // Take the data type of the variable from its first assignment, leaving boolean and
+                // string variables for the type checker to report
+                let data_type = match variables.get(&name).map(|v| &v.vartype)
+                {
+                    Some(VariableType::Float(_)) => NumericExpressionDataType::Float,
+                    Some(_) => NumericExpressionDataType::Int,
+                    None => return Err(ParseError::UnassignedVariable { name, span: start })
+                };
+
+                NumericExpression
+                {
+                    inverted: false,
+                    value: NumericExpressionType::Variable(name),
+                    data_type,
+                    span: start
+                }
+            },
+            _ => return Err(ParseError::expected("numeric expression", tokens))
+        };
+
+        // Pop the value token
+        tokens.pop();
+
+        return Ok(expr);
+    }
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum NumericOpType
 {
     Add,
@@ -37,59 +253,156 @@ pub enum NumericOpType
 
 impl NumericOpType
 {
-    pub fn parse(tokens: &mut TokenList) -> Option<NumericOpType>
-    {
-        // Check to see if a token is available
-        if !tokens.available()
-        {
-            return None;
-        }
+    /// Defines the binding power of each numeric operation. Operations with a higher binding
+    /// power are grouped first, and all are left-associative
+    const BINDINGS: [(NumericOpType, u8); 4] = [
+        (NumericOpType::Add, 10),
+        (NumericOpType::Subtract, 10),
+        (NumericOpType::Multiply, 20),
+        (NumericOpType::Divide, 20)
+    ];
 
-        // Peek the next token
-        let next = tokens.peek().unwrap();
+    /// Returns the binding power of the operation
+    pub fn binding_power(&self) -> u8
+    {
+        return NumericOpType::BINDINGS
+            .iter()
+            .find(|(op, _)| op == self)
+            .map(|(_, power)| *power)
+            .unwrap();
+    }
 
-        // Iterate over the possible operations
-        let op_type: Option<NumericOpType> = match next
+    /// Returns the operation associated with the next token, if any, without removing it from the list
+    pub fn peek(tokens: &TokenList) -> Option<NumericOpType>
+    {
+        return match tokens.peek()
         {
-            Token::Operator(s) =>
-                {
-                    // Check if the token matches the possible operation
-                    if s == "+"
-                    {
-                        Some(NumericOpType::Add)
-                    }
-                    else if s == "-"
-                    {
-                        Some(NumericOpType::Subtract)
-                    }
-                    else if s == "*"
-                    {
-                        Some(NumericOpType::Multiply)
-                    }
-                    else if s == "/"
-                    {
-                        Some(NumericOpType::Divide)
-                    }
-                    else
-                    {
-                        None
-                    }
-                },
+            Some(Token::Operator(op)) => match OperatorRole::of(op)
+            {
+                OperatorRole::Numeric(v) => Some(v),
+                _ => None
+            },
             _ => None
         };
+    }
+}
+
+/// Defines tests of the numeric expression parser
+#[cfg(test)]
+mod tests
+{
+    use super::{NumericExpression, NumericExpressionDataType, NumericExpressionType, NumericOpType};
+    use crate::lexer::error::ParseError;
+    use crate::span::Span;
+    use crate::token::Token;
+    use crate::function::Functions;
+    use std::collections::HashMap;
 
-        // Return the result
-        return match op_type
+    /// Parses the input and provides a fully-parenthesized string of the resulting expression
+    fn grouping(input: &str) -> String
+    {
+        fn write(expr: &NumericExpression) -> String
         {
-            None => None,
-            Some(op) =>
+            let value = match &expr.value
+            {
+                NumericExpressionType::IntConstant(v) => v.to_string(),
+                NumericExpressionType::FloatConstant(v) => format!("{0:?}", v),
+                NumericExpressionType::Variable(name) => name.clone(),
+                NumericExpressionType::Call(call) => format!("{0:}(..)", call.name),
+                NumericExpressionType::Operation(op) =>
                 {
-                    // Pop the current token
-                    tokens.pop();
-
-                    // Return the result
-                    Some(op)
+                    let symbol = match op.op
+                    {
+                        NumericOpType::Add => "+",
+                        NumericOpType::Subtract => "-",
+                        NumericOpType::Multiply => "*",
+                        NumericOpType::Divide => "/"
+                    };
+                    format!("({0:} {1:} {2:})", write(&op.a), symbol, write(&op.b))
                 }
+            };
+
+            return if expr.inverted
+            {
+                format!("-{0:}", value)
+            }
+            else
+            {
+                value
+            };
         }
+
+        let mut tokens = Token::tokenize(input).unwrap();
+        let expr = NumericExpression::parse(&mut tokens, &HashMap::new(), &Functions::new()).unwrap();
+        assert!(!tokens.available());
+        return write(&expr);
+    }
+
+    /// Tests that multiplication and division bind tighter than addition and subtraction
+    #[test]
+    fn precedence()
+    {
+        assert_eq!(grouping("1 + 2 * 3"), "(1 + (2 * 3))");
+        assert_eq!(grouping("1 * 2 + 3"), "((1 * 2) + 3)");
+        assert_eq!(grouping("1 - 6 / 3 * 2 + 4"), "((1 - ((6 / 3) * 2)) + 4)");
+    }
+
+    /// Tests that operations of equal precedence are left-associative
+    #[test]
+    fn associativity()
+    {
+        assert_eq!(grouping("8 - 4 - 2"), "((8 - 4) - 2)");
+        assert_eq!(grouping("8 / 4 / 2"), "((8 / 4) / 2)");
+        assert_eq!(grouping("1 + 2 - 3 + 4"), "(((1 + 2) - 3) + 4)");
+    }
+
+    /// Tests that parentheses override the default precedence
+    #[test]
+    fn parentheses()
+    {
+        assert_eq!(grouping("(1 + 2) * 3"), "((1 + 2) * 3)");
+        assert_eq!(grouping("8 - (4 - 2)"), "(8 - (4 - 2))");
+        assert_eq!(grouping("((2.5))"), "2.5");
+    }
+
+    /// Tests that negation binds tighter than numeric operations and folds into constants
+    #[test]
+    fn negation()
+    {
+        assert_eq!(grouping("-(1 + 2) * 3"), "(-(1 + 2) * 3)");
+        assert_eq!(grouping("2 * -3"), "(2 * -3)");
+        assert_eq!(grouping("1 - -2.5"), "(1 - -2.5)");
+        assert_eq!(grouping("--3"), "3");
+        assert_eq!(grouping("- - -3"), "-3");
+        assert_eq!(grouping("--(4 / 2)"), "(4 / 2)");
+        assert_eq!(grouping("-(-(1))"), "1");
+
+        let mut tokens = Token::tokenize("-").unwrap();
+        assert!(NumericExpression::parse(&mut tokens, &HashMap::new(), &Functions::new()).is_err());
+    }
+
+    /// Tests that integer literals are range-checked after any negation before them is folded
+    #[test]
+    fn integer_range()
+    {
+        assert_eq!(grouping("-2147483648"), "-2147483648");
+        assert_eq!(grouping("1 - -2147483648"), "(1 - -2147483648)");
+        assert_eq!(grouping("-(-2147483648)"), "--2147483648");
+
+        let mut tokens = Token::tokenize("1 - 2147483648").unwrap();
+        assert_eq!(
+            NumericExpression::parse(&mut tokens, &HashMap::new(), &Functions::new()).err(),
+            Some(ParseError::IntegerOverflow { text: String::from("2147483648"), span: Span::new(4, 14, 1, 5) }));
+    }
+
+    /// Tests that the resulting data type is only an integer if all values are integers
+    #[test]
+    fn data_type()
+    {
+        let mut tokens = Token::tokenize("1 + 2 * 3").unwrap();
+        assert_eq!(NumericExpression::parse(&mut tokens, &HashMap::new(), &Functions::new()).unwrap().data_type, NumericExpressionDataType::Int);
+
+        let mut tokens = Token::tokenize("1 + 2 * 3.0").unwrap();
+        assert_eq!(NumericExpression::parse(&mut tokens, &HashMap::new(), &Functions::new()).unwrap().data_type, NumericExpressionDataType::Float);
     }
 }
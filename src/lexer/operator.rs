@@ -0,0 +1,83 @@
+use crate::token::symbol::Operator;
+
+use super::boolean::{BooleanOpType, ComparisonOpType};
+use super::numeric::NumericOpType;
+
+/// Defines the role of an operator token within the grammar
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OperatorRole
+{
+    /// Separates or groups other parts of the grammar
+    Punctuation,
+
+    /// Assigns a value to a variable
+    Assignment,
+
+    /// Assigns the result of a numeric operation between the variable and a value to the variable
+    CompoundAssignment(NumericOpType),
+
+    /// Inverts a boolean value
+    Not,
+
+    /// Combines two numeric values
+    Numeric(NumericOpType),
+
+    /// Combines two boolean values
+    Boolean(BooleanOpType),
+
+    /// Compares two numeric values
+    Comparison(ComparisonOpType)
+}
+
+impl OperatorRole
+{
+    /// Returns the role of the provided operator. Every operator is listed explicitly so that
+    /// new operators must be assigned a role before the parser will compile
+    pub fn of(op: Operator) -> OperatorRole
+    {
+        return match op
+        {
+            Operator::Semicolon => OperatorRole::Punctuation,
+            Operator::Comma => OperatorRole::Punctuation,
+            Operator::LeftParen => OperatorRole::Punctuation,
+            Operator::RightParen => OperatorRole::Punctuation,
+            Operator::LeftBrace => OperatorRole::Punctuation,
+            Operator::RightBrace => OperatorRole::Punctuation,
+            Operator::Plus => OperatorRole::Numeric(NumericOpType::Add),
+            Operator::Star => OperatorRole::Numeric(NumericOpType::Multiply),
+            Operator::Minus => OperatorRole::Numeric(NumericOpType::Subtract),
+            Operator::Slash => OperatorRole::Numeric(NumericOpType::Divide),
+            Operator::Assign => OperatorRole::Assignment,
+            Operator::PlusAssign => OperatorRole::CompoundAssignment(NumericOpType::Add),
+            Operator::MinusAssign => OperatorRole::CompoundAssignment(NumericOpType::Subtract),
+            Operator::StarAssign => OperatorRole::CompoundAssignment(NumericOpType::Multiply),
+            Operator::SlashAssign => OperatorRole::CompoundAssignment(NumericOpType::Divide),
+            Operator::And => OperatorRole::Boolean(BooleanOpType::And),
+            Operator::Or => OperatorRole::Boolean(BooleanOpType::Or),
+            Operator::Equal => OperatorRole::Comparison(ComparisonOpType::Equal),
+            Operator::NotEqual => OperatorRole::Comparison(ComparisonOpType::NotEqual),
+            Operator::Not => OperatorRole::Not,
+            Operator::Less => OperatorRole::Comparison(ComparisonOpType::Less),
+            Operator::Greater => OperatorRole::Comparison(ComparisonOpType::Greater),
+            Operator::LessEqual => OperatorRole::Comparison(ComparisonOpType::LessEqual),
+            Operator::GreaterEqual => OperatorRole::Comparison(ComparisonOpType::GreaterEqual)
+        };
+    }
+
+    /// Returns true if the role assigns a value to a variable
+    pub fn is_assignment(&self) -> bool
+    {
+        return matches!(self, OperatorRole::Assignment | OperatorRole::CompoundAssignment(_));
+    }
+
+    /// Returns the source text of the first operator with the role, such as "+" for an addition
+    /// or "+=" for a compound addition
+    pub fn symbol(&self) -> &'static str
+    {
+        return Operator::TABLE
+            .iter()
+            .find(|(op, _)| OperatorRole::of(*op) == *self)
+            .map(|(_, text)| *text)
+            .unwrap();
+    }
+}
@@ -1,13 +1,587 @@
+use super::error::ParseError;
 use super::statement::Statement;
+use crate::function::Functions;
+use crate::token::TokenList;
 use crate::variable::Variable;
 use std::collections::HashMap;
 
 /// Defines the overall program
+#[derive(Debug, PartialEq)]
 pub struct Program
 {
     /// Provides the main/first program
     pub main: Statement,
 
-    /// Defines the variables
+    /// Defines the variables assigned within the program, with the type of their first assignment
     pub variables: HashMap<String, Variable>
 }
+
+impl Program
+{
+    /// Parses the full token list into a program, returning an error if any tokens do not
+    /// match the language grammar. Variables are resolved in source order, such that each
+    /// variable must be assigned before it is read
+    pub fn parse(tokens: &mut TokenList) -> Result<Program, ParseError>
+    {
+        return Program::parse_with(tokens, HashMap::new(), &Functions::new());
+    }
+
+    /// Parses the full token list into a program, where the provided variables are treated as
+    /// assigned before the program starts, such as by a previous program in the same session,
+    /// and the provided host functions may be called
+    pub fn parse_with(tokens: &mut TokenList, mut variables: HashMap<String, Variable>, functions: &Functions) -> Result<Program, ParseError>
+    {
+        // Parse the main statement sequence, collecting variables as they are assigned
+        let main = Statement::parse(tokens, &mut variables, functions)?;
+
+        // Ensure that all tokens were consumed, such as an unmatched closing brace
+        if tokens.available()
+        {
+            return Err(ParseError::expected("statement", tokens));
+        }
+
+        return Ok(Program
+        {
+            main,
+            variables
+        });
+    }
+}
+
+/// Defines tests of the program parser
+#[cfg(test)]
+mod tests
+{
+    use super::Program;
+    use crate::function::Functions;
+    use crate::lexer::boolean::{BoolExpressionType, BooleanOpType, ComparisonOpType};
+    use crate::lexer::error::ParseError;
+    use crate::lexer::expression::Expression;
+    use crate::lexer::MAX_NESTING;
+    use crate::lexer::numeric::{NumericExpressionDataType, NumericExpressionType, NumericOpType};
+    use crate::lexer::statement::StatementType;
+    use crate::lexer::string::StringExpressionType;
+    use crate::span::Span;
+    use crate::token::Token;
+    use crate::token::symbol::Operator;
+    use crate::variable::VariableType;
+    use std::collections::HashMap;
+
+    /// Tokenizes and parses the provided input
+    fn parse(input: &str) -> Result<Program, ParseError>
+    {
+        return Program::parse(&mut Token::tokenize(input).unwrap());
+    }
+
+    /// Tokenizes and parses the provided input, with functions returning each type of value
+    fn parse_with_functions(input: &str) -> Result<Program, ParseError>
+    {
+        let mut functions = Functions::new();
+        functions.register("clamp", |x: f32, lo: f32, hi: f32| x.max(lo).min(hi));
+        functions.register("even", |x: i32| x % 2 == 0);
+        functions.register("seven", || 7);
+
+        return Program::parse_with(&mut Token::tokenize(input).unwrap(), HashMap::new(), &functions);
+    }
+
+    /// Tests that an empty program parses to an empty statement
+    #[test]
+    fn parse_empty()
+    {
+        let program = parse("").unwrap();
+
+        assert_eq!(program.main.data, StatementType::Empty);
+        assert!(program.main.next.is_none());
+        assert!(program.variables.is_empty());
+    }
+
+    /// Tests that assignments are parsed and linked in order
+    #[test]
+    fn parse_assignments()
+    {
+        let program = parse("a = 1 + 2.5; b = true && false; c = -3;").unwrap();
+
+        match &program.main.data
+        {
+            StatementType::Assign(v) =>
+            {
+                assert_eq!(v.varname, "a");
+                match &v.expr
+                {
+                    Expression::Numeric(e) =>
+                    {
+                        assert_eq!(e.data_type, NumericExpressionDataType::Float);
+                        match &e.value
+                        {
+                            NumericExpressionType::Operation(op) =>
+                            {
+                                assert_eq!(op.op, NumericOpType::Add);
+                                assert_eq!(op.a.value, NumericExpressionType::IntConstant(1));
+                                assert_eq!(op.b.value, NumericExpressionType::FloatConstant(2.5));
+                            },
+                            _ => panic!("expected operation")
+                        }
+                    },
+                    _ => panic!("expected numeric expression")
+                }
+            },
+            _ => panic!("expected assignment")
+        }
+
+        let second = program.main.next.as_ref().unwrap();
+        match &second.data
+        {
+            StatementType::Assign(v) =>
+            {
+                assert_eq!(v.varname, "b");
+                match &v.expr
+                {
+                    Expression::Boolean(e) => match &e.value
+                    {
+                        BoolExpressionType::Operation(op) => assert_eq!(op.op, BooleanOpType::And),
+                        _ => panic!("expected operation")
+                    },
+                    _ => panic!("expected boolean expression")
+                }
+            },
+            _ => panic!("expected assignment")
+        }
+
+        let third = second.next.as_ref().unwrap();
+        assert!(third.next.is_none());
+
+        assert_eq!(program.variables["a"].vartype, VariableType::Float(0.0));
+        assert_eq!(program.variables["b"].vartype, VariableType::Boolean(false));
+        assert_eq!(program.variables["c"].vartype, VariableType::Integer(0));
+    }
+
+    /// Tests that if, else and while statements parse with their nested blocks
+    #[test]
+    fn parse_control_flow()
+    {
+        let program = parse("if (!true || (false)) { x = 1; } else { y = 2; } while (true) { }").unwrap();
+
+        match &program.main.data
+        {
+            StatementType::If(v) =>
+            {
+                assert!(!v.boolexpr.inverted);
+                assert!(matches!(v.statement.data, StatementType::Assign(_)));
+                assert!(matches!(v.else_statement.data, StatementType::Assign(_)));
+            },
+            _ => panic!("expected if statement")
+        }
+
+        match &program.main.next.as_ref().unwrap().data
+        {
+            StatementType::While(v) =>
+            {
+                assert_eq!(v.boolexpr.value, BoolExpressionType::Constant(true));
+                assert_eq!(v.statement.data, StatementType::Empty);
+            },
+            _ => panic!("expected while statement")
+        }
+
+        assert!(program.variables.contains_key("x"));
+        assert!(program.variables.contains_key("y"));
+    }
+
+    /// Tests that statements and expressions are given the source location they were parsed from
+    #[test]
+    fn parse_spans()
+    {
+        let program = parse("a = (1 + 2) * 3;\nif (4 > 2) {\n  b = !true;\n}").unwrap();
+
+        assert_eq!(program.main.span, Span::new(0, 16, 1, 1));
+        match &program.main.data
+        {
+            StatementType::Assign(v) => match &v.expr
+            {
+                Expression::Numeric(e) =>
+                {
+                    assert_eq!(e.span, Span::new(4, 15, 1, 5));
+                    match &e.value
+                    {
+                        NumericExpressionType::Operation(op) =>
+                        {
+                            assert_eq!(op.a.span, Span::new(4, 11, 1, 5));
+                            assert_eq!(op.b.span, Span::new(14, 15, 1, 15));
+                        },
+                        _ => panic!("expected operation")
+                    }
+                },
+                _ => panic!("expected numeric expression")
+            },
+            _ => panic!("expected assignment")
+        }
+
+        let second = program.main.next.as_ref().unwrap();
+        assert_eq!(second.span, Span::new(17, 44, 2, 1));
+        match &second.data
+        {
+            StatementType::If(v) =>
+            {
+                assert_eq!(v.boolexpr.span, Span::new(21, 26, 2, 5));
+                assert_eq!(v.statement.span, Span::new(32, 42, 3, 3));
+            },
+            _ => panic!("expected if statement")
+        }
+    }
+
+    /// Tests that for statements parse with optional init and step assignments
+    #[test]
+    fn parse_for()
+    {
+        let program = parse("for (i = 0; 1 < 2; j = 1.5) { k = true; } for (; false; ) { }").unwrap();
+
+        match &program.main.data
+        {
+            StatementType::For(v) =>
+            {
+                assert!(matches!(&v.init.data, StatementType::Assign(a) if a.varname == "i"));
+                assert!(matches!(&v.step.data, StatementType::Assign(a) if a.varname == "j"));
+                assert!(matches!(&v.statement.data, StatementType::Assign(a) if a.varname == "k"));
+                assert_eq!(v.init.span, Span::new(5, 10, 1, 6));
+            },
+            _ => panic!("expected for statement")
+        }
+
+        match &program.main.next.as_ref().unwrap().data
+        {
+            StatementType::For(v) =>
+            {
+                assert_eq!(v.init.data, StatementType::Empty);
+                assert_eq!(v.step.data, StatementType::Empty);
+            },
+            _ => panic!("expected for statement")
+        }
+
+        assert_eq!(program.variables["j"].vartype, VariableType::Float(0.0));
+        assert!(parse("for (i = 0; 1 < 2; i = 1;) { }").is_err());
+        assert!(parse("for (i = 0; 1 < 2) { }").is_err());
+    }
+
+    /// Tests that variables are read with the type of their first assignment
+    #[test]
+    fn parse_variables()
+    {
+        let program = parse("a = 1; b = a * 2.5; c = a < b && true; d = !c || (c); e = -(b);").unwrap();
+
+        let second = program.main.next.as_ref().unwrap();
+        match &second.data
+        {
+            StatementType::Assign(v) => match &v.expr
+            {
+                Expression::Numeric(e) =>
+                {
+                    assert_eq!(e.data_type, NumericExpressionDataType::Float);
+                    match &e.value
+                    {
+                        NumericExpressionType::Operation(op) =>
+                        {
+                            assert_eq!(op.a.value, NumericExpressionType::Variable("a".to_string()));
+                            assert_eq!(op.a.data_type, NumericExpressionDataType::Int);
+                            assert_eq!(op.a.span, Span::new(11, 12, 1, 12));
+                        },
+                        _ => panic!("expected operation")
+                    }
+                },
+                _ => panic!("expected numeric expression")
+            },
+            _ => panic!("expected assignment")
+        }
+
+        let fourth = second.next.as_ref().unwrap().next.as_ref().unwrap();
+        match &fourth.data
+        {
+            StatementType::Assign(v) => match &v.expr
+            {
+                Expression::Boolean(e) => match &e.value
+                {
+                    BoolExpressionType::Operation(op) =>
+                    {
+                        assert_eq!(op.a.value, BoolExpressionType::Variable("c".to_string()));
+                        assert!(op.a.inverted);
+                    },
+                    _ => panic!("expected operation")
+                },
+                _ => panic!("expected boolean expression")
+            },
+            _ => panic!("expected assignment")
+        }
+
+        assert_eq!(program.variables["c"].vartype, VariableType::Boolean(false));
+        assert_eq!(program.variables["d"].vartype, VariableType::Boolean(false));
+        assert_eq!(program.variables["e"].vartype, VariableType::Float(0.0));
+        assert!(parse("i = 0; while (i < 3) { i = i + 1; }").is_ok());
+        assert!(parse("for (i = 0; i < 3; i = i + 1) { j = i; } k = j;").is_ok());
+    }
+
+    /// Tests that reading a variable before its first assignment is reported as a semantic error
+    #[test]
+    fn parse_unassigned()
+    {
+        assert_eq!(
+            parse("a = 1;
+b = a + c;").unwrap_err(),
+            ParseError::UnassignedVariable
+            {
+                name: "c".to_string(),
+                span: Span::new(15, 16, 2, 9)
+            });
+        assert_eq!(
+            parse("if (x) { }").unwrap_err(),
+            ParseError::UnassignedVariable
+            {
+                name: "x".to_string(),
+                span: Span::new(4, 5, 1, 5)
+            });
+        assert!(matches!(parse("a = a;"), Err(ParseError::UnassignedVariable { .. })));
+        assert!(matches!(parse("while (i < 3) { i = 1; }"), Err(ParseError::UnassignedVariable { .. })));
+
+        // Check that variables of another type are left for the type checker to report
+        assert!(parse("a = true; b = a + 1;").is_ok());
+        assert!(parse("a = 1; if (a) { }").is_ok());
+    }
+
+    /// Tests that compound assignments are expanded to an operation on the current value
+    #[test]
+    fn parse_compound_assignment()
+    {
+        let program = parse("a = 2; a *= 1 + 2.5;").unwrap();
+
+        let second = program.main.next.as_ref().unwrap();
+        assert_eq!(second.span, Span::new(7, 20, 1, 8));
+        match &second.data
+        {
+            StatementType::Assign(v) =>
+            {
+                assert_eq!(v.varname, "a");
+                match &v.expr
+                {
+                    Expression::Numeric(e) => match &e.value
+                    {
+                        NumericExpressionType::Operation(op) =>
+                        {
+                            assert_eq!(op.op, NumericOpType::Multiply);
+                            assert_eq!(op.a.value, NumericExpressionType::Variable("a".to_string()));
+                            assert!(matches!(&op.b.value, NumericExpressionType::Operation(b) if b.op == NumericOpType::Add));
+                        },
+                        _ => panic!("expected operation")
+                    },
+                    _ => panic!("expected numeric expression")
+                }
+            },
+            _ => panic!("expected assignment")
+        }
+
+        assert!(parse("for (i = 0; i < 3; i += 1) { }").is_ok());
+        assert!(matches!(parse("b -= 1;"), Err(ParseError::UnassignedVariable { .. })));
+        assert!(parse("b = true; b += 1;").is_ok());
+        assert!(parse("a = 1; a += true;").is_err());
+    }
+
+    /// Tests that string assignments, concatenation and comparisons are parsed
+    #[test]
+    fn parse_strings()
+    {
+        let program = parse("s = \"a\" + \"b\"; s += s + \"c\"; b = s == \"abc\" && (\"x\" + s) != s; t = s;").unwrap();
+
+        assert_eq!(program.variables["s"].vartype, VariableType::String(String::new()));
+        assert_eq!(program.variables["b"].vartype, VariableType::Boolean(false));
+        assert_eq!(program.variables["t"].vartype, VariableType::String(String::new()));
+
+        let second = program.main.next.as_ref().unwrap();
+        match &second.data
+        {
+            StatementType::Assign(v) => match &v.expr
+            {
+                Expression::String(e) => match &e.value
+                {
+                    StringExpressionType::Concat(c) =>
+                    {
+                        assert_eq!(c.a.value, StringExpressionType::Variable("s".to_string()));
+                        assert!(matches!(c.b.value, StringExpressionType::Concat(_)));
+                    },
+                    _ => panic!("expected concatenation")
+                },
+                _ => panic!("expected string expression")
+            },
+            _ => panic!("expected assignment")
+        }
+
+        let third = second.next.as_ref().unwrap();
+        match &third.data
+        {
+            StatementType::Assign(v) => match &v.expr
+            {
+                Expression::Boolean(e) => match &e.value
+                {
+                    BoolExpressionType::Operation(op) =>
+                    {
+                        assert!(matches!(&op.a.value, BoolExpressionType::StringComparison(c) if c.op == ComparisonOpType::Equal));
+                        assert!(matches!(&op.b.value, BoolExpressionType::StringComparison(c) if c.op == ComparisonOpType::NotEqual));
+                    },
+                    _ => panic!("expected operation")
+                },
+                _ => panic!("expected boolean expression")
+            },
+            _ => panic!("expected assignment")
+        }
+
+        assert!(matches!(parse("s = \"a\"; if (s < \"b\") { }"), Err(ParseError::UnexpectedToken { expected, .. }) if expected == "'==' or '!='"));
+        assert!(matches!(parse("s = \"a\"; b = s == 1;"), Err(ParseError::UnexpectedToken { expected, .. }) if expected == "string expression"));
+        assert!(matches!(parse("s = \"a\" + 1;"), Err(ParseError::UnexpectedToken { expected, .. }) if expected == "string expression"));
+        assert!(parse("s = \"a\"; n = s * 2;").is_ok());
+        assert!(matches!(parse("s = \"a\"; s -= \"a\";"), Err(ParseError::UnexpectedToken { expected, .. }) if expected == "numeric expression"));
+        assert!(matches!(parse("n = 1; n += \"a\";"), Err(ParseError::UnexpectedToken { .. })));
+    }
+
+    /// Tests that function calls are read as boolean or numeric values from their signatures
+    #[test]
+    fn parse_calls()
+    {
+        let program = parse_with_functions("a = clamp(seven() * 2, 0, 2.5); b = even(seven()) && !even(3); c = seven() > 4;").unwrap();
+
+        match &program.main.data
+        {
+            StatementType::Assign(v) => match &v.expr
+            {
+                Expression::Numeric(e) =>
+                {
+                    assert_eq!(e.data_type, NumericExpressionDataType::Float);
+                    assert_eq!(e.span, Span::new(4, 30, 1, 5));
+                    match &e.value
+                    {
+                        NumericExpressionType::Call(call) =>
+                        {
+                            assert_eq!(call.name, "clamp");
+                            assert_eq!(call.args.len(), 3);
+                            assert!(matches!(&call.args[0], Expression::Numeric(a) if matches!(&a.value, NumericExpressionType::Operation(_))));
+                        },
+                        _ => panic!("expected call")
+                    }
+                },
+                _ => panic!("expected numeric expression")
+            },
+            _ => panic!("expected assignment")
+        }
+
+        assert_eq!(program.variables["a"].vartype, VariableType::Float(0.0));
+        assert_eq!(program.variables["b"].vartype, VariableType::Boolean(false));
+        assert_eq!(program.variables["c"].vartype, VariableType::Boolean(false));
+        assert!(parse_with_functions("if (even(2) || seven() < 1) { }").is_ok());
+
+        assert_eq!(
+            parse_with_functions("a = missing(1);").unwrap_err(),
+            ParseError::UnknownFunction
+            {
+                name: "missing".to_string(),
+                span: Span::new(4, 11, 1, 5)
+            });
+        assert!(matches!(parse("a = seven();"), Err(ParseError::UnknownFunction { .. })));
+        assert!(matches!(parse_with_functions("a = even(1) + 1;"), Err(ParseError::UnexpectedToken { .. })));
+        assert!(matches!(parse_with_functions("a = clamp(1, 2;"), Err(ParseError::UnexpectedToken { expected, .. }) if expected == "',' or ')'"));
+    }
+
+    /// Tests that print statements read any number of expressions, and input statements read
+    /// the type of the variable
+    #[test]
+    fn parse_print_input()
+    {
+        let program = parse("a = 1; print a, a < 2, 2.5; print; input a; input x; y = x * 2;").unwrap();
+
+        let second = program.main.next.as_ref().unwrap();
+        match &second.data
+        {
+            StatementType::Print(v) =>
+            {
+                assert_eq!(v.exprs.len(), 3);
+                assert!(matches!(v.exprs[1], Expression::Boolean(_)));
+            },
+            _ => panic!("expected print")
+        }
+        assert_eq!(second.span, Span::new(7, 27, 1, 8));
+
+        let third = second.next.as_ref().unwrap();
+        assert!(matches!(&third.data, StatementType::Print(v) if v.exprs.is_empty()));
+
+        let fourth = third.next.as_ref().unwrap();
+        assert!(matches!(&fourth.data, StatementType::Input(v) if v.varname == "a" && v.vartype == VariableType::Integer(0)));
+
+        let fifth = fourth.next.as_ref().unwrap();
+        assert!(matches!(&fifth.data, StatementType::Input(v) if v.varname == "x" && v.vartype == VariableType::Float(0.0)));
+
+        assert_eq!(program.variables["y"].vartype, VariableType::Float(0.0));
+        assert!(matches!(parse("print 1 2;"), Err(ParseError::UnexpectedToken { expected, .. }) if expected == "',' or ';'"));
+        assert!(matches!(parse("print 1,;"), Err(ParseError::UnexpectedToken { .. })));
+        assert!(matches!(parse("input 3;"), Err(ParseError::UnexpectedToken { expected, .. }) if expected == "variable name"));
+        assert!(matches!(parse("input x"), Err(ParseError::UnexpectedEnd { .. })));
+    }
+
+    /// Tests that invalid programs provide parse errors
+    #[test]
+    fn parse_invalid()
+    {
+        assert!(parse("a = 1").is_err());
+        assert!(parse("a = true + 1;").is_err());
+        assert!(parse("if (1) { }").is_err());
+        assert!(parse("while (true) { a = 1;").is_err());
+        assert!(parse("}").is_err());
+        assert_eq!(
+            parse("a = (1 + 2;").unwrap_err(),
+            ParseError::UnexpectedToken
+            {
+                expected: "')'".to_string(),
+                found: Token::Operator(Operator::Semicolon),
+                span: Span::new(10, 11, 1, 11)
+            });
+    }
+
+    /// Tests that long programs are parsed and dropped without overflowing the stack
+    #[test]
+    fn parse_long()
+    {
+        let program = parse(&"a = 1;\n".repeat(100_000)).unwrap();
+        let mut count = 0;
+        let mut current = Some(&program.main);
+
+        while let Some(statement) = current
+        {
+            count += 1;
+            current = statement.next.as_deref();
+        }
+
+        assert_eq!(count, 100_000);
+    }
+
+    /// Tests that values and blocks may be nested up to the limit, with deeper nesting reported
+    /// as an error rather than overflowing the stack
+    #[test]
+    fn parse_nesting()
+    {
+        let nest = |open: &str, value: &str, close: &str, n: usize| format!("{0:}{1:}{2:}", open.repeat(n), value, close.repeat(n));
+
+        assert!(parse(&format!("a = {0:};", nest("(", "1", ")", MAX_NESTING))).is_ok());
+        assert!(parse(&format!("a = {0:};", nest("-", "1", "", MAX_NESTING))).is_ok());
+        assert!(parse(&format!("a = {0:};", nest("!", "true", "", MAX_NESTING))).is_ok());
+        assert!(parse(&format!("a = {0:};", nest("(", "\"a\"", ")", MAX_NESTING))).is_ok());
+        assert!(parse(&nest("if (true) {", "a = 1;", "}", MAX_NESTING)).is_ok());
+
+        assert_eq!(
+            parse(&format!("a = {0:};", nest("(", "1", ")", MAX_NESTING + 1))).unwrap_err(),
+            ParseError::NestingLimit { span: Span::new(37, 38, 1, 38) });
+
+        let deep = [
+            format!("a = {0:};", nest("(", "1", ")", 10_000)),
+            format!("a = {0:};", nest("-", "1", "", 10_000)),
+            format!("a = {0:};", nest("!", "true", "", 10_000)),
+            nest("while (true) {", "", "}", 10_000)
+        ];
+
+        for source in deep.iter()
+        {
+            assert!(matches!(parse(source), Err(ParseError::NestingLimit { .. })));
+        }
+    }
+}
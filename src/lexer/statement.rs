@@ -1,25 +1,198 @@
-use crate::token::TokenList;
+use crate::function::Functions;
+use crate::span::Span;
+use crate::token::{Token, TokenList};
+use crate::token::symbol::{Keyword, Operator};
 
 use super::expression::Expression;
 use super::boolean::BoolExpression;
+use super::error::ParseError;
+use super::numeric::{NumericExpression, NumericOpType};
+use super::operator::OperatorRole;
+use super::string::StringExpression;
+use crate::variable::{Variable, VariableType};
+use std::collections::HashMap;
 
 /// Defines a statement program
+#[derive(Debug, PartialEq)]
 pub struct Statement
 {
     /// Defines the tokens within the statement
     pub data: StatementType,
 
     /// Defines the optional next statement
-    pub next: Option<Box<Statement>>
+    pub next: Option<Box<Statement>>,
+
+    /// Defines the source location of the statement, not including any following statements
+    pub span: Span
+}
+
+impl Statement
+{
+    /// Returns a statement that performs no action
+    pub fn empty() -> Statement
+    {
+        return Statement
+        {
+            data: StatementType::Empty,
+            next: None,
+            span: Span::default()
+        };
+    }
+
+    /// Parses a sequence of statements until the end of the token list or a closing brace is
+    /// found, linking each statement to the next. An empty statement is returned if no statements
+    /// are present
+    pub fn parse(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>, functions: &Functions) -> Result<Statement, ParseError>
+    {
+        // Read each statement in the sequence
+        let mut statements: Vec<(StatementType, Span)> = Vec::new();
+
+        while tokens.available() && !tokens.next_is_operator(Operator::RightBrace)
+        {
+            let start = tokens.peek_span();
+            let data = StatementType::parse(tokens, variables, functions)?;
+            statements.push((data, tokens.span_from(start)));
+        }
+
+        // Link the statements together, starting from the last statement
+        let mut next: Option<Box<Statement>> = None;
+
+        while let Some((data, span)) = statements.pop()
+        {
+            next = Some(Box::new(Statement
+            {
+                data,
+                next,
+                span
+            }));
+        }
+
+        return match next
+        {
+            Some(s) => Ok(*s),
+            None => Ok(Statement::empty())
+        };
+    }
+
+    /// Parses a brace-enclosed block of statements
+    pub fn parse_block(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>, functions: &Functions) -> Result<Statement, ParseError>
+    {
+        if !tokens.consume_operator(Operator::LeftBrace)
+        {
+            return Err(ParseError::expected("'{'", tokens));
+        }
+
+        let statement = super::nested(tokens, |tokens| Statement::parse(tokens, variables, functions))?;
+
+        if !tokens.consume_operator(Operator::RightBrace)
+        {
+            return Err(ParseError::expected("'}'", tokens));
+        }
+
+        return Ok(statement);
+    }
 }
 
+impl Drop for Statement
+{
+    /// Drops the following statements one at a time, so that long programs do not overflow the
+    /// stack by dropping each statement from within the one before it
+    fn drop(&mut self)
+    {
+        let mut next = self.next.take();
+
+        while let Some(mut statement) = next
+        {
+            next = statement.next.take();
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub enum StatementType
 {
+    Empty,
     If(IfStatement),
     While(WhileStatement),
+    For(ForStatement),
+    Assign(VarStatement),
+    Print(PrintStatement),
+    Input(InputStatement),
     Expr(Expression)
 }
 
+impl StatementType
+{
+    /// Parses a single statement from the token list
+    pub fn parse(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>, functions: &Functions) -> Result<StatementType, ParseError>
+    {
+        // Check for keyword statements
+        if tokens.next_is_keyword(Keyword::If)
+        {
+            return Ok(StatementType::If(IfStatement::parse(tokens, variables, functions)?));
+        }
+        else if tokens.next_is_keyword(Keyword::While)
+        {
+            return Ok(StatementType::While(WhileStatement::parse(tokens, variables, functions)?));
+        }
+        else if tokens.next_is_keyword(Keyword::For)
+        {
+            return Ok(StatementType::For(ForStatement::parse(tokens, variables, functions)?));
+        }
+        else if tokens.next_is_keyword(Keyword::Print)
+        {
+            return Ok(StatementType::Print(PrintStatement::parse(tokens, variables, functions)?));
+        }
+        else if tokens.next_is_keyword(Keyword::Input)
+        {
+            return Ok(StatementType::Input(InputStatement::parse(tokens, variables)?));
+        }
+
+        // Check for an assignment, being a variable followed by an assignment operator
+        if let Some(Token::Variable(_)) = tokens.peek()
+        {
+            let start = tokens.position();
+            tokens.pop();
+            let is_assign = matches!(tokens.peek(), Some(Token::Operator(op)) if OperatorRole::of(op).is_assignment());
+            tokens.set_position(start);
+
+            if is_assign
+            {
+                return Ok(StatementType::Assign(VarStatement::parse(tokens, variables, functions)?));
+            }
+        }
+
+        // Otherwise, read an expression statement
+        let expr = Expression::parse(tokens, variables, functions)?;
+
+        if !tokens.consume_operator(Operator::Semicolon)
+        {
+            return Err(ParseError::expected("';'", tokens));
+        }
+
+        return Ok(StatementType::Expr(expr));
+    }
+}
+
+/// Reads the parenthesized boolean condition of an if or while statement
+fn parse_condition(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<BoolExpression, ParseError>
+{
+    if !tokens.consume_operator(Operator::LeftParen)
+    {
+        return Err(ParseError::expected("'('", tokens));
+    }
+
+    let boolexpr = BoolExpression::parse(tokens, variables, functions)?;
+
+    if !tokens.consume_operator(Operator::RightParen)
+    {
+        return Err(ParseError::expected("')'", tokens));
+    }
+
+    return Ok(boolexpr);
+}
+
+#[derive(Debug, PartialEq)]
 pub struct IfStatement
 {
     pub boolexpr: BoolExpression,
@@ -27,14 +200,333 @@ pub struct IfStatement
     pub else_statement: Box<Statement>
 }
 
+impl IfStatement
+{
+    /// Parses an if statement, with an optional else block
+    pub fn parse(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>, functions: &Functions) -> Result<IfStatement, ParseError>
+    {
+        if !tokens.next_is_keyword(Keyword::If)
+        {
+            return Err(ParseError::expected("'if'", tokens));
+        }
+        tokens.pop();
+
+        let boolexpr = parse_condition(tokens, variables, functions)?;
+        let statement = Statement::parse_block(tokens, variables, functions)?;
+
+        // Check for the else block, providing an empty statement if not found
+        let else_statement = if tokens.next_is_keyword(Keyword::Else)
+        {
+            tokens.pop();
+            Statement::parse_block(tokens, variables, functions)?
+        }
+        else
+        {
+            Statement::empty()
+        };
+
+        return Ok(IfStatement
+        {
+            boolexpr,
+            statement: Box::new(statement),
+            else_statement: Box::new(else_statement)
+        });
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct WhileStatement
 {
     pub boolexpr: BoolExpression,
     pub statement: Box<Statement>
 }
 
+impl WhileStatement
+{
+    /// Parses a while statement
+    pub fn parse(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>, functions: &Functions) -> Result<WhileStatement, ParseError>
+    {
+        if !tokens.next_is_keyword(Keyword::While)
+        {
+            return Err(ParseError::expected("'while'", tokens));
+        }
+        tokens.pop();
+
+        let boolexpr = parse_condition(tokens, variables, functions)?;
+        let statement = Statement::parse_block(tokens, variables, functions)?;
+
+        return Ok(WhileStatement
+        {
+            boolexpr,
+            statement: Box::new(statement)
+        });
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ForStatement
+{
+    pub init: Box<Statement>,
+    pub boolexpr: BoolExpression,
+    pub step: Box<Statement>,
+    pub statement: Box<Statement>
+}
+
+impl ForStatement
+{
+    /// Parses a for statement, in the form of for (init; <BoolExpr>; step) { <Statement> }, where
+    /// the init and step assignments may be left empty
+    pub fn parse(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>, functions: &Functions) -> Result<ForStatement, ParseError>
+    {
+        if !tokens.next_is_keyword(Keyword::For)
+        {
+            return Err(ParseError::expected("'for'", tokens));
+        }
+        tokens.pop();
+
+        if !tokens.consume_operator(Operator::LeftParen)
+        {
+            return Err(ParseError::expected("'('", tokens));
+        }
+
+        // Read the optional initial assignment
+        let init = ForStatement::parse_assignment(tokens, variables, functions, Operator::Semicolon)?;
+
+        if !tokens.consume_operator(Operator::Semicolon)
+        {
+            return Err(ParseError::expected("';'", tokens));
+        }
+
+        // Read the loop condition
+        let boolexpr = BoolExpression::parse(tokens, variables, functions)?;
+
+        if !tokens.consume_operator(Operator::Semicolon)
+        {
+            return Err(ParseError::expected("';'", tokens));
+        }
+
+        // Read the optional step assignment
+        let step = ForStatement::parse_assignment(tokens, variables, functions, Operator::RightParen)?;
+
+        if !tokens.consume_operator(Operator::RightParen)
+        {
+            return Err(ParseError::expected("')'", tokens));
+        }
+
+        let statement = Statement::parse_block(tokens, variables, functions)?;
+
+        return Ok(ForStatement
+        {
+            init: Box::new(init),
+            boolexpr,
+            step: Box::new(step),
+            statement: Box::new(statement)
+        });
+    }
+
+    /// Parses an assignment within the for statement header, providing an empty statement if
+    /// the terminating operator is found first
+    fn parse_assignment(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>, functions: &Functions, terminator: Operator) -> Result<Statement, ParseError>
+    {
+        if tokens.next_is_operator(terminator)
+        {
+            return Ok(Statement::empty());
+        }
+
+        let start = tokens.peek_span();
+        let data = StatementType::Assign(VarStatement::parse_assignment(tokens, variables, functions)?);
+
+        return Ok(Statement
+        {
+            data,
+            next: None,
+            span: tokens.span_from(start)
+        });
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct VarStatement
 {
     pub varname: String,
     pub expr: Expression
 }
+
+impl VarStatement
+{
+    /// Parses a variable assignment, in the form of Var = <Expr>;
+    pub fn parse(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>, functions: &Functions) -> Result<VarStatement, ParseError>
+    {
+        let statement = VarStatement::parse_assignment(tokens, variables, functions)?;
+
+        if !tokens.consume_operator(Operator::Semicolon)
+        {
+            return Err(ParseError::expected("';'", tokens));
+        }
+
+        return Ok(statement);
+    }
+
+    /// Parses a variable assignment without the terminating semicolon, in the form of Var = <Expr>.
+    /// Compound assignments, such as Var += <NumExpr>, are expanded to Var = Var + (<NumExpr>),
+    /// with Var += <StrExpr> appending to a string variable
+    pub fn parse_assignment(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>, functions: &Functions) -> Result<VarStatement, ParseError>
+    {
+        let start = tokens.position();
+
+        let varname = match tokens.peek()
+        {
+            Some(Token::Variable(name)) => name,
+            _ => return Err(ParseError::expected("variable name", tokens))
+        };
+        tokens.pop();
+
+        let role = match tokens.peek()
+        {
+            Some(Token::Operator(op)) => OperatorRole::of(op),
+            _ => return Err(ParseError::expected("'='", tokens))
+        };
+
+        let expr = match role
+        {
+            OperatorRole::Assignment =>
+            {
+                tokens.pop();
+                Expression::parse(tokens, variables, functions)?
+            },
+            OperatorRole::CompoundAssignment(op) =>
+            {
+                // Re-read the variable as the left-hand side of the operation, which must
+                // already hold a numeric value, or a string value for an addition
+                tokens.set_position(start);
+
+                if op == NumericOpType::Add && StringExpression::starts(tokens, variables, functions)
+                {
+                    let current = StringExpression::parse_value(tokens, variables, functions)?;
+                    tokens.pop();
+
+                    let value = StringExpression::parse(tokens, variables, functions)?;
+                    Expression::String(StringExpression::concat(current, value))
+                }
+                else
+                {
+                    let current = NumericExpression::parse_value(tokens, variables, functions)?;
+                    tokens.pop();
+
+                    let value = NumericExpression::parse(tokens, variables, functions)?;
+                    Expression::Numeric(NumericExpression::operation(op, current, value))
+                }
+            },
+            _ => return Err(ParseError::expected("'='", tokens))
+        };
+
+        // Record the variable from its first assignment, allowing following statements to read it
+        variables.entry(varname.clone()).or_insert(Variable
+        {
+            vartype: expr.variable_type(),
+            varname: varname.clone()
+        });
+
+        return Ok(VarStatement
+        {
+            varname,
+            expr
+        });
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PrintStatement
+{
+    /// Defines the expressions written on a single line, in order
+    pub exprs: Vec<Expression>
+}
+
+impl PrintStatement
+{
+    /// Parses a print statement, in the form of print <Expr>, <Expr>; with any number of
+    /// comma-separated expressions, including none to write an empty line
+    pub fn parse(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<PrintStatement, ParseError>
+    {
+        if !tokens.next_is_keyword(Keyword::Print)
+        {
+            return Err(ParseError::expected("'print'", tokens));
+        }
+        tokens.pop();
+
+        let mut exprs = Vec::new();
+
+        if !tokens.consume_operator(Operator::Semicolon)
+        {
+            loop
+            {
+                exprs.push(Expression::parse(tokens, variables, functions)?);
+
+                if tokens.consume_operator(Operator::Semicolon)
+                {
+                    break;
+                }
+
+                if !tokens.consume_operator(Operator::Comma)
+                {
+                    return Err(ParseError::expected("',' or ';'", tokens));
+                }
+            }
+        }
+
+        return Ok(PrintStatement
+        {
+            exprs
+        });
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InputStatement
+{
+    /// Defines the variable assigned the value that is read
+    pub varname: String,
+
+    /// Defines the type of value to read, given as a zero value. Variables without a previous
+    /// assignment read float values, and the type checker updates the type if the variable is
+    /// promoted
+    pub vartype: VariableType
+}
+
+impl InputStatement
+{
+    /// Parses an input statement, in the form of input Var;
+    pub fn parse(tokens: &mut TokenList, variables: &mut HashMap<String, Variable>) -> Result<InputStatement, ParseError>
+    {
+        if !tokens.next_is_keyword(Keyword::Input)
+        {
+            return Err(ParseError::expected("'input'", tokens));
+        }
+        tokens.pop();
+
+        let varname = match tokens.peek()
+        {
+            Some(Token::Variable(name)) => name,
+            _ => return Err(ParseError::expected("variable name", tokens))
+        };
+        tokens.pop();
+
+        if !tokens.consume_operator(Operator::Semicolon)
+        {
+            return Err(ParseError::expected("';'", tokens));
+        }
+
+        // Read the type of any previous assignment, or record the variable as a float
+        let vartype = variables.entry(varname.clone()).or_insert(Variable
+        {
+            vartype: VariableType::Float(0.0),
+            varname: varname.clone()
+        }).vartype.clone();
+
+        return Ok(InputStatement
+        {
+            varname,
+            vartype
+        });
+    }
+}
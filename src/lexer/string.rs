@@ -0,0 +1,219 @@
+use crate::function::Functions;
+use crate::span::Span;
+use crate::token::{Token, TokenList};
+use crate::token::symbol::Operator;
+
+use super::call::CallExpression;
+use super::error::ParseError;
+use super::operator::OperatorRole;
+use crate::variable::{Variable, VariableType};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+pub struct ConcatStringExpression
+{
+    pub a: Box<StringExpression>,
+    pub b: Box<StringExpression>
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StringExpressionType
+{
+    Concat(ConcatStringExpression),
+    Constant(String),
+    Variable(String),
+    Call(CallExpression)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StringExpression
+{
+    pub value: StringExpressionType,
+    pub span: Span
+}
+
+impl StringExpression
+{
+    /// Returns true if the next tokens start a string value, being a string literal, a string
+    /// variable, or a call to a function returning a string, after any opening parentheses. A
+    /// string variable followed by a numeric operator other than concatenation is left to be read
+    /// as a numeric value, for the type checker to report. No tokens are removed from the list
+    pub fn starts(tokens: &TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> bool
+    {
+        let mut n = 0;
+
+        while tokens.peek_nth(n) == Some(Token::Operator(Operator::LeftParen))
+        {
+            n += 1;
+        }
+
+        return match tokens.peek_nth(n)
+        {
+            Some(Token::Str(_)) => true,
+            Some(Token::Variable(name)) if tokens.peek_nth(n + 1) == Some(Token::Operator(Operator::LeftParen)) =>
+                matches!(functions.signature(&name).map(|s| &s.result), Some(VariableType::String(_))),
+            Some(Token::Variable(name)) =>
+            {
+                let numeric = match tokens.peek_nth(n + 1)
+                {
+                    Some(Token::Operator(op)) => op != Operator::Plus && matches!(OperatorRole::of(op), OperatorRole::Numeric(_)),
+                    _ => false
+                };
+
+                !numeric && matches!(variables.get(&name).map(|v| &v.vartype), Some(VariableType::String(_)))
+            },
+            _ => false
+        };
+    }
+
+    /// Parses a string expression from the token list, being values joined by left-associative
+    /// concatenation operations
+    pub fn parse(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<StringExpression, ParseError>
+    {
+        let mut a = StringExpression::parse_value(tokens, variables, functions)?;
+
+        while tokens.consume_operator(Operator::Plus)
+        {
+            let b = StringExpression::parse_value(tokens, variables, functions)?;
+            a = StringExpression::concat(a, b);
+        }
+
+        return Ok(a);
+    }
+
+    /// Creates an expression joining the text of the two expressions
+    pub fn concat(a: StringExpression, b: StringExpression) -> StringExpression
+    {
+        let span = a.span.merge(&b.span);
+
+        return StringExpression
+        {
+            value: StringExpressionType::Concat(ConcatStringExpression
+            {
+                a: Box::new(a),
+                b: Box::new(b)
+            }),
+            span
+        };
+    }
+
+    /// Parses a single string value, being a constant, a variable, a function call, or a
+    /// parenthesized expression
+    pub fn parse_value(tokens: &mut TokenList, variables: &HashMap<String, Variable>, functions: &Functions) -> Result<StringExpression, ParseError>
+    {
+        // Save the starting location of the value
+        let start = tokens.peek_span();
+
+        // Check for a parenthesized expression
+        if tokens.consume_operator(Operator::LeftParen)
+        {
+            let mut expr = super::nested(tokens, |tokens| StringExpression::parse(tokens, variables, functions))?;
+
+            if !tokens.consume_operator(Operator::RightParen)
+            {
+                return Err(ParseError::expected("')'", tokens));
+            }
+
+            expr.span = tokens.span_from(start);
+            return Ok(expr);
+        }
+
+        // Check for a call to a function returning a string value
+        if let Some(name) = CallExpression::peek(tokens)
+        {
+            if !matches!(functions.signature(&name).map(|s| &s.result), None | Some(VariableType::String(_)))
+            {
+                return Err(ParseError::expected("string expression", tokens));
+            }
+
+            let (call, span) = CallExpression::parse(tokens, variables, functions)?;
+
+            return Ok(StringExpression
+            {
+                value: StringExpressionType::Call(call),
+                span
+            });
+        }
+
+        // Read the constant or variable value
+        let value = match tokens.peek()
+        {
+            Some(Token::Str(v)) => StringExpressionType::Constant(v),
+            // Variables of other types are left for the type checker to report
+            Some(Token::Variable(name)) => match variables.get(&name)
+            {
+                Some(_) => StringExpressionType::Variable(name),
+                None => return Err(ParseError::UnassignedVariable { name, span: start })
+            },
+            _ => return Err(ParseError::expected("string expression", tokens))
+        };
+
+        // Pop the value token
+        tokens.pop();
+
+        return Ok(StringExpression
+        {
+            value,
+            span: start
+        });
+    }
+}
+
+/// Defines tests of the string expression parser
+#[cfg(test)]
+mod tests
+{
+    use super::{StringExpression, StringExpressionType};
+    use crate::function::Functions;
+    use crate::lexer::error::ParseError;
+    use crate::token::Token;
+    use crate::variable::{Variable, VariableType};
+    use std::collections::HashMap;
+
+    /// Parses the input and provides a fully-parenthesized string of the resulting expression
+    fn grouping(input: &str) -> Result<String, ParseError>
+    {
+        fn write(expr: &StringExpression) -> String
+        {
+            return match &expr.value
+            {
+                StringExpressionType::Constant(v) => Token::quote(v),
+                StringExpressionType::Variable(name) => name.clone(),
+                StringExpressionType::Call(call) => format!("{0:}(..)", call.name),
+                StringExpressionType::Concat(c) => format!("({0:} + {1:})", write(&c.a), write(&c.b))
+            };
+        }
+
+        let mut variables = HashMap::new();
+        variables.insert(String::from("s"), Variable { vartype: VariableType::String(String::new()), varname: String::from("s") });
+        variables.insert(String::from("n"), Variable { vartype: VariableType::Integer(0), varname: String::from("n") });
+
+        let mut functions = Functions::new();
+        functions.register("name", || String::from("zbasic"));
+
+        let mut tokens = Token::tokenize(input).unwrap();
+        let expr = StringExpression::parse(&mut tokens, &variables, &functions)?;
+        assert!(!tokens.available());
+        return Ok(write(&expr));
+    }
+
+    /// Tests that concatenation is left-associative, with parentheses overriding the grouping
+    #[test]
+    fn parse_concat()
+    {
+        assert_eq!(grouping("\"a\"").unwrap(), "\"a\"");
+        assert_eq!(grouping("\"a\" + s + name()").unwrap(), "((\"a\" + s) + name(..))");
+        assert_eq!(grouping("\"a\" + (s + \"b\")").unwrap(), "(\"a\" + (s + \"b\"))");
+        assert_eq!(grouping("((s))").unwrap(), "s");
+    }
+
+    /// Tests that non-string values are not read as part of a string expression
+    #[test]
+    fn parse_invalid()
+    {
+        assert!(matches!(grouping("\"a\" + true"), Err(ParseError::UnexpectedToken { expected, .. }) if expected == "string expression"));
+        assert!(matches!(grouping("\"a\" + 1"), Err(ParseError::UnexpectedToken { .. })));
+        assert!(matches!(grouping("x + \"a\""), Err(ParseError::UnassignedVariable { .. })));
+        assert!(matches!(grouping("(\"a\""), Err(ParseError::UnexpectedEnd { .. })));
+    }
+}
@@ -0,0 +1,693 @@
+use super::boolean::{BoolExpression, BoolExpressionType, ComparisonBoolExpression, OperationBoolExpression, StringComparisonBoolExpression};
+use super::call::CallExpression;
+use super::expression::Expression;
+use super::numeric::{NumericExpression, NumericExpressionType, OperationNumericExpression};
+use super::program::Program;
+use super::statement::{ForStatement, IfStatement, InputStatement, PrintStatement, Statement, StatementType, VarStatement, WhileStatement};
+use super::string::{ConcatStringExpression, StringExpression, StringExpressionType};
+
+/// Defines a pass over the syntax tree of a program. Each method visits a single type of node,
+/// by default calling the matching walk function to visit each of its children in source order.
+/// A pass overrides only the methods for the nodes it acts on, calling the walk function from
+/// the override to continue into the children
+pub trait Visitor
+{
+    /// Visits the main statements of the program
+    fn visit_program(&mut self, program: &Program)
+    {
+        self.visit_block(&program.main);
+    }
+
+    /// Visits the statement and each statement linked after it
+    fn visit_block(&mut self, first: &Statement)
+    {
+        walk_block(self, first);
+    }
+
+    /// Visits a single statement, not including the statements linked after it
+    fn visit_statement(&mut self, statement: &Statement)
+    {
+        walk_statement(self, statement);
+    }
+
+    /// Visits an if statement
+    fn visit_if(&mut self, statement: &IfStatement)
+    {
+        walk_if(self, statement);
+    }
+
+    /// Visits a while statement
+    fn visit_while(&mut self, statement: &WhileStatement)
+    {
+        walk_while(self, statement);
+    }
+
+    /// Visits a for statement
+    fn visit_for(&mut self, statement: &ForStatement)
+    {
+        walk_for(self, statement);
+    }
+
+    /// Visits a variable assignment
+    fn visit_assign(&mut self, statement: &VarStatement)
+    {
+        walk_assign(self, statement);
+    }
+
+    /// Visits a print statement
+    fn visit_print(&mut self, statement: &PrintStatement)
+    {
+        walk_print(self, statement);
+    }
+
+    /// Visits an input statement, which has no children
+    fn visit_input(&mut self, _statement: &InputStatement)
+    {
+    }
+
+    /// Visits an expression of any type
+    fn visit_expression(&mut self, expr: &Expression)
+    {
+        walk_expression(self, expr);
+    }
+
+    /// Visits a numeric expression
+    fn visit_numeric(&mut self, expr: &NumericExpression)
+    {
+        walk_numeric(self, expr);
+    }
+
+    /// Visits an operation between two numeric expressions
+    fn visit_numeric_operation(&mut self, expr: &OperationNumericExpression)
+    {
+        walk_numeric_operation(self, expr);
+    }
+
+    /// Visits a boolean expression
+    fn visit_boolean(&mut self, expr: &BoolExpression)
+    {
+        walk_boolean(self, expr);
+    }
+
+    /// Visits an operation between two boolean expressions
+    fn visit_boolean_operation(&mut self, expr: &OperationBoolExpression)
+    {
+        walk_boolean_operation(self, expr);
+    }
+
+    /// Visits a comparison between two numeric expressions
+    fn visit_comparison(&mut self, expr: &ComparisonBoolExpression)
+    {
+        walk_comparison(self, expr);
+    }
+
+    /// Visits a comparison between two string expressions
+    fn visit_string_comparison(&mut self, expr: &StringComparisonBoolExpression)
+    {
+        walk_string_comparison(self, expr);
+    }
+
+    /// Visits a string expression
+    fn visit_string(&mut self, expr: &StringExpression)
+    {
+        walk_string(self, expr);
+    }
+
+    /// Visits a concatenation of two string expressions
+    fn visit_concat(&mut self, expr: &ConcatStringExpression)
+    {
+        walk_concat(self, expr);
+    }
+
+    /// Visits a call to a host function
+    fn visit_call(&mut self, expr: &CallExpression)
+    {
+        walk_call(self, expr);
+    }
+}
+
+/// Visits each statement in the sequence, starting from the first
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, first: &Statement)
+{
+    let mut current = Some(first);
+
+    while let Some(statement) = current
+    {
+        visitor.visit_statement(statement);
+        current = statement.next.as_deref();
+    }
+}
+
+/// Visits the contents of the statement
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement)
+{
+    match &statement.data
+    {
+        StatementType::Empty => (),
+        StatementType::If(s) => visitor.visit_if(s),
+        StatementType::While(s) => visitor.visit_while(s),
+        StatementType::For(s) => visitor.visit_for(s),
+        StatementType::Assign(s) => visitor.visit_assign(s),
+        StatementType::Print(s) => visitor.visit_print(s),
+        StatementType::Input(s) => visitor.visit_input(s),
+        StatementType::Expr(e) => visitor.visit_expression(e)
+    }
+}
+
+/// Visits the condition, then the block, then the else block
+pub fn walk_if<V: Visitor + ?Sized>(visitor: &mut V, statement: &IfStatement)
+{
+    visitor.visit_boolean(&statement.boolexpr);
+    visitor.visit_block(&statement.statement);
+    visitor.visit_block(&statement.else_statement);
+}
+
+/// Visits the condition, then the block
+pub fn walk_while<V: Visitor + ?Sized>(visitor: &mut V, statement: &WhileStatement)
+{
+    visitor.visit_boolean(&statement.boolexpr);
+    visitor.visit_block(&statement.statement);
+}
+
+/// Visits the initial assignment, the condition and the step assignment, then the block
+pub fn walk_for<V: Visitor + ?Sized>(visitor: &mut V, statement: &ForStatement)
+{
+    visitor.visit_block(&statement.init);
+    visitor.visit_boolean(&statement.boolexpr);
+    visitor.visit_block(&statement.step);
+    visitor.visit_block(&statement.statement);
+}
+
+/// Visits the assigned expression
+pub fn walk_assign<V: Visitor + ?Sized>(visitor: &mut V, statement: &VarStatement)
+{
+    visitor.visit_expression(&statement.expr);
+}
+
+/// Visits each printed expression in order
+pub fn walk_print<V: Visitor + ?Sized>(visitor: &mut V, statement: &PrintStatement)
+{
+    for expr in statement.exprs.iter()
+    {
+        visitor.visit_expression(expr);
+    }
+}
+
+/// Visits the expression as its specific type
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expression)
+{
+    match expr
+    {
+        Expression::Numeric(e) => visitor.visit_numeric(e),
+        Expression::Boolean(e) => visitor.visit_boolean(e),
+        Expression::String(e) => visitor.visit_string(e)
+    }
+}
+
+/// Visits the operation or call within the numeric expression, if any
+pub fn walk_numeric<V: Visitor + ?Sized>(visitor: &mut V, expr: &NumericExpression)
+{
+    match &expr.value
+    {
+        NumericExpressionType::Operation(o) => visitor.visit_numeric_operation(o),
+        NumericExpressionType::Call(c) => visitor.visit_call(c),
+        NumericExpressionType::IntConstant(_) | NumericExpressionType::FloatConstant(_) | NumericExpressionType::Variable(_) => ()
+    }
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_numeric_operation<V: Visitor + ?Sized>(visitor: &mut V, expr: &OperationNumericExpression)
+{
+    visitor.visit_numeric(&expr.a);
+    visitor.visit_numeric(&expr.b);
+}
+
+/// Visits the operation, comparison or call within the boolean expression, if any
+pub fn walk_boolean<V: Visitor + ?Sized>(visitor: &mut V, expr: &BoolExpression)
+{
+    match &expr.value
+    {
+        BoolExpressionType::Operation(o) => visitor.visit_boolean_operation(o),
+        BoolExpressionType::Comparison(c) => visitor.visit_comparison(c),
+        BoolExpressionType::StringComparison(c) => visitor.visit_string_comparison(c),
+        BoolExpressionType::Call(c) => visitor.visit_call(c),
+        BoolExpressionType::Constant(_) | BoolExpressionType::Variable(_) => ()
+    }
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_boolean_operation<V: Visitor + ?Sized>(visitor: &mut V, expr: &OperationBoolExpression)
+{
+    visitor.visit_boolean(&expr.a);
+    visitor.visit_boolean(&expr.b);
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_comparison<V: Visitor + ?Sized>(visitor: &mut V, expr: &ComparisonBoolExpression)
+{
+    visitor.visit_numeric(&expr.a);
+    visitor.visit_numeric(&expr.b);
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_string_comparison<V: Visitor + ?Sized>(visitor: &mut V, expr: &StringComparisonBoolExpression)
+{
+    visitor.visit_string(&expr.a);
+    visitor.visit_string(&expr.b);
+}
+
+/// Visits the concatenation or call within the string expression, if any
+pub fn walk_string<V: Visitor + ?Sized>(visitor: &mut V, expr: &StringExpression)
+{
+    match &expr.value
+    {
+        StringExpressionType::Concat(c) => visitor.visit_concat(c),
+        StringExpressionType::Call(c) => visitor.visit_call(c),
+        StringExpressionType::Constant(_) | StringExpressionType::Variable(_) => ()
+    }
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_concat<V: Visitor + ?Sized>(visitor: &mut V, expr: &ConcatStringExpression)
+{
+    visitor.visit_string(&expr.a);
+    visitor.visit_string(&expr.b);
+}
+
+/// Visits each argument in order
+pub fn walk_call<V: Visitor + ?Sized>(visitor: &mut V, expr: &CallExpression)
+{
+    for arg in expr.args.iter()
+    {
+        visitor.visit_expression(arg);
+    }
+}
+
+/// Defines a pass that may modify the syntax tree of a program in place. Each method visits a
+/// single type of node, by default calling the matching walk function to visit each of its
+/// children in source order. A pass overrides only the methods for the nodes it acts on,
+/// calling the walk function from the override to continue into the children, either before
+/// changing the node to work from the innermost nodes outwards, or after
+pub trait VisitorMut
+{
+    /// Visits the main statements of the program
+    fn visit_program_mut(&mut self, program: &mut Program)
+    {
+        self.visit_block_mut(&mut program.main);
+    }
+
+    /// Visits the statement and each statement linked after it
+    fn visit_block_mut(&mut self, first: &mut Statement)
+    {
+        walk_block_mut(self, first);
+    }
+
+    /// Visits a single statement, not including the statements linked after it
+    fn visit_statement_mut(&mut self, statement: &mut Statement)
+    {
+        walk_statement_mut(self, statement);
+    }
+
+    /// Visits an if statement
+    fn visit_if_mut(&mut self, statement: &mut IfStatement)
+    {
+        walk_if_mut(self, statement);
+    }
+
+    /// Visits a while statement
+    fn visit_while_mut(&mut self, statement: &mut WhileStatement)
+    {
+        walk_while_mut(self, statement);
+    }
+
+    /// Visits a for statement
+    fn visit_for_mut(&mut self, statement: &mut ForStatement)
+    {
+        walk_for_mut(self, statement);
+    }
+
+    /// Visits a variable assignment
+    fn visit_assign_mut(&mut self, statement: &mut VarStatement)
+    {
+        walk_assign_mut(self, statement);
+    }
+
+    /// Visits a print statement
+    fn visit_print_mut(&mut self, statement: &mut PrintStatement)
+    {
+        walk_print_mut(self, statement);
+    }
+
+    /// Visits an input statement, which has no children
+    fn visit_input_mut(&mut self, _statement: &mut InputStatement)
+    {
+    }
+
+    /// Visits an expression of any type
+    fn visit_expression_mut(&mut self, expr: &mut Expression)
+    {
+        walk_expression_mut(self, expr);
+    }
+
+    /// Visits a numeric expression
+    fn visit_numeric_mut(&mut self, expr: &mut NumericExpression)
+    {
+        walk_numeric_mut(self, expr);
+    }
+
+    /// Visits an operation between two numeric expressions
+    fn visit_numeric_operation_mut(&mut self, expr: &mut OperationNumericExpression)
+    {
+        walk_numeric_operation_mut(self, expr);
+    }
+
+    /// Visits a boolean expression
+    fn visit_boolean_mut(&mut self, expr: &mut BoolExpression)
+    {
+        walk_boolean_mut(self, expr);
+    }
+
+    /// Visits an operation between two boolean expressions
+    fn visit_boolean_operation_mut(&mut self, expr: &mut OperationBoolExpression)
+    {
+        walk_boolean_operation_mut(self, expr);
+    }
+
+    /// Visits a comparison between two numeric expressions
+    fn visit_comparison_mut(&mut self, expr: &mut ComparisonBoolExpression)
+    {
+        walk_comparison_mut(self, expr);
+    }
+
+    /// Visits a comparison between two string expressions
+    fn visit_string_comparison_mut(&mut self, expr: &mut StringComparisonBoolExpression)
+    {
+        walk_string_comparison_mut(self, expr);
+    }
+
+    /// Visits a string expression
+    fn visit_string_mut(&mut self, expr: &mut StringExpression)
+    {
+        walk_string_mut(self, expr);
+    }
+
+    /// Visits a concatenation of two string expressions
+    fn visit_concat_mut(&mut self, expr: &mut ConcatStringExpression)
+    {
+        walk_concat_mut(self, expr);
+    }
+
+    /// Visits a call to a host function
+    fn visit_call_mut(&mut self, expr: &mut CallExpression)
+    {
+        walk_call_mut(self, expr);
+    }
+}
+
+/// Visits each statement in the sequence, starting from the first
+pub fn walk_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, first: &mut Statement)
+{
+    let mut current = Some(first);
+
+    while let Some(statement) = current
+    {
+        visitor.visit_statement_mut(statement);
+        current = statement.next.as_deref_mut();
+    }
+}
+
+/// Visits the contents of the statement
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement)
+{
+    match &mut statement.data
+    {
+        StatementType::Empty => (),
+        StatementType::If(s) => visitor.visit_if_mut(s),
+        StatementType::While(s) => visitor.visit_while_mut(s),
+        StatementType::For(s) => visitor.visit_for_mut(s),
+        StatementType::Assign(s) => visitor.visit_assign_mut(s),
+        StatementType::Print(s) => visitor.visit_print_mut(s),
+        StatementType::Input(s) => visitor.visit_input_mut(s),
+        StatementType::Expr(e) => visitor.visit_expression_mut(e)
+    }
+}
+
+/// Visits the condition, then the block, then the else block
+pub fn walk_if_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut IfStatement)
+{
+    visitor.visit_boolean_mut(&mut statement.boolexpr);
+    visitor.visit_block_mut(&mut statement.statement);
+    visitor.visit_block_mut(&mut statement.else_statement);
+}
+
+/// Visits the condition, then the block
+pub fn walk_while_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut WhileStatement)
+{
+    visitor.visit_boolean_mut(&mut statement.boolexpr);
+    visitor.visit_block_mut(&mut statement.statement);
+}
+
+/// Visits the initial assignment, the condition and the step assignment, then the block
+pub fn walk_for_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut ForStatement)
+{
+    visitor.visit_block_mut(&mut statement.init);
+    visitor.visit_boolean_mut(&mut statement.boolexpr);
+    visitor.visit_block_mut(&mut statement.step);
+    visitor.visit_block_mut(&mut statement.statement);
+}
+
+/// Visits the assigned expression
+pub fn walk_assign_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut VarStatement)
+{
+    visitor.visit_expression_mut(&mut statement.expr);
+}
+
+/// Visits each printed expression in order
+pub fn walk_print_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut PrintStatement)
+{
+    for expr in statement.exprs.iter_mut()
+    {
+        visitor.visit_expression_mut(expr);
+    }
+}
+
+/// Visits the expression as its specific type
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expression)
+{
+    match expr
+    {
+        Expression::Numeric(e) => visitor.visit_numeric_mut(e),
+        Expression::Boolean(e) => visitor.visit_boolean_mut(e),
+        Expression::String(e) => visitor.visit_string_mut(e)
+    }
+}
+
+/// Visits the operation or call within the numeric expression, if any
+pub fn walk_numeric_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut NumericExpression)
+{
+    match &mut expr.value
+    {
+        NumericExpressionType::Operation(o) => visitor.visit_numeric_operation_mut(o),
+        NumericExpressionType::Call(c) => visitor.visit_call_mut(c),
+        NumericExpressionType::IntConstant(_) | NumericExpressionType::FloatConstant(_) | NumericExpressionType::Variable(_) => ()
+    }
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_numeric_operation_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut OperationNumericExpression)
+{
+    visitor.visit_numeric_mut(&mut expr.a);
+    visitor.visit_numeric_mut(&mut expr.b);
+}
+
+/// Visits the operation, comparison or call within the boolean expression, if any
+pub fn walk_boolean_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut BoolExpression)
+{
+    match &mut expr.value
+    {
+        BoolExpressionType::Operation(o) => visitor.visit_boolean_operation_mut(o),
+        BoolExpressionType::Comparison(c) => visitor.visit_comparison_mut(c),
+        BoolExpressionType::StringComparison(c) => visitor.visit_string_comparison_mut(c),
+        BoolExpressionType::Call(c) => visitor.visit_call_mut(c),
+        BoolExpressionType::Constant(_) | BoolExpressionType::Variable(_) => ()
+    }
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_boolean_operation_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut OperationBoolExpression)
+{
+    visitor.visit_boolean_mut(&mut expr.a);
+    visitor.visit_boolean_mut(&mut expr.b);
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_comparison_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut ComparisonBoolExpression)
+{
+    visitor.visit_numeric_mut(&mut expr.a);
+    visitor.visit_numeric_mut(&mut expr.b);
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_string_comparison_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut StringComparisonBoolExpression)
+{
+    visitor.visit_string_mut(&mut expr.a);
+    visitor.visit_string_mut(&mut expr.b);
+}
+
+/// Visits the concatenation or call within the string expression, if any
+pub fn walk_string_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut StringExpression)
+{
+    match &mut expr.value
+    {
+        StringExpressionType::Concat(c) => visitor.visit_concat_mut(c),
+        StringExpressionType::Call(c) => visitor.visit_call_mut(c),
+        StringExpressionType::Constant(_) | StringExpressionType::Variable(_) => ()
+    }
+}
+
+/// Visits the left-hand then the right-hand operand
+pub fn walk_concat_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut ConcatStringExpression)
+{
+    visitor.visit_string_mut(&mut expr.a);
+    visitor.visit_string_mut(&mut expr.b);
+}
+
+/// Visits each argument in order
+pub fn walk_call_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut CallExpression)
+{
+    for arg in expr.args.iter_mut()
+    {
+        visitor.visit_expression_mut(arg);
+    }
+}
+
+/// Defines tests of the syntax tree visitors
+#[cfg(test)]
+mod tests
+{
+    use super::{Visitor, VisitorMut};
+    use crate::function::Functions;
+    use crate::lexer::numeric::{NumericExpression, NumericExpressionType, NumericOpType};
+    use crate::lexer::expression::Expression;
+    use crate::lexer::program::Program;
+    use crate::lexer::statement::{StatementType, VarStatement};
+    use crate::lexer::string::StringExpression;
+
+    /// Parses the source into a program that may call the test functions
+    fn parse(source: &str) -> Program
+    {
+        let mut functions = Functions::new();
+        functions.register("max", |a: f32, b: f32| a.max(b));
+        functions.register("upper", |s: String| s.to_uppercase());
+        return crate::parse_with(source, &functions).unwrap();
+    }
+
+    /// Tests that the default walk reaches every variable read within nested statements and
+    /// expressions, in source order
+    #[test]
+    fn visit_variables()
+    {
+        /// Collects the name of each variable read by the program
+        struct Reads(Vec<String>);
+
+        impl Visitor for Reads
+        {
+            fn visit_numeric(&mut self, expr: &NumericExpression)
+            {
+                if let NumericExpressionType::Variable(name) = &expr.value
+                {
+                    self.0.push(name.clone());
+                }
+
+                super::walk_numeric(self, expr);
+            }
+
+            fn visit_string(&mut self, expr: &StringExpression)
+            {
+                if let crate::lexer::string::StringExpressionType::Variable(name) = &expr.value
+                {
+                    self.0.push(name.clone());
+                }
+
+                super::walk_string(self, expr);
+            }
+        }
+
+        let program = parse(
+            "a = 1; s = \"x\";\n\
+             for (i = a; i < 3 && s + \"y\" != upper(s); i += 1) {\n\
+               while (max(a, i) > 2) { print -(a * 2), s; }\n\
+             }\n\
+             if (true) { } else { input a; b = a / i; }");
+
+        let mut reads = Reads(Vec::new());
+        reads.visit_program(&program);
+
+        assert_eq!(reads.0, vec!{"a", "i", "s", "s", "i", "a", "i", "a", "s", "a", "i"});
+    }
+
+    /// Tests that a mutable pass may replace nodes in place, working from the innermost
+    /// operations outwards
+    #[test]
+    fn visit_mut_fold()
+    {
+        /// Replaces operations between integer constants with their result, counting the
+        /// assignments visited
+        struct Fold(usize);
+
+        impl VisitorMut for Fold
+        {
+            fn visit_numeric_mut(&mut self, expr: &mut NumericExpression)
+            {
+                super::walk_numeric_mut(self, expr);
+
+                if let NumericExpressionType::Operation(o) = &expr.value
+                {
+                    if let (NumericExpressionType::IntConstant(a), NumericExpressionType::IntConstant(b)) = (&o.a.value, &o.b.value)
+                    {
+                        let value = match o.op
+                        {
+                            NumericOpType::Add => a + b,
+                            NumericOpType::Subtract => a - b,
+                            NumericOpType::Multiply => a * b,
+                            NumericOpType::Divide => a / b
+                        };
+
+                        expr.value = NumericExpressionType::IntConstant(if expr.inverted { -value } else { value });
+                        expr.inverted = false;
+                    }
+                }
+            }
+
+            fn visit_assign_mut(&mut self, statement: &mut VarStatement)
+            {
+                self.0 += 1;
+                super::walk_assign_mut(self, statement);
+            }
+        }
+
+        let mut program = parse("a = 1 + 2 * 3; if (a - (4 - 1) * 2 > 0) { a = -(2 * 5) + a; }");
+
+        let mut fold = Fold(0);
+        fold.visit_program_mut(&mut program);
+        assert_eq!(fold.0, 2);
+
+        // Check that each constant operation was folded, leaving operations on variables
+        let assign = |data: &StatementType| match data
+        {
+            StatementType::Assign(VarStatement { expr: Expression::Numeric(e), .. }) => format!("{0:?}", e.value),
+            _ => String::new()
+        };
+
+        assert_eq!(assign(&program.main.data), "IntConstant(7)");
+
+        let statement = match &program.main.next.as_ref().unwrap().data
+        {
+            StatementType::If(s) => s,
+            _ => panic!("expected if statement")
+        };
+
+        assert!(format!("{0:?}", statement.boolexpr.value).contains("IntConstant(6)"));
+        assert!(assign(&statement.statement.data).starts_with("Operation(OperationNumericExpression { op: Add, a: NumericExpression { inverted: false, value: IntConstant(-10)"));
+    }
+}
@@ -0,0 +1,186 @@
+//! ZBasic is a small imperative language with integer, float and boolean variables. The
+//! library provides each stage of processing a program, from splitting source text into tokens
+//! through to running it with the tree-walking interpreter or the bytecode virtual machine.
+//!
+//! The simplest use is to check and run a program from source:
+//!
+//! ```
+//! let program = zbasic::check("a = 2; b = a * 1.5;").unwrap();
+//! let variables = zbasic::Interpreter::new().run(&program).unwrap().clone();
+//!
+//! assert_eq!(variables["b"].vartype, zbasic::VariableType::Float(3.0));
+//! ```
+//!
+//! Host functions may be registered with the interpreter and called from programs parsed with
+//! them:
+//!
+//! ```
+//! let mut interpreter = zbasic::Interpreter::new();
+//! interpreter.register("clamp", |x: f32, lo: f32, hi: f32| x.max(lo).min(hi));
+//!
+//! let program = zbasic::check_with("a = clamp(7, 0, 2.5);", &interpreter.functions).unwrap();
+//! let variables = interpreter.run(&program).unwrap();
+//!
+//! assert_eq!(variables["a"].vartype, zbasic::VariableType::Float(2.5));
+//! ```
+
+#![allow(clippy::needless_return)]
+
+pub mod bytecode;
+pub mod checker;
+pub mod diagnostics;
+pub mod error;
+pub mod formatter;
+pub mod function;
+pub mod interpreter;
+pub mod json;
+pub mod lexer;
+pub mod schema;
+pub mod span;
+pub mod token;
+pub mod variable;
+
+pub use checker::error::TypeError;
+pub use diagnostics::Diagnostic;
+pub use error::Error;
+pub use function::{Functions, HostFunction, HostValue, Signature};
+pub use interpreter::Interpreter;
+pub use interpreter::error::RuntimeError;
+pub use json::{Json, JsonError};
+pub use lexer::error::ParseError;
+pub use lexer::program::Program;
+pub use lexer::visitor::{Visitor, VisitorMut};
+pub use span::Span;
+pub use token::{Token, TokenList};
+pub use token::error::LexError;
+pub use token::trivia::{Comment, CommentKind, Trivia};
+pub use variable::{Variable, VariableType};
+
+use std::collections::HashMap;
+
+/// Splits the source text into tokens
+pub fn tokenize(source: &str) -> Result<TokenList, LexError>
+{
+    return Token::tokenize(source);
+}
+
+/// Tokenizes and parses the source text into a program, without type checking it. Programs should
+/// be checked before being run or compiled, as mismatched values are otherwise only reported as
+/// RuntimeError::TypeMismatch once reached
+pub fn parse(source: &str) -> Result<Program, Error>
+{
+    return parse_with(source, &Functions::new());
+}
+
+/// Tokenizes and parses the source text into a program that may call the provided host
+/// functions, without type checking it. Programs should be checked before being run or compiled
+pub fn parse_with(source: &str, functions: &Functions) -> Result<Program, Error>
+{
+    let mut tokens = tokenize(source)?;
+    return Ok(Program::parse_with(&mut tokens, HashMap::new(), functions)?);
+}
+
+/// Tokenizes, parses and type checks the source text, returning a program ready to be run
+pub fn check(source: &str) -> Result<Program, Error>
+{
+    return check_with(source, &Functions::new());
+}
+
+/// Tokenizes, parses and type checks the source text, returning a program ready to be run that
+/// may call the provided host functions
+pub fn check_with(source: &str, functions: &Functions) -> Result<Program, Error>
+{
+    let mut program = parse_with(source, functions)?;
+    checker::check(&mut program)?;
+    return Ok(program);
+}
+
+/// Tokenizes and parses the source text, returning it rewritten in the canonical style with its
+/// comments kept
+pub fn format(source: &str) -> Result<String, Error>
+{
+    return format_with(source, &Functions::new());
+}
+
+/// Tokenizes and parses the source text of a program that may call the provided host functions,
+/// returning it rewritten in the canonical style with its comments kept
+pub fn format_with(source: &str, functions: &Functions) -> Result<String, Error>
+{
+    let mut tokens = Token::tokenize_with_trivia(source)?;
+    let program = Program::parse_with(&mut tokens, HashMap::new(), functions)?;
+    return Ok(formatter::format(&program, &tokens, source));
+}
+
+/// Defines tests of the library entry points
+#[cfg(test)]
+mod tests
+{
+    use super::{bytecode, lexer, Error, RuntimeError, VariableType};
+
+    /// Tests that each entry point reports errors from the stage that failed
+    #[test]
+    fn stages()
+    {
+        assert!(matches!(super::parse("a = $;"), Err(Error::Lex(_))));
+        assert!(matches!(super::parse("a = (1;"), Err(Error::Parse(_))));
+        assert!(super::parse("a = 1; a = true;").is_ok());
+        assert!(matches!(super::check("a = 1; a = true;"), Err(Error::Type(_))));
+
+        let program = super::check("a = 1; a = a / 2.0;").unwrap();
+        assert_eq!(program.variables["a"].vartype, VariableType::Float(0.0));
+    }
+
+    /// Tests that programs nested up to the parser limit are checked, run, compiled and formatted
+    /// without overflowing the stack
+    #[test]
+    fn nesting()
+    {
+        let n = lexer::MAX_NESTING;
+        let sources = [
+            format!("a = {0:}1{1:};", "(".repeat(n), ")".repeat(n)),
+            format!("a = {0:}1;", "-".repeat(n)),
+            format!("a = {0:}true;", "!".repeat(n)),
+            format!("{0:}a = 1;{1:}", "if (true) {".repeat(n), "}".repeat(n))
+        ];
+
+        for source in sources.iter()
+        {
+            let program = super::check(source).unwrap();
+
+            assert!(super::Interpreter::new().run(&program).is_ok());
+            assert!(bytecode::vm::Vm::new().run(&bytecode::compiler::compile(&program)).is_ok());
+            assert!(super::format(source).is_ok());
+        }
+    }
+
+    /// Tests that programs that were parsed without type checking report mismatched values when
+    /// run or compiled, rather than panicking
+    #[test]
+    fn unchecked()
+    {
+        let program = super::parse("a = 1; a = true; b = a + 1;").unwrap();
+
+        assert!(matches!(super::Interpreter::new().run(&program), Err(RuntimeError::TypeMismatch { .. })));
+        assert!(matches!(
+            bytecode::vm::Vm::new().run(&bytecode::compiler::compile(&program)),
+            Err(RuntimeError::TypeMismatch { .. })));
+    }
+
+    /// Tests that interpreters and virtual machines with registered functions may be moved to
+    /// another thread to run
+    #[test]
+    fn send()
+    {
+        let mut interpreter = super::Interpreter::with_io(Box::new(&b""[..]), Box::new(Vec::new()));
+        interpreter.register("twice", |x: i32| x * 2);
+        let program = super::check_with("a = twice(4);", &interpreter.functions).unwrap();
+        let chunk = bytecode::compiler::compile(&program);
+        let mut vm = bytecode::vm::Vm::with_functions(interpreter.functions.clone());
+
+        let result = std::thread::spawn(move || interpreter.run(&program).unwrap()["a"].vartype.clone());
+        assert_eq!(result.join().unwrap(), VariableType::Integer(8));
+
+        let result = std::thread::spawn(move || vm.run(&chunk).unwrap()["a"].vartype.clone());
+        assert_eq!(result.join().unwrap(), VariableType::Integer(8));
+    }
+}
@@ -1,23 +1,19 @@
-use token::Token;
+#![allow(clippy::needless_return)]
 
-mod token;
-mod lexer;
-mod variable;
+mod cli;
+mod repl;
 
 fn main()
 {
-    let q = Token::Variable("Hello".to_string());
-    println!("{0:}", q.to_string());
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-    match Token::tokenize("this   is(atest&&=||)====998 a93 Z384=3.458484")
+    let code = cli::run(&args, &mut cli::Context
     {
-        Ok(mut tokens) =>
-        {
-            while tokens.available()
-            {
-                println!("{0:}", tokens.pop().unwrap().to_string());
-            }
-        },
-        Err(e) => println!("Error: {0:}", e)
-    }
+        input: &mut std::io::stdin(),
+        out: &mut std::io::stdout(),
+        err: &mut std::io::stderr(),
+        color: zbasic::diagnostics::use_color()
+    });
+
+    std::process::exit(code);
 }
@@ -0,0 +1,368 @@
+use zbasic::checker;
+use zbasic::diagnostics::Diagnostic;
+use zbasic::interpreter::Interpreter;
+use zbasic::lexer::program::Program;
+use zbasic::lexer::statement::StatementType;
+use zbasic::token::Token;
+use zbasic::token::symbol::Operator;
+use zbasic::variable::Variable;
+
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Defines the prompt shown for the first line of an entry
+const PROMPT: &str = "zbasic> ";
+
+/// Defines the prompt shown while waiting for the remaining lines of an entry
+const CONTINUE_PROMPT: &str = "   ...> ";
+
+/// Defines the help text listing the meta-commands
+const HELP: &str = "\
+:vars            list the current variables
+:tokens [code]   print the tokens of the code, or of the previous entry
+:ast [code]      print the syntax tree of the code, or of the previous entry
+:reset           remove all variables
+:help            print this message
+:quit            exit the session
+";
+
+/// Defines an interactive session, where variables persist between entries
+pub struct Repl
+{
+    /// Defines the interpreter holding the variable values
+    interpreter: Interpreter<'static>,
+
+    /// Defines the variable types, used to parse and check later entries
+    variables: HashMap<String, Variable>,
+
+    /// Defines the most recent entry that was not a meta-command
+    last: String,
+
+    /// Indicates that the session should end
+    pub done: bool,
+
+    /// Indicates that errors should be written with ANSI colors
+    color: bool
+}
+
+impl Repl
+{
+    /// Returns a new session with no variables defined
+    pub fn new(color: bool) -> Repl
+    {
+        return Repl
+        {
+            interpreter: Interpreter::new(),
+            variables: HashMap::new(),
+            last: String::new(),
+            done: false,
+            color
+        };
+    }
+
+    /// Returns true if the input has more opening braces or parentheses than closing ones, such
+    /// that the entry continues onto the next line. Input that cannot be tokenized is complete,
+    /// allowing the error to be reported
+    pub fn needs_more(input: &str) -> bool
+    {
+        let tokens = match Token::tokenize(input)
+        {
+            Ok(t) => t,
+            Err(_) => return false
+        };
+
+        let mut depth: i32 = 0;
+
+        for token in tokens.tokens.iter()
+        {
+            match token
+            {
+                Token::Operator(Operator::LeftBrace) | Token::Operator(Operator::LeftParen) => depth += 1,
+                Token::Operator(Operator::RightBrace) | Token::Operator(Operator::RightParen) => depth -= 1,
+                _ => ()
+            }
+        }
+
+        return depth > 0;
+    }
+
+    /// Handles a complete entry, being either a meta-command or program text, writing any
+    /// results or errors to the output
+    pub fn handle(&mut self, entry: &str, out: &mut dyn Write) -> std::io::Result<()>
+    {
+        let trimmed = entry.trim();
+
+        if trimmed.is_empty()
+        {
+            return Ok(());
+        }
+
+        // Split meta-commands from their optional argument
+        if let Some(command) = trimmed.strip_prefix(':')
+        {
+            let (name, argument) = match command.find(char::is_whitespace)
+            {
+                Some(i) => (&command[..i], command[i..].trim()),
+                None => (command, "")
+            };
+
+            let source = if argument.is_empty() { self.last.clone() } else { argument.to_string() };
+
+            return match name
+            {
+                "vars" => self.write_vars(out),
+                "tokens" => self.write_tokens(&source, out),
+                "ast" => self.write_ast(&source, out),
+                "reset" =>
+                {
+                    self.interpreter = Interpreter::new();
+                    self.variables.clear();
+                    writeln!(out, "variables cleared")
+                },
+                "help" => write!(out, "{0:}", HELP),
+                "quit" | "exit" | "q" =>
+                {
+                    self.done = true;
+                    Ok(())
+                },
+                _ => writeln!(out, "unknown command :{0:}, try :help", name)
+            };
+        }
+
+        self.last = entry.to_string();
+        return self.execute(entry, out);
+    }
+
+    /// Parses, checks and runs the entry, writing the value of a lone expression statement
+    fn execute(&mut self, entry: &str, out: &mut dyn Write) -> std::io::Result<()>
+    {
+        let mut tokens = match Token::tokenize(entry)
+        {
+            Ok(t) => t,
+            Err(e) => return self.report(Diagnostic::from(&e), entry, out)
+        };
+
+        let mut program = match Program::parse_with(&mut tokens, self.variables.clone(), &self.interpreter.functions)
+        {
+            Ok(p) => p,
+            Err(e) => return self.report(Diagnostic::from(&e), entry, out)
+        };
+
+        if let Err(e) = checker::check_with(&mut program, &self.variables)
+        {
+            return self.report(Diagnostic::from(&e), entry, out);
+        }
+
+        // Keep the variable types even if the program fails at runtime, as the interpreter will
+        // have stored any values assigned before the error
+        self.variables = program.variables.clone();
+
+        // Write the value of a lone expression instead of discarding it
+        if let (StatementType::Expr(expr), None) = (&program.main.data, &program.main.next)
+        {
+            return match self.interpreter.evaluate(expr)
+            {
+                Ok(value) => writeln!(out, "{0:}", value),
+                Err(e) => self.report(Diagnostic::from(&e), entry, out)
+            };
+        }
+
+        return match self.interpreter.run(&program)
+        {
+            Ok(_) => Ok(()),
+            Err(e) => self.report(Diagnostic::from(&e), entry, out)
+        };
+    }
+
+    /// Writes each variable with its value and type, sorted by name
+    fn write_vars(&self, out: &mut dyn Write) -> std::io::Result<()>
+    {
+        let mut names: Vec<&String> = self.interpreter.variables.keys().collect();
+        names.sort();
+
+        for name in names
+        {
+            let value = &self.interpreter.variables[name].vartype;
+            writeln!(out, "{0:} = {1:} ({2:})", name, value, value.type_name())?;
+        }
+
+        return Ok(());
+    }
+
+    /// Writes the tokens of the source, one per line
+    fn write_tokens(&self, source: &str, out: &mut dyn Write) -> std::io::Result<()>
+    {
+        return match Token::tokenize(source)
+        {
+            Ok(tokens) =>
+            {
+                for (token, span) in tokens.tokens.iter().zip(tokens.spans.iter())
+                {
+                    writeln!(out, "{0:>7}  {1:}", span.to_string(), token)?;
+                }
+
+                Ok(())
+            },
+            Err(e) => self.report(Diagnostic::from(&e), source, out)
+        };
+    }
+
+    /// Writes the syntax tree of the source, resolving variables against the current session
+    fn write_ast(&self, source: &str, out: &mut dyn Write) -> std::io::Result<()>
+    {
+        let mut tokens = match Token::tokenize(source)
+        {
+            Ok(t) => t,
+            Err(e) => return self.report(Diagnostic::from(&e), source, out)
+        };
+
+        return match Program::parse_with(&mut tokens, self.variables.clone(), &self.interpreter.functions)
+        {
+            Ok(program) => writeln!(out, "{0:#?}", program.main),
+            Err(e) => self.report(Diagnostic::from(&e), source, out)
+        };
+    }
+
+    /// Writes the diagnostic for an error within the source
+    fn report(&self, diagnostic: Diagnostic, source: &str, out: &mut dyn Write) -> std::io::Result<()>
+    {
+        return write!(out, "{0:}", diagnostic.render(source, "<repl>", self.color));
+    }
+}
+
+/// Runs an interactive session on the terminal until the input ends or :quit is entered.
+/// Entries are read with line editing and history, and continue across lines until all braces
+/// and parentheses are closed
+pub fn run() -> Result<(), ReadlineError>
+{
+    let mut editor = DefaultEditor::new()?;
+    let mut repl = Repl::new(zbasic::diagnostics::use_color());
+    let mut stdout = std::io::stdout();
+
+    println!("ZBasic {0:} - enter :help for commands", env!("CARGO_PKG_VERSION"));
+
+    while !repl.done
+    {
+        // Read lines until the entry is complete
+        let mut entry = String::new();
+
+        loop
+        {
+            let prompt = if entry.is_empty() { PROMPT } else { CONTINUE_PROMPT };
+
+            match editor.readline(prompt)
+            {
+                Ok(line) =>
+                {
+                    entry.push_str(&line);
+                    entry.push('\n');
+
+                    if !Repl::needs_more(&entry)
+                    {
+                        break;
+                    }
+                },
+
+                // Discard the current entry on Ctrl-C
+                Err(ReadlineError::Interrupted) =>
+                {
+                    entry.clear();
+                    break;
+                },
+
+                // End the session on Ctrl-D
+                Err(ReadlineError::Eof) => return Ok(()),
+                Err(e) => return Err(e)
+            }
+        }
+
+        if !entry.trim().is_empty()
+        {
+            editor.add_history_entry(entry.trim_end())?;
+        }
+
+        repl.handle(&entry, &mut stdout)?;
+        stdout.flush()?;
+    }
+
+    return Ok(());
+}
+
+/// Defines tests of the interactive session
+#[cfg(test)]
+mod tests
+{
+    use super::Repl;
+
+    /// Handles each entry in order, returning the output of the final entry
+    fn session(repl: &mut Repl, entries: &[&str]) -> String
+    {
+        let mut output: Vec<u8> = Vec::new();
+
+        for entry in entries.iter()
+        {
+            output.clear();
+            repl.handle(entry, &mut output).unwrap();
+        }
+
+        return String::from_utf8(output).unwrap();
+    }
+
+    /// Tests that entries continue while braces or parentheses are unbalanced
+    #[test]
+    fn needs_more()
+    {
+        assert!(Repl::needs_more("if (a < 3) {\n"));
+        assert!(Repl::needs_more("a = (1 +\n"));
+        assert!(Repl::needs_more("while (true) {\n  if (false) { }\n"));
+        assert!(!Repl::needs_more("if (a < 3) {\n}\n"));
+        assert!(!Repl::needs_more("a = 1;"));
+        assert!(!Repl::needs_more("}"));
+        assert!(!Repl::needs_more("{ a = 1$"));
+    }
+
+    /// Tests that variables persist between entries and are listed with their types
+    #[test]
+    fn persistent_variables()
+    {
+        let mut repl = Repl::new(false);
+
+        assert_eq!(session(&mut repl, &["a = 1;", "b = a + 1.5;", "c = b > a;", ":vars"]), "a = 1 (integer)\nb = 2.5 (float)\nc = true (boolean)\n");
+        assert_eq!(session(&mut repl, &["a * 4;"]), "4\n");
+        assert_eq!(session(&mut repl, &["for (i = 0; i < 3; i += 1) {\n  a *= 2;\n}\n", "a;"]), "8\n");
+        assert_eq!(session(&mut repl, &[":reset", ":vars"]), "");
+        assert!(session(&mut repl, &["a;"]).starts_with("error[E0201]: use of unassigned variable `a`"));
+    }
+
+    /// Tests that errors are written without ending the session
+    #[test]
+    fn errors()
+    {
+        let mut repl = Repl::new(false);
+
+        assert!(session(&mut repl, &["a = 1 / 0;"]).starts_with("error[E0301]: division by zero"));
+        assert!(session(&mut repl, &["a = 1;", "a = true;"]).starts_with("error[E0202]"));
+        assert!(session(&mut repl, &["b = $;"]).starts_with("error[E0003]"));
+        assert!(session(&mut repl, &[":nothing"]).starts_with("unknown command"));
+        assert_eq!(session(&mut repl, &["a + 1;"]), "2\n");
+        assert!(!repl.done);
+
+        session(&mut repl, &[":quit"]);
+        assert!(repl.done);
+    }
+
+    /// Tests that tokens and syntax trees are written for the argument or the previous entry
+    #[test]
+    fn inspect()
+    {
+        let mut repl = Repl::new(false);
+
+        assert_eq!(session(&mut repl, &[":tokens a = 1;"]), "    1:1  (variable a)\n    1:3  (operator '=')\n    1:5  (int 1)\n    1:6  (operator ';')\n");
+        assert_eq!(session(&mut repl, &["x = 2;", ":tokens"]), "    1:1  (variable x)\n    1:3  (operator '=')\n    1:5  (int 2)\n    1:6  (operator ';')\n");
+        assert!(session(&mut repl, &[":ast x + 1;"]).contains("Variable(\n"));
+        assert!(session(&mut repl, &[":ast y;"]).starts_with("error[E0201]"));
+    }
+}
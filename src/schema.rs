@@ -0,0 +1,1070 @@
+//! Converts tokens and programs to and from JSON, and writes any JSON value as an S-expression,
+//! for exchange with external tools. The schema is versioned, and fields are only added to it
+//! within a version. Fields are written in the order listed below.
+//!
+//! # Common values
+//!
+//! - Span: `{"start": 0, "end": 6, "line": 1, "column": 1}`, with byte offsets for the start and
+//!   end, and the line and column of the start counted from one.
+//! - Type: one of `"boolean"`, `"integer"`, `"float"` or `"string"`.
+//!
+//! # Tokens
+//!
+//! `{"kind": "tokens", "version": 1, "tokens": [Token]}`, where each token is
+//! `{"kind": kind, "value": value, "span": Span}` with one of the kinds:
+//!
+//! - `"int"` or `"float"` with a number value.
+//! - `"bool"` with a boolean value.
+//! - `"string"` with the text of the literal, after replacing escape sequences.
+//! - `"keyword"` or `"operator"` with the source text.
+//! - `"variable"` with the name.
+//!
+//! # Programs
+//!
+//! `{"kind": "program", "version": 1, "variables": [Variable], "statements": [Statement]}`, where
+//! each variable is `{"name": name, "type": Type}`, sorted by name, with the type of its first
+//! assignment. Each statement has a `"kind"` and a `"span"` followed by the fields of its kind:
+//!
+//! - `"if"`: `"condition"` boolean expression, `"then"` and `"else"` statement lists.
+//! - `"while"`: `"condition"` boolean expression, `"body"` statement list.
+//! - `"for"`: `"init"` assign statement or null, `"condition"` boolean expression, `"step"`
+//!   assign statement or null, `"body"` statement list.
+//! - `"assign"`: `"name"` of the variable, `"value"` expression. Compound assignments are
+//!   written as an operation on the variable.
+//! - `"print"`: `"values"` expression list.
+//! - `"input"`: `"name"` of the variable, `"type"` of the value read.
+//! - `"expression"`: `"value"` expression.
+//!
+//! Each expression has a `"kind"`, the `"type"` of its value and a `"span"`. Numeric and boolean
+//! expressions then have `"inverted"`, being true if the value is negated or inverted, followed
+//! by the fields of the kind:
+//!
+//! - `"operation"`: `"op"` source text, `"a"` and `"b"` operands of the same type class.
+//!   Numeric operations are `+`, `-`, `*` or `/`, and boolean operations are `&&` or `||`.
+//! - `"comparison"`: boolean, with `"op"` one of `==`, `!=`, `<`, `>`, `<=` or `>=`, and
+//!   `"a"` and `"b"` numeric expressions.
+//! - `"string_comparison"`: boolean, with `"op"` one of `==` or `!=`, and `"a"` and `"b"` string
+//!   expressions.
+//! - `"concat"`: string, with `"a"` and `"b"` string expressions.
+//! - `"constant"`: `"value"` number, boolean or string.
+//! - `"variable"`: `"name"` of the variable.
+//! - `"call"`: `"name"` of the function, `"args"` expression list, and `"signature"` being
+//!   `{"params": [Type], "result": Type}`.
+//!
+//! # S-expressions
+//!
+//! The S-expression form holds the same values as the JSON form. An object is a list of its
+//! kind, if it has one, followed by a `:name value` pair for each other field. An array is a
+//! list of its items. Strings are quoted as ZBasic string literals, and null is `nil`.
+
+use crate::function::Signature;
+use crate::json::{Json, JsonError};
+use crate::lexer::boolean::{BoolExpression, BoolExpressionType, ComparisonBoolExpression, ComparisonOpType, OperationBoolExpression, StringComparisonBoolExpression};
+use crate::lexer::call::CallExpression;
+use crate::lexer::expression::Expression;
+use crate::lexer::numeric::{NumericExpression, NumericExpressionDataType, NumericExpressionType, OperationNumericExpression};
+use crate::lexer::operator::OperatorRole;
+use crate::lexer::program::Program;
+use crate::lexer::statement::{ForStatement, IfStatement, InputStatement, PrintStatement, Statement, StatementType, VarStatement, WhileStatement};
+use crate::lexer::string::{ConcatStringExpression, StringExpression, StringExpressionType};
+use crate::span::Span;
+use crate::token::{Token, TokenList};
+use crate::token::symbol::Operator;
+use crate::variable::{Variable, VariableType};
+
+use std::collections::HashMap;
+
+/// Defines the version of the schema written, and the only version read
+pub const VERSION: i32 = 1;
+
+/// Provides the tokens in the JSON schema
+pub fn tokens_to_json(tokens: &TokenList) -> Json
+{
+    let items = tokens.tokens.iter().zip(tokens.spans.iter()).map(|(token, span)|
+    {
+        let (kind, value) = match token
+        {
+            Token::Int(v) => ("int", Json::Number(v.to_string())),
+            Token::Float(v) => ("float", Json::float(*v)),
+            Token::Bool(v) => ("bool", Json::Bool(*v)),
+            Token::Str(v) => ("string", Json::String(v.clone())),
+            Token::Keyword(v) => ("keyword", Json::String(v.to_string())),
+            Token::Operator(v) => ("operator", Json::String(v.to_string())),
+            Token::Variable(v) => ("variable", Json::String(v.clone()))
+        };
+
+        return Json::object(vec!{
+            ("kind", Json::String(kind.to_string())),
+            ("value", value),
+            ("span", span_to_json(span))
+        });
+    });
+
+    return Json::object(vec!{
+        ("kind", Json::String(String::from("tokens"))),
+        ("version", Json::int(VERSION)),
+        ("tokens", Json::Array(items.collect()))
+    });
+}
+
+/// Provides the program in the JSON schema. A schema error is returned if the program is nested
+/// too deeply to be read back, such as by a long chain of operations, as the depth of arrays and
+/// objects is limited to json::MAX_DEPTH
+pub fn program_to_json(program: &Program) -> Result<Json, JsonError>
+{
+    let mut names: Vec<&String> = program.variables.keys().collect();
+    names.sort();
+
+    let variables = names.into_iter().map(|name| Json::object(vec!{
+        ("name", Json::String(name.clone())),
+        ("type", type_to_json(&program.variables[name].vartype))
+    }));
+
+    let json = Json::object(vec!{
+        ("kind", Json::String(String::from("program"))),
+        ("version", Json::int(VERSION)),
+        ("variables", Json::Array(variables.collect())),
+        ("statements", statements_to_json(&program.main))
+    });
+
+    json.check_depth()?;
+    return Ok(json);
+}
+
+/// Provides the span in the JSON schema
+fn span_to_json(span: &Span) -> Json
+{
+    return Json::object(vec!{
+        ("start", Json::Number(span.start.to_string())),
+        ("end", Json::Number(span.end.to_string())),
+        ("line", Json::Number(span.line.to_string())),
+        ("column", Json::Number(span.column.to_string()))
+    });
+}
+
+/// Provides the name of the type in the JSON schema
+fn type_to_json(vartype: &VariableType) -> Json
+{
+    return Json::String(vartype.type_name().to_string());
+}
+
+/// Provides the statement and each statement linked after it as a list, leaving out empty
+/// statements
+fn statements_to_json(first: &Statement) -> Json
+{
+    let mut items = Vec::new();
+    let mut current = Some(first);
+
+    while let Some(statement) = current
+    {
+        if statement.data != StatementType::Empty
+        {
+            items.push(statement_to_json(statement));
+        }
+
+        current = statement.next.as_deref();
+    }
+
+    return Json::Array(items);
+}
+
+/// Provides a single statement, not including the statements linked after it
+fn statement_to_json(statement: &Statement) -> Json
+{
+    let (kind, mut fields) = match &statement.data
+    {
+        StatementType::Empty => return Json::Null,
+        StatementType::If(s) => ("if", vec!{
+            ("condition", boolean_to_json(&s.boolexpr)),
+            ("then", statements_to_json(&s.statement)),
+            ("else", statements_to_json(&s.else_statement))
+        }),
+        StatementType::While(s) => ("while", vec!{
+            ("condition", boolean_to_json(&s.boolexpr)),
+            ("body", statements_to_json(&s.statement))
+        }),
+        StatementType::For(s) => ("for", vec!{
+            ("init", statement_to_json(&s.init)),
+            ("condition", boolean_to_json(&s.boolexpr)),
+            ("step", statement_to_json(&s.step)),
+            ("body", statements_to_json(&s.statement))
+        }),
+        StatementType::Assign(s) => ("assign", vec!{
+            ("name", Json::String(s.varname.clone())),
+            ("value", expression_to_json(&s.expr))
+        }),
+        StatementType::Print(s) => ("print", vec!{
+            ("values", Json::Array(s.exprs.iter().map(expression_to_json).collect()))
+        }),
+        StatementType::Input(s) => ("input", vec!{
+            ("name", Json::String(s.varname.clone())),
+            ("type", type_to_json(&s.vartype))
+        }),
+        StatementType::Expr(e) => ("expression", vec!{
+            ("value", expression_to_json(e))
+        })
+    };
+
+    fields.insert(0, ("kind", Json::String(kind.to_string())));
+    fields.insert(1, ("span", span_to_json(&statement.span)));
+    return Json::object(fields);
+}
+
+/// Provides an expression of any type
+fn expression_to_json(expr: &Expression) -> Json
+{
+    return match expr
+    {
+        Expression::Numeric(e) => numeric_to_json(e),
+        Expression::Boolean(e) => boolean_to_json(e),
+        Expression::String(e) => string_to_json(e)
+    };
+}
+
+/// Provides the common fields of an expression followed by the fields of its kind
+fn expression_object(kind: &str, vartype: &str, span: &Span, inverted: Option<bool>, fields: Vec<(&str, Json)>) -> Json
+{
+    let mut all = vec!{
+        ("kind", Json::String(kind.to_string())),
+        ("type", Json::String(vartype.to_string())),
+        ("span", span_to_json(span))
+    };
+
+    if let Some(inverted) = inverted
+    {
+        all.push(("inverted", Json::Bool(inverted)));
+    }
+
+    all.extend(fields);
+    return Json::object(all);
+}
+
+/// Provides the fields of a call
+fn call_fields(call: &CallExpression) -> Vec<(&'static str, Json)>
+{
+    return vec!{
+        ("name", Json::String(call.name.clone())),
+        ("args", Json::Array(call.args.iter().map(expression_to_json).collect())),
+        ("signature", Json::object(vec!{
+            ("params", Json::Array(call.signature.params.iter().map(type_to_json).collect())),
+            ("result", type_to_json(&call.signature.result))
+        }))
+    };
+}
+
+/// Provides a numeric expression
+fn numeric_to_json(expr: &NumericExpression) -> Json
+{
+    let (kind, fields) = match &expr.value
+    {
+        NumericExpressionType::Operation(o) => ("operation", vec!{
+            ("op", Json::String(OperatorRole::Numeric(o.op).symbol().to_string())),
+            ("a", numeric_to_json(&o.a)),
+            ("b", numeric_to_json(&o.b))
+        }),
+        NumericExpressionType::IntConstant(v) => ("constant", vec!{("value", Json::int(*v))}),
+        NumericExpressionType::FloatConstant(v) => ("constant", vec!{("value", Json::float(*v))}),
+        NumericExpressionType::Variable(name) => ("variable", vec!{("name", Json::String(name.clone()))}),
+        NumericExpressionType::Call(c) => ("call", call_fields(c))
+    };
+
+    let vartype = match expr.data_type
+    {
+        NumericExpressionDataType::Int => "integer",
+        NumericExpressionDataType::Float => "float"
+    };
+
+    return expression_object(kind, vartype, &expr.span, Some(expr.inverted), fields);
+}
+
+/// Provides a boolean expression
+fn boolean_to_json(expr: &BoolExpression) -> Json
+{
+    let (kind, fields) = match &expr.value
+    {
+        BoolExpressionType::Operation(o) => ("operation", vec!{
+            ("op", Json::String(OperatorRole::Boolean(o.op).symbol().to_string())),
+            ("a", boolean_to_json(&o.a)),
+            ("b", boolean_to_json(&o.b))
+        }),
+        BoolExpressionType::Comparison(c) => ("comparison", vec!{
+            ("op", Json::String(OperatorRole::Comparison(c.op).symbol().to_string())),
+            ("a", numeric_to_json(&c.a)),
+            ("b", numeric_to_json(&c.b))
+        }),
+        BoolExpressionType::StringComparison(c) => ("string_comparison", vec!{
+            ("op", Json::String(OperatorRole::Comparison(c.op).symbol().to_string())),
+            ("a", string_to_json(&c.a)),
+            ("b", string_to_json(&c.b))
+        }),
+        BoolExpressionType::Constant(v) => ("constant", vec!{("value", Json::Bool(*v))}),
+        BoolExpressionType::Variable(name) => ("variable", vec!{("name", Json::String(name.clone()))}),
+        BoolExpressionType::Call(c) => ("call", call_fields(c))
+    };
+
+    return expression_object(kind, "boolean", &expr.span, Some(expr.inverted), fields);
+}
+
+/// Provides a string expression
+fn string_to_json(expr: &StringExpression) -> Json
+{
+    let (kind, fields) = match &expr.value
+    {
+        StringExpressionType::Concat(c) => ("concat", vec!{
+            ("a", string_to_json(&c.a)),
+            ("b", string_to_json(&c.b))
+        }),
+        StringExpressionType::Constant(v) => ("constant", vec!{("value", Json::String(v.clone()))}),
+        StringExpressionType::Variable(name) => ("variable", vec!{("name", Json::String(name.clone()))}),
+        StringExpressionType::Call(c) => ("call", call_fields(c))
+    };
+
+    return expression_object(kind, "string", &expr.span, None, fields);
+}
+
+/// Rebuilds a program from the JSON schema. The structure of the program is checked against
+/// the schema, but the program is not type checked, as the types written in the JSON may not
+/// match its values. Callers must check the program with checker::check before running or
+/// compiling it, as mismatched values are otherwise only reported as RuntimeError::TypeMismatch
+/// once reached
+pub fn program_from_json(json: &Json) -> Result<Program, JsonError>
+{
+    let path = "$";
+
+    if field(json, "kind", path)?.as_str() != Some("program")
+    {
+        return Err(JsonError::schema("kind \"program\"", &child(path, "kind")));
+    }
+
+    if field(json, "version", path)?.as_i32() != Some(VERSION)
+    {
+        return Err(JsonError::schema(&format!("version {0:}", VERSION), &child(path, "version")));
+    }
+
+    let mut variables = HashMap::new();
+    let variables_path = child(path, "variables");
+
+    for (i, item) in array(field(json, "variables", path)?, &variables_path)?.iter().enumerate()
+    {
+        let item_path = index(&variables_path, i);
+        let varname = string_field(item, "name", &item_path)?;
+        let vartype = type_from_json(field(item, "type", &item_path)?, &child(&item_path, "type"))?;
+
+        variables.insert(varname.clone(), Variable
+        {
+            vartype,
+            varname
+        });
+    }
+
+    return Ok(Program
+    {
+        main: statements_from_json(field(json, "statements", path)?, &child(path, "statements"))?,
+        variables
+    });
+}
+
+/// Returns the path of the field of the object at the path
+fn child(path: &str, key: &str) -> String
+{
+    return format!("{0:}.{1:}", path, key);
+}
+
+/// Returns the path of the item of the array at the path
+fn index(path: &str, i: usize) -> String
+{
+    return format!("{0:}[{1:}]", path, i);
+}
+
+/// Returns the field of the object at the path
+fn field<'a>(json: &'a Json, key: &str, path: &str) -> Result<&'a Json, JsonError>
+{
+    if !matches!(json, Json::Object(_))
+    {
+        return Err(JsonError::schema("object", path));
+    }
+
+    return json.get(key).ok_or_else(|| JsonError::schema(&format!("field \"{0:}\"", key), path));
+}
+
+/// Returns the items of the array at the path
+fn array<'a>(json: &'a Json, path: &str) -> Result<&'a [Json], JsonError>
+{
+    return json.as_array().ok_or_else(|| JsonError::schema("array", path));
+}
+
+/// Returns the text of the string field of the object at the path
+fn string_field(json: &Json, key: &str, path: &str) -> Result<String, JsonError>
+{
+    return match field(json, key, path)?.as_str()
+    {
+        Some(v) => Ok(v.to_string()),
+        None => Err(JsonError::schema("string", &child(path, key)))
+    };
+}
+
+/// Returns the value of the boolean field of the object at the path
+fn bool_field(json: &Json, key: &str, path: &str) -> Result<bool, JsonError>
+{
+    return field(json, key, path)?.as_bool().ok_or_else(|| JsonError::schema("boolean", &child(path, key)));
+}
+
+/// Reads a span from the object at the path
+fn span_from_json(json: &Json, path: &str) -> Result<Span, JsonError>
+{
+    let number = |key: &str| field(json, key, path)?
+        .as_usize()
+        .ok_or_else(|| JsonError::schema("non-negative integer", &child(path, key)));
+
+    return Ok(Span::new(number("start")?, number("end")?, number("line")?, number("column")?));
+}
+
+/// Reads a type name, providing the zero value of the type
+fn type_from_json(json: &Json, path: &str) -> Result<VariableType, JsonError>
+{
+    return match json.as_str()
+    {
+        Some("boolean") => Ok(VariableType::Boolean(false)),
+        Some("integer") => Ok(VariableType::Integer(0)),
+        Some("float") => Ok(VariableType::Float(0.0)),
+        Some("string") => Ok(VariableType::String(String::new())),
+        _ => Err(JsonError::schema("type name", path))
+    };
+}
+
+/// Reads the operator text of the operation at the path, providing its role
+fn operator_from_json(json: &Json, path: &str) -> Result<OperatorRole, JsonError>
+{
+    return match field(json, "op", path)?.as_str().and_then(Operator::lookup)
+    {
+        Some(op) => Ok(OperatorRole::of(op)),
+        None => Err(JsonError::schema("operator", &child(path, "op")))
+    };
+}
+
+/// Reads a list of statements, linking each statement to the next
+fn statements_from_json(json: &Json, path: &str) -> Result<Statement, JsonError>
+{
+    let mut next: Option<Box<Statement>> = None;
+
+    for (i, item) in array(json, path)?.iter().enumerate().rev()
+    {
+        let mut statement = statement_from_json(item, &index(path, i))?;
+        statement.next = next;
+        next = Some(Box::new(statement));
+    }
+
+    return match next
+    {
+        Some(s) => Ok(*s),
+        None => Ok(Statement::empty())
+    };
+}
+
+/// Reads the optional assignment of a for statement header, providing an empty statement for null
+fn header_from_json(json: &Json, key: &str, path: &str) -> Result<Statement, JsonError>
+{
+    let value = field(json, key, path)?;
+    let path = child(path, key);
+
+    if *value == Json::Null
+    {
+        return Ok(Statement::empty());
+    }
+
+    let statement = statement_from_json(value, &path)?;
+
+    if !matches!(statement.data, StatementType::Assign(_))
+    {
+        return Err(JsonError::schema("assign statement or null", &path));
+    }
+
+    return Ok(statement);
+}
+
+/// Reads a single statement
+fn statement_from_json(json: &Json, path: &str) -> Result<Statement, JsonError>
+{
+    let kind = string_field(json, "kind", path)?;
+    let span = span_from_json(field(json, "span", path)?, &child(path, "span"))?;
+
+    // Reads the expression, boolean condition or statement list in the field
+    let value = |key: &str| expression_from_json(field(json, key, path)?, &child(path, key));
+    let condition = || boolean_from_json(field(json, "condition", path)?, &child(path, "condition"));
+    let block = |key: &str| statements_from_json(field(json, key, path)?, &child(path, key)).map(Box::new);
+
+    let data = match kind.as_str()
+    {
+        "if" => StatementType::If(IfStatement
+        {
+            boolexpr: condition()?,
+            statement: block("then")?,
+            else_statement: block("else")?
+        }),
+        "while" => StatementType::While(WhileStatement
+        {
+            boolexpr: condition()?,
+            statement: block("body")?
+        }),
+        "for" => StatementType::For(ForStatement
+        {
+            init: Box::new(header_from_json(json, "init", path)?),
+            boolexpr: condition()?,
+            step: Box::new(header_from_json(json, "step", path)?),
+            statement: block("body")?
+        }),
+        "assign" => StatementType::Assign(VarStatement
+        {
+            varname: string_field(json, "name", path)?,
+            expr: value("value")?
+        }),
+        "print" =>
+        {
+            let values_path = child(path, "values");
+            let exprs = array(field(json, "values", path)?, &values_path)?
+                .iter()
+                .enumerate()
+                .map(|(i, v)| expression_from_json(v, &index(&values_path, i)))
+                .collect::<Result<Vec<Expression>, JsonError>>()?;
+
+            StatementType::Print(PrintStatement
+            {
+                exprs
+            })
+        },
+        "input" => StatementType::Input(InputStatement
+        {
+            varname: string_field(json, "name", path)?,
+            vartype: type_from_json(field(json, "type", path)?, &child(path, "type"))?
+        }),
+        "expression" => StatementType::Expr(value("value")?),
+        _ => return Err(JsonError::schema("statement kind", &child(path, "kind")))
+    };
+
+    return Ok(Statement
+    {
+        data,
+        next: None,
+        span
+    });
+}
+
+/// Reads an expression of any type
+fn expression_from_json(json: &Json, path: &str) -> Result<Expression, JsonError>
+{
+    return match field(json, "type", path)?.as_str()
+    {
+        Some("boolean") => Ok(Expression::Boolean(boolean_from_json(json, path)?)),
+        Some("string") => Ok(Expression::String(string_from_json(json, path)?)),
+        Some("integer") | Some("float") => Ok(Expression::Numeric(numeric_from_json(json, path)?)),
+        _ => Err(JsonError::schema("type name", &child(path, "type")))
+    };
+}
+
+/// Reads a call to a host function
+fn call_from_json(json: &Json, path: &str) -> Result<CallExpression, JsonError>
+{
+    let args_path = child(path, "args");
+    let args = array(field(json, "args", path)?, &args_path)?
+        .iter()
+        .enumerate()
+        .map(|(i, v)| expression_from_json(v, &index(&args_path, i)))
+        .collect::<Result<Vec<Expression>, JsonError>>()?;
+
+    let signature_path = child(path, "signature");
+    let signature = field(json, "signature", path)?;
+    let params_path = child(&signature_path, "params");
+
+    let params = array(field(signature, "params", &signature_path)?, &params_path)?
+        .iter()
+        .enumerate()
+        .map(|(i, v)| type_from_json(v, &index(&params_path, i)))
+        .collect::<Result<Vec<VariableType>, JsonError>>()?;
+
+    return Ok(CallExpression
+    {
+        name: string_field(json, "name", path)?,
+        args,
+        signature: Signature
+        {
+            params,
+            result: type_from_json(field(signature, "result", &signature_path)?, &child(&signature_path, "result"))?
+        }
+    });
+}
+
+/// Reads a numeric expression
+fn numeric_from_json(json: &Json, path: &str) -> Result<NumericExpression, JsonError>
+{
+    let data_type = match field(json, "type", path)?.as_str()
+    {
+        Some("integer") => NumericExpressionDataType::Int,
+        Some("float") => NumericExpressionDataType::Float,
+        _ => return Err(JsonError::schema("numeric type", &child(path, "type")))
+    };
+
+    let operand = |key: &str| numeric_from_json(field(json, key, path)?, &child(path, key)).map(Box::new);
+
+    let value = match string_field(json, "kind", path)?.as_str()
+    {
+        "operation" => match operator_from_json(json, path)?
+        {
+            OperatorRole::Numeric(op) => NumericExpressionType::Operation(OperationNumericExpression
+            {
+                op,
+                a: operand("a")?,
+                b: operand("b")?
+            }),
+            _ => return Err(JsonError::schema("numeric operator", &child(path, "op")))
+        },
+        "constant" =>
+        {
+            let value = field(json, "value", path)?;
+
+            match data_type
+            {
+                NumericExpressionDataType::Int => NumericExpressionType::IntConstant(value
+                    .as_i32()
+                    .ok_or_else(|| JsonError::schema("integer", &child(path, "value")))?),
+                NumericExpressionDataType::Float => NumericExpressionType::FloatConstant(value
+                    .as_f32()
+                    .ok_or_else(|| JsonError::schema("number", &child(path, "value")))?)
+            }
+        },
+        "variable" => NumericExpressionType::Variable(string_field(json, "name", path)?),
+        "call" => NumericExpressionType::Call(call_from_json(json, path)?),
+        _ => return Err(JsonError::schema("numeric expression kind", &child(path, "kind")))
+    };
+
+    return Ok(NumericExpression
+    {
+        inverted: bool_field(json, "inverted", path)?,
+        value,
+        data_type,
+        span: span_from_json(field(json, "span", path)?, &child(path, "span"))?
+    });
+}
+
+/// Reads a boolean expression
+fn boolean_from_json(json: &Json, path: &str) -> Result<BoolExpression, JsonError>
+{
+    if field(json, "type", path)?.as_str() != Some("boolean")
+    {
+        return Err(JsonError::schema("boolean type", &child(path, "type")));
+    }
+
+    let operand = |key: &str| boolean_from_json(field(json, key, path)?, &child(path, key)).map(Box::new);
+    let numeric = |key: &str| numeric_from_json(field(json, key, path)?, &child(path, key));
+    let string = |key: &str| string_from_json(field(json, key, path)?, &child(path, key));
+
+    let value = match string_field(json, "kind", path)?.as_str()
+    {
+        "operation" => match operator_from_json(json, path)?
+        {
+            OperatorRole::Boolean(op) => BoolExpressionType::Operation(OperationBoolExpression
+            {
+                op,
+                a: operand("a")?,
+                b: operand("b")?
+            }),
+            _ => return Err(JsonError::schema("boolean operator", &child(path, "op")))
+        },
+        "comparison" => match operator_from_json(json, path)?
+        {
+            OperatorRole::Comparison(op) => BoolExpressionType::Comparison(ComparisonBoolExpression
+            {
+                op,
+                a: numeric("a")?,
+                b: numeric("b")?
+            }),
+            _ => return Err(JsonError::schema("comparison operator", &child(path, "op")))
+        },
+        "string_comparison" => match operator_from_json(json, path)?
+        {
+            OperatorRole::Comparison(op) if matches!(op, ComparisonOpType::Equal | ComparisonOpType::NotEqual) =>
+                BoolExpressionType::StringComparison(StringComparisonBoolExpression
+                {
+                    op,
+                    a: string("a")?,
+                    b: string("b")?
+                }),
+            _ => return Err(JsonError::schema("'==' or '!='", &child(path, "op")))
+        },
+        "constant" => BoolExpressionType::Constant(bool_field(json, "value", path)?),
+        "variable" => BoolExpressionType::Variable(string_field(json, "name", path)?),
+        "call" => BoolExpressionType::Call(call_from_json(json, path)?),
+        _ => return Err(JsonError::schema("boolean expression kind", &child(path, "kind")))
+    };
+
+    return Ok(BoolExpression
+    {
+        inverted: bool_field(json, "inverted", path)?,
+        value,
+        span: span_from_json(field(json, "span", path)?, &child(path, "span"))?
+    });
+}
+
+/// Reads a string expression
+fn string_from_json(json: &Json, path: &str) -> Result<StringExpression, JsonError>
+{
+    if field(json, "type", path)?.as_str() != Some("string")
+    {
+        return Err(JsonError::schema("string type", &child(path, "type")));
+    }
+
+    let operand = |key: &str| string_from_json(field(json, key, path)?, &child(path, key)).map(Box::new);
+
+    let value = match string_field(json, "kind", path)?.as_str()
+    {
+        "concat" => StringExpressionType::Concat(ConcatStringExpression
+        {
+            a: operand("a")?,
+            b: operand("b")?
+        }),
+        "constant" => StringExpressionType::Constant(string_field(json, "value", path)?),
+        "variable" => StringExpressionType::Variable(string_field(json, "name", path)?),
+        "call" => StringExpressionType::Call(call_from_json(json, path)?),
+        _ => return Err(JsonError::schema("string expression kind", &child(path, "kind")))
+    };
+
+    return Ok(StringExpression
+    {
+        value,
+        span: span_from_json(field(json, "span", path)?, &child(path, "span"))?
+    });
+}
+
+/// Provides the value as an S-expression, indented by two spaces for each level. Lists whose
+/// items hold no further lists are written on a single line
+pub fn to_sexpr(json: &Json) -> String
+{
+    let mut output = String::new();
+    write_sexpr(json, &mut output, 0);
+    return output;
+}
+
+/// Writes the S-expression of the value, where the current line is at the depth
+fn write_sexpr(json: &Json, output: &mut String, depth: usize)
+{
+    // Collect the head of the list, along with each item and the name of its field
+    let (head, items): (Option<&str>, Vec<(Option<&str>, &Json)>) = match json
+    {
+        Json::Null => return output.push_str("nil"),
+        Json::Bool(v) => return output.push_str(&v.to_string()),
+        Json::Number(v) => return output.push_str(v),
+        Json::String(v) => return output.push_str(&Token::quote(v)),
+        Json::Array(items) => (None, items.iter().map(|v| (None, v)).collect()),
+        Json::Object(fields) =>
+        {
+            let kind = json.get("kind").and_then(|k| k.as_str());
+
+            let items = fields
+                .iter()
+                .filter(|(k, _)| kind.is_none() || k != "kind")
+                .map(|(k, v)| (Some(k.as_str()), v))
+                .collect();
+
+            (kind, items)
+        }
+    };
+
+    let nested = items.iter().any(|(_, v)| !is_flat(v));
+
+    output.push('(');
+
+    if let Some(head) = head
+    {
+        output.push_str(head);
+    }
+
+    for (i, (key, value)) in items.iter().enumerate()
+    {
+        if nested
+        {
+            output.push('\n');
+            output.push_str(&"  ".repeat(depth + 1));
+        }
+        else if i > 0 || head.is_some()
+        {
+            output.push(' ');
+        }
+
+        if let Some(key) = key
+        {
+            output.push_str(&format!(":{0:} ", key));
+        }
+
+        write_sexpr(value, output, depth + 1);
+    }
+
+    output.push(')');
+}
+
+/// Returns true if the value is not an array or object, or holds only such values
+fn is_flat(json: &Json) -> bool
+{
+    let scalar = |v: &Json| !matches!(v, Json::Array(_) | Json::Object(_));
+
+    return match json
+    {
+        Json::Array(items) => items.iter().all(scalar),
+        Json::Object(fields) => fields.iter().all(|(_, v)| scalar(v)),
+        _ => true
+    };
+}
+
+/// Defines tests of the JSON and S-expression schemas
+#[cfg(test)]
+mod tests
+{
+    use crate::checker::{self, error::TypeError};
+    use crate::function::Functions;
+    use crate::json::{Json, JsonError, MAX_DEPTH};
+    use crate::lexer::program::Program;
+    use crate::span::Span;
+    use crate::variable::VariableType;
+
+    /// Parses the source into a program that may call the test functions
+    fn parse(source: &str) -> Program
+    {
+        let mut functions = Functions::new();
+        functions.register("max", |a: f32, b: f32| a.max(b));
+        functions.register("upper", |s: String| s.to_uppercase());
+        functions.register("even", |a: i32| a % 2 == 0);
+        return crate::parse_with(source, &functions).unwrap();
+    }
+
+    /// Tests that tokens are written with their kind, value and location
+    #[test]
+    fn tokens_json()
+    {
+        let json = super::tokens_to_json(&crate::tokenize("if (x) { s = \"a\\n\"; f = -1.5; }").unwrap());
+        let tokens = json.get("tokens").unwrap().as_array().unwrap();
+
+        assert_eq!(tokens.len(), 15);
+        assert_eq!(tokens[0].to_string(), "{\"kind\":\"keyword\",\"value\":\"if\",\"span\":{\"start\":0,\"end\":2,\"line\":1,\"column\":1}}");
+        assert_eq!(tokens[7].to_string(), "{\"kind\":\"string\",\"value\":\"a\\n\",\"span\":{\"start\":13,\"end\":18,\"line\":1,\"column\":14}}");
+        assert_eq!(tokens[12].get("value"), Some(&Json::Number(String::from("1.5"))));
+        assert_eq!(json.get("version").unwrap().as_i32(), Some(super::VERSION));
+    }
+
+    /// Tests that programs are written in the documented schema
+    #[test]
+    fn program_json()
+    {
+        let json = super::program_to_json(&parse("a = 1;\nwhile (!(a > 2)) { a += 1; }")).unwrap();
+
+        assert_eq!(
+            json.pretty(),
+            r#"{
+  "kind": "program",
+  "version": 1,
+  "variables": [
+    {"name": "a", "type": "integer"}
+  ],
+  "statements": [
+    {
+      "kind": "assign",
+      "span": {"start": 0, "end": 6, "line": 1, "column": 1},
+      "name": "a",
+      "value": {
+        "kind": "constant",
+        "type": "integer",
+        "span": {"start": 4, "end": 5, "line": 1, "column": 5},
+        "inverted": false,
+        "value": 1
+      }
+    },
+    {
+      "kind": "while",
+      "span": {"start": 7, "end": 35, "line": 2, "column": 1},
+      "condition": {
+        "kind": "comparison",
+        "type": "boolean",
+        "span": {"start": 14, "end": 22, "line": 2, "column": 8},
+        "inverted": true,
+        "op": ">",
+        "a": {
+          "kind": "variable",
+          "type": "integer",
+          "span": {"start": 16, "end": 17, "line": 2, "column": 10},
+          "inverted": false,
+          "name": "a"
+        },
+        "b": {
+          "kind": "constant",
+          "type": "integer",
+          "span": {"start": 20, "end": 21, "line": 2, "column": 14},
+          "inverted": false,
+          "value": 2
+        }
+      },
+      "body": [
+        {
+          "kind": "assign",
+          "span": {"start": 26, "end": 33, "line": 2, "column": 20},
+          "name": "a",
+          "value": {
+            "kind": "operation",
+            "type": "integer",
+            "span": {"start": 26, "end": 32, "line": 2, "column": 20},
+            "inverted": false,
+            "op": "+",
+            "a": {
+              "kind": "variable",
+              "type": "integer",
+              "span": {"start": 26, "end": 27, "line": 2, "column": 20},
+              "inverted": false,
+              "name": "a"
+            },
+            "b": {
+              "kind": "constant",
+              "type": "integer",
+              "span": {"start": 31, "end": 32, "line": 2, "column": 25},
+              "inverted": false,
+              "value": 1
+            }
+          }
+        }
+      ]
+    }
+  ]
+}"#);
+    }
+
+    /// Tests that programs read back from JSON text are unchanged, for every kind of node
+    #[test]
+    fn program_round_trip()
+    {
+        let sources = [
+            "a = 1; b = 2.5; c = a < b || !(b >= 3) && true; s = \"x\\ty\"; s += upper(s) + \"!\";",
+            "n = 0; input n; for (i = 0; i < n; i += 1) { if (s == \"a\" || s != upper(\"b\")) { print i, -n; } else { n = n * (2 - i); } }",
+            "t = max(1, 2.5) / 3; for (; false;) { } while (t > 0.1) { t /= 2; } t - 1; c = false; !c;",
+            "f = 340282350000000000000000000000000000000.0; g = -f; h = 0.000000000000000000000000000000000000000000001;",
+            ""
+        ];
+
+        for source in sources.iter()
+        {
+            let program = parse(&format!("s = \"\"; {0:}", source));
+            let text = super::program_to_json(&program).unwrap().pretty();
+
+            assert_eq!(super::program_from_json(&Json::parse(&text).unwrap()).unwrap(), program, "{0:}", source);
+        }
+    }
+
+    /// Tests that programs nested up to the JSON depth limit are written and read back, and that
+    /// deeper programs are reported when written rather than when read
+    #[test]
+    fn program_round_trip_nested()
+    {
+        // The root object, statement list, statement and operations each enclose the next
+        // operand, and the last operand encloses its span
+        let terms = MAX_DEPTH - 4;
+        let program = parse(&format!("a = 1{0:};", " + 1".repeat(terms - 1)));
+        let text = super::program_to_json(&program).unwrap().to_string();
+
+        // Read the program back on a thread with the stack size of a main thread, as each
+        // operation read takes more of the stack in debug builds than test threads provide
+        let read = std::thread::Builder::new()
+            .stack_size(8 << 20)
+            .spawn(move || super::program_from_json(&Json::parse(&text).unwrap()).unwrap() == program)
+            .unwrap();
+        assert!(read.join().unwrap());
+
+        let program = parse(&format!("a = 1{0:};", " + 1".repeat(terms)));
+        assert_eq!(
+            super::program_to_json(&program),
+            Err(JsonError::schema(
+                "at most 512 nested arrays and objects",
+                &format!("$.statements[0].value{0:}.span", ".a".repeat(terms)))));
+    }
+
+    /// Tests that JSON not matching the schema is reported with the path of the value
+    #[test]
+    fn program_invalid()
+    {
+        let load = |text: &str| super::program_from_json(&Json::parse(text).unwrap());
+        let program = super::program_to_json(&parse("a = 1; if (a > 0) { print a; }")).unwrap().to_string();
+
+        assert!(load(&program).is_ok());
+        assert_eq!(load("[]"), Err(JsonError::schema("object", "$")));
+        assert_eq!(load(&program.replace("\"version\":1", "\"version\":2")), Err(JsonError::schema("version 1", "$.version")));
+        assert_eq!(
+            load(&program.replace("\"op\":\">\"", "\"op\":\"+\"")),
+            Err(JsonError::schema("comparison operator", "$.statements[1].condition.op")));
+        assert_eq!(
+            load(&program.replace("\"op\":\">\"", "\"op\":\"?\"")),
+            Err(JsonError::schema("operator", "$.statements[1].condition.op")));
+        assert_eq!(
+            load(&program.replace("\"op\":\">\",", "")),
+            Err(JsonError::schema("field \"op\"", "$.statements[1].condition")));
+        assert_eq!(
+            load(&program.replace("\"kind\":\"print\"", "\"kind\":\"goto\"")),
+            Err(JsonError::schema("statement kind", "$.statements[1].then[0].kind")));
+        assert_eq!(
+            load(&program.replace("\"value\":1", "\"value\":1.5")),
+            Err(JsonError::schema("integer", "$.statements[0].value.value")));
+        assert_eq!(
+            load(&program.replace("\"name\":\"a\",\"value\"", "\"value\"")),
+            Err(JsonError::schema("field \"name\"", "$.statements[0]")));
+    }
+
+    /// Tests that programs are loaded without being type checked, leaving mismatched values to be
+    /// reported by the checker
+    #[test]
+    fn program_unchecked()
+    {
+        let program = super::program_to_json(&parse("a = 1; b = true; c = a + 1;")).unwrap().to_string();
+        let mut loaded = super::program_from_json(&Json::parse(&program.replace("\"name\":\"a\"}", "\"name\":\"b\"}")).unwrap()).unwrap();
+
+        assert!(matches!(checker::check(&mut loaded), Err(TypeError::MismatchedVariable { name, .. }) if name == "b"));
+    }
+
+    /// Tests that calls in loaded programs with signatures returning a different kind of value
+    /// than their position expects are reported by the checker
+    #[test]
+    fn program_mismatched_result()
+    {
+        let load = |source: &str, from: &str, to: &str|
+        {
+            let program = super::program_to_json(&parse(source)).unwrap().to_string();
+            assert!(program.contains(from), "{0:}", program);
+            return super::program_from_json(&Json::parse(&program.replace(from, to)).unwrap()).unwrap();
+        };
+
+        let mut program = load("a = max(1, 2);", "\"result\":\"float\"", "\"result\":\"string\"");
+        assert_eq!(
+            checker::check(&mut program),
+            Err(TypeError::MismatchedResult { name: String::from("max"), expected: "numeric", found: VariableType::String(String::new()), span: Span::new(4, 13, 1, 5) }));
+
+        let mut program = load("b = even(2) && true;", "\"result\":\"boolean\"", "\"result\":\"float\"");
+        assert!(matches!(checker::check(&mut program), Err(TypeError::MismatchedResult { expected: "boolean", .. })));
+
+        let mut program = load("s = upper(\"a\");", "\"result\":\"string\"", "\"result\":\"boolean\"");
+        assert!(matches!(checker::check(&mut program), Err(TypeError::MismatchedResult { expected: "string", .. })));
+
+        let mut program = load("b = \"a\" == upper(\"a\");", "\"result\":\"string\"", "\"result\":\"integer\"");
+        assert!(matches!(checker::check(&mut program), Err(TypeError::MismatchedResult { expected: "string", .. })));
+    }
+
+    /// Tests that S-expressions hold the kind of each object followed by its fields
+    #[test]
+    fn sexpr()
+    {
+        let json = super::program_to_json(&parse("s = \"a\\\"\";")).unwrap();
+
+        assert_eq!(
+            super::to_sexpr(&json),
+            "(program\n  \
+               :version 1\n  \
+               :variables ((:name \"s\" :type \"string\"))\n  \
+               :statements (\n    \
+                 (assign\n      \
+                   :span (:start 0 :end 10 :line 1 :column 1)\n      \
+                   :name \"s\"\n      \
+                   :value (constant :type \"string\" :span (:start 4 :end 9 :line 1 :column 5) :value \"a\\\"\"))))");
+
+        assert_eq!(super::to_sexpr(&Json::Array(vec!{Json::Null, Json::Array(Vec::new())})), "(nil ())");
+    }
+}
@@ -0,0 +1,63 @@
+/// Defines a location within the source text, being a range of bytes along with the line and
+/// column at which the range starts
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Span
+{
+    /// Defines the byte offset of the first character
+    pub start: usize,
+
+    /// Defines the byte offset just past the last character
+    pub end: usize,
+
+    /// Defines the 1-based line number of the first character
+    pub line: usize,
+
+    /// Defines the 1-based column number of the first character, counted in characters
+    pub column: usize
+}
+
+impl Span
+{
+    /// Returns a new span with the provided values
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Span
+    {
+        return Span
+        {
+            start,
+            end,
+            line,
+            column
+        };
+    }
+
+    /// Returns a span covering from the start of the current span to the end of the other span
+    pub fn merge(&self, other: &Span) -> Span
+    {
+        return Span
+        {
+            end: std::cmp::max(self.end, other.end),
+            ..*self
+        };
+    }
+
+    /// Returns an empty span located at the end of the current span
+    pub fn end_point(&self) -> Span
+    {
+        return Span
+        {
+            start: self.end,
+            end: self.end,
+            line: self.line,
+            column: self.column + (self.end - self.start)
+        };
+    }
+}
+
+impl std::fmt::Display for Span
+{
+    /// Writes the line and column of the span
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return write!(f, "{0:}:{1:}", self.line, self.column);
+    }
+}
@@ -1,4 +1,12 @@
-use regex::Regex;
+use crate::span::Span;
+
+pub mod error;
+pub mod symbol;
+pub mod trivia;
+
+use error::LexError;
+use symbol::{Keyword, Operator};
+use trivia::{Comment, CommentKind, Trivia};
 
 /// Defines a list of tokens that can be streamed in a queue
 pub struct TokenList
@@ -6,22 +14,73 @@ pub struct TokenList
     /// Defines the queue list of tokens
     pub tokens: Vec<Token>,
 
+    /// Defines the source location of each token in the queue
+    pub spans: Vec<Span>,
+
+    /// Defines the comments attached to each token in the queue, if comments were retained when
+    /// tokenizing; otherwise empty
+    pub trivia: Vec<Trivia>,
+
+    /// Defines the comments after the last token that were not attached to it, if comments were
+    /// retained when tokenizing
+    pub end_trivia: Vec<Comment>,
+
     /// Defines the current queue index of the tokens
     ind: usize,
+
+    /// Defines the number of nested values enclosing the current queue location while parsing
+    depth: usize
 }
 
 impl TokenList
 {
-    /// Returns a new TokenList with the tokens provided
+    /// Returns a new TokenList with the tokens provided, with each token given an empty location
     pub fn new(tokens: Vec<Token>) -> TokenList
     {
+        let spans = vec![Span::default(); tokens.len()];
+        return TokenList::with_spans(tokens, spans);
+    }
+
+    /// Returns a new TokenList with the tokens provided and the source location of each token
+    pub fn with_spans(tokens: Vec<Token>, spans: Vec<Span>) -> TokenList
+    {
+        assert_eq!(tokens.len(), spans.len());
+
         return TokenList
         {
-            tokens: tokens,
-            ind: 0
+            tokens,
+            spans,
+            trivia: Vec::new(),
+            end_trivia: Vec::new(),
+            ind: 0,
+            depth: 0
         };
     }
 
+    /// Returns the current queue location, which may be restored with set_position
+    pub fn position(&self) -> usize
+    {
+        return self.ind;
+    }
+
+    /// Moves the queue location to the provided position, such as to backtrack after a failed parse
+    pub fn set_position(&mut self, ind: usize)
+    {
+        self.ind = ind;
+    }
+
+    /// Returns the number of nested values enclosing the current queue location while parsing
+    pub fn depth(&self) -> usize
+    {
+        return self.depth;
+    }
+
+    /// Sets the number of nested values enclosing the current queue location while parsing
+    pub fn set_depth(&mut self, depth: usize)
+    {
+        self.depth = depth;
+    }
+
     /// Resets the queue location to the start
     pub fn reset(&mut self)
     {
@@ -47,6 +106,13 @@ impl TokenList
         };
     }
 
+    /// Returns the Token the provided number of places after the next token if available;
+    /// otherwise returns None
+    pub fn peek_nth(&self, n: usize) -> Option<Token>
+    {
+        return self.tokens.get(self.ind + n).cloned();
+    }
+
     /// Returns a Token if available and moves to the next token in the queue; otherwise None
     pub fn pop(&mut self) -> Option<Token>
     {
@@ -61,17 +127,102 @@ impl TokenList
             None
         };
     }
+
+    /// Returns the next token and its source location if available; otherwise returns None
+    pub fn peek_spanned(&self) -> Option<(Token, Span)>
+    {
+        return self.peek().map(|t| (t, self.spans[self.ind]));
+    }
+
+    /// Returns the next token and its source location if available and moves to the next token
+    /// in the queue; otherwise None
+    pub fn pop_spanned(&mut self) -> Option<(Token, Span)>
+    {
+        let val = self.peek_spanned();
+
+        if val.is_some()
+        {
+            self.ind += 1;
+        }
+
+        return val;
+    }
+
+    /// Returns the source location of the next token. If no tokens remain, an empty location
+    /// at the end of the last token is provided instead
+    pub fn peek_span(&self) -> Span
+    {
+        return if self.available()
+        {
+            self.spans[self.ind]
+        }
+        else
+        {
+            self.spans.last().map(|s| s.end_point()).unwrap_or_default()
+        };
+    }
+
+    /// Returns the source location of the most recently read token
+    pub fn last_span(&self) -> Span
+    {
+        return if self.ind > 0
+        {
+            self.spans[self.ind - 1]
+        }
+        else
+        {
+            Span::default()
+        };
+    }
+
+    /// Returns a span covering from the provided start location to the end of the most recently
+    /// read token
+    pub fn span_from(&self, start: Span) -> Span
+    {
+        return start.merge(&self.last_span());
+    }
+
+    /// Returns true if the next token is the provided operator
+    pub fn next_is_operator(&self, op: Operator) -> bool
+    {
+        return self.peek() == Some(Token::Operator(op));
+    }
+
+    /// Returns true if the next token is the provided keyword
+    pub fn next_is_keyword(&self, keyword: Keyword) -> bool
+    {
+        return self.peek() == Some(Token::Keyword(keyword));
+    }
+
+    /// Pops the next token if it is the provided operator, returning true if the token was consumed
+    pub fn consume_operator(&mut self, op: Operator) -> bool
+    {
+        let found = self.next_is_operator(op);
+
+        if found
+        {
+            self.ind += 1;
+        }
+
+        return found;
+    }
 }
 
+/// Defines the words split from source text, along with their source locations
+type Words<'a> = Vec<(&'a str, Span)>;
+
 /// Defines a token type that stores information associated with a particular token
 #[derive(PartialEq, Clone)]
 pub enum Token
 {
-    Int(i32),
+    /// The magnitude of an integer literal, which is range-checked once any negation before it
+    /// is folded, so that the lowest integer may be written
+    Int(u32),
     Float(f32),
     Bool(bool),
-    Keyword(String),
-    Operator(String),
+    Str(String),
+    Keyword(Keyword),
+    Operator(Operator),
     Variable(String)
 }
 
@@ -87,6 +238,7 @@ impl std::fmt::Debug for Token
             Token::Int(v) => debug_struct.field("Int", &v),
             Token::Float(v) => debug_struct.field("Float", &v),
             Token::Bool(v) => debug_struct.field("Bool", &v),
+            Token::Str(v) => debug_struct.field("Str", &v),
             Token::Keyword(v) => debug_struct.field("Keyword", &v),
             Token::Operator(v) => debug_struct.field("Operator", &v),
             Token::Variable(v) => debug_struct.field("Variable", &v)
@@ -96,341 +248,664 @@ impl std::fmt::Debug for Token
     }
 }
 
-impl ToString for Token
+impl std::fmt::Display for Token
 {
-    /// Converts the token into a string representation
-    fn to_string(&self) -> String
+    /// Writes the token as a string representation
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
     {
         return match self
         {
-            Token::Int(v) => format!("(int {0:})", *v),
-            Token::Float(v) => format!("(float {0:})", *v),
-            Token::Bool(v) => format!("(bool {0:})", if *v { "true" } else { "false" }),
-            Token::Keyword(v) => format!("(keyword {0:})", v),
-            Token::Operator(v) => format!("(operator '{0:}')", v),
-            Token::Variable(v) => format!("(variable {0:})", v),
+            Token::Int(v) => write!(f, "(int {0:})", *v),
+            Token::Float(v) => write!(f, "(float {0:})", *v),
+            Token::Bool(v) => write!(f, "(bool {0:})", if *v { "true" } else { "false" }),
+            Token::Str(v) => write!(f, "(string {0:})", Token::quote(v)),
+            Token::Keyword(v) => write!(f, "(keyword {0:})", v),
+            Token::Operator(v) => write!(f, "(operator '{0:}')", v),
+            Token::Variable(v) => write!(f, "(variable {0:})", v),
         }
     }
 }
 
 impl Token
 {
-    /// Defines the allowed operators list for the ZBasic language
-    const OPERATORS: [&'static str; 16] = [
-        ";",
-        ",",
-        "(",
-        ")",
-        "{",
-        "}",
-        "+",
-        "*",
-        "-",
-        "/",
-        "=",
-        "&&",
-        "||",
-        "==",
-        "!=",
-        "!"
-    ];
-
-    /// Defines the keywords provided by the ZBasic language
-    const KEYWORDS: [&'static str; 3] = [
-        "if",
-        "else",
-        "for"
-    ];
-
-    /// Splits a string into a token list, separating by operators and whitespace, to return
-    /// a vector of strings representing individiual tokens
-    fn split_tokens(input: &str) -> Vec<String>
+    /// Returns the byte length of the longest operator at the start of the input, or zero if no
+    /// operator matches. An error with the matching operators is returned if more than one
+    /// operator of the longest length matches
+    fn match_operator(input: &str) -> Result<usize, String>
     {
-        // Define the resulting string list and the current word tracker
-        let mut string_list: Vec<String> = Vec::new();
-        let mut current: String = String::new();
+        // Only ASCII punctuation may start an operator
+        if !input.as_bytes().first().map(|b| b.is_ascii_punctuation()).unwrap_or(false)
+        {
+            return Ok(0);
+        }
+
+        // Find each operator that the input starts with, keeping the longest
+        let mut best: Vec<&str> = Vec::new();
+
+        for (_, text) in Operator::TABLE.iter()
+        {
+            if !input.starts_with(text)
+            {
+                continue;
+            }
+
+            match best.first()
+            {
+                Some(b) if b.len() > text.len() => (),
+                Some(b) if b.len() == text.len() => best.push(text),
+                _ => best = vec![text]
+            }
+        }
 
-        // Define a function to check for separation
-        // Returns the length of characters to read in as the next operator
-        fn check_separator(input: &str) -> usize
+        return match best.len()
         {
-            // Define the maximum length of the operators
-            let max_len = Token::OPERATORS.iter().map(|v| v.len()).max().unwrap();
+            0 => Ok(0),
+            1 => Ok(best[0].len()),
+            _ => Err(best.join(" "))
+        };
+    }
+
+    /// Splits a string into a token list, separating by operators and whitespace, to return
+    /// a vector of strings representing individiual tokens along with their source locations.
+    /// Comments are skipped
+    fn split_tokens(input: &str) -> Result<Vec<(&str, Span)>, LexError>
+    {
+        return Token::split_words(input).map(|(words, _)| words);
+    }
 
-            // Define a vector for possible values
-            let mut possible = Token::OPERATORS.iter().map(|v| v.len() <= input.len()).collect::<Vec<bool>>();
-            let mut num_possible = possible.iter().filter(|v| **v).count();
+    /// Splits a string into words as with split_tokens, also providing each comment in the
+    /// order found. The input is scanned in a single pass, with each character visited once
+    fn split_words(input: &str) -> Result<(Words<'_>, Vec<Comment>), LexError>
+    {
+        // Define the resulting string list and comments, and the start of the current word, if any
+        let mut string_list: Vec<(&str, Span)> = Vec::new();
+        let mut comments: Vec<Comment> = Vec::new();
+        let mut current: Option<Span> = None;
 
-            // Iterate over values
-            for size in 1..(std::cmp::min(max_len, input.len()) + 1)
+        // Define a function to add the current word to the list, ending at the provided byte offset
+        fn push_word<'a>(input: &'a str, string_list: &mut Vec<(&'a str, Span)>, current: &mut Option<Span>, end: usize)
+        {
+            if let Some(span) = current.take()
             {
-                // Extract the current string
-                let val = &input[..size];
+                string_list.push((&input[span.start..end], Span { end, ..span }));
+            }
+        }
 
-                // Loop through all possibilities
-                for i in 0..possible.len()
+        // Define a function to move past the next character, updating the line and column locations
+        fn advance(chars: &mut std::iter::Peekable<std::str::CharIndices>, line: &mut usize, column: &mut usize)
+        {
+            match chars.next()
+            {
+                Some((_, '\n')) =>
                 {
-                    if !possible[i]
-                    {
-                        continue;
-                    }
+                    *line += 1;
+                    *column = 1;
+                },
+                Some(_) => *column += 1,
+                None => ()
+            }
+        }
 
-                    let op = Token::OPERATORS[i];
+        // Define the current line and column locations, and whether only whitespace has been
+        // read on the current line
+        let mut line = 1usize;
+        let mut column = 1usize;
+        let mut line_start = true;
 
-                    if op.len() == val.len() && op != val
-                    {
-                        possible[i] = false;
-                        num_possible -= 1;
-                    }
-                }
+        // Loop through each character in the input
+        let mut chars = input.char_indices().peekable();
+
+        while let Some(&(byte, c)) = chars.peek()
+        {
+            // Check for a comment, which separates words and is skipped. REM only starts a
+            // comment as the first word of a line
+            let rest = &input[byte..];
+
+            let kind = if rest.starts_with("//")
+            {
+                Some(CommentKind::Line)
+            }
+            else if rest.starts_with("/*")
+            {
+                Some(CommentKind::Block)
+            }
+            else if line_start && rest.starts_with("REM") && rest[3..].chars().next().is_none_or(char::is_whitespace)
+            {
+                Some(CommentKind::Rem)
             }
+            else
+            {
+                None
+            };
 
-            // Check remaining possibilities and see if one is a subset of another
-            if num_possible > 1
+            if let Some(kind) = kind
             {
-                // Loop over each possibility
-                for i in 0..possible.len()
+                push_word(input, &mut string_list, &mut current, byte);
+
+                let start = Span::new(byte, byte + 2, line, column);
+
+                let end = if kind == CommentKind::Block
                 {
-                    // Skip any that are not possible
-                    if !possible[i]
-                    {
-                        continue;
-                    }
+                    // Skip to the matching close, counting nested opening delimiters
+                    let mut depth = 0usize;
+                    let mut end = None;
 
-                    // Loop over each future possibility
-                    for j in (i+1)..possible.len()
+                    while let Some(&(i, _)) = chars.peek()
                     {
-                        // Skip any that are not possible
-                        if !possible[j]
+                        let delimiter = if input[i..].starts_with("/*")
                         {
-                            continue;
+                            depth += 1;
+                            true
                         }
-
-                        // Extract the two strings
-                        let pi = Token::OPERATORS[i];
-                        let pj = Token::OPERATORS[j];
-
-                        // Determine which value is smaller/larger
-                        let larger;
-                        let smaller;
-                        let smaller_ind;
-
-                        if pi.len() > pj.len()
+                        else if input[i..].starts_with("*/")
                         {
-                            larger = pi;
-                            smaller = pj;
-                            smaller_ind = j;
+                            depth -= 1;
+                            true
                         }
                         else
                         {
-                            larger = pj;
-                            smaller = pi;
-                            smaller_ind = i;
+                            false
+                        };
+
+                        // Delimiters are ASCII, so each byte is a single character
+                        let length = if delimiter { 2 } else { 1 };
+
+                        for _ in 0..length
+                        {
+                            advance(&mut chars, &mut line, &mut column);
                         }
 
-                        // Check if one is a subset of the other, and remove if so
-                        if smaller == &larger[..smaller.len()]
+                        if depth == 0
                         {
-                            possible[smaller_ind] = false;
-                            num_possible -= 1;
+                            end = Some(i + length);
+                            break;
                         }
                     }
-                }
-            }
 
-            // Check resulting values
-            if num_possible == 0
-            {
-                return 0;
-            }
-            else if num_possible == 1
-            {
-                for i in 0..possible.len()
-                {
-                    if possible[i]
+                    match end
                     {
-                        return Token::OPERATORS[i].len();
+                        Some(v) => v,
+                        None => return Err(LexError::UnterminatedComment { span: start })
                     }
                 }
-                panic!();
-            }
-            else
-            {
-                panic!();
-            }
-        }
+                else
+                {
+                    // Skip to the end of the line, leaving the line ending to be read as whitespace
+                    let length = rest.find('\n').unwrap_or(rest.len());
+                    let end = byte + rest[..length].trim_end_matches('\r').len();
 
-        // Define flags for operator flags
-        let mut token_found = true;
-        let mut token_len = 0usize;
+                    while chars.peek().is_some_and(|(i, _)| *i < end)
+                    {
+                        advance(&mut chars, &mut line, &mut column);
+                    }
 
-        // Define the character values
-        let input_chars = input.chars().collect::<Vec<char>>();
+                    end
+                };
 
-        // Loop through each character in the input
-        let mut i = 0usize;
-        while i < input_chars.len()
-        {
-            // Extract the current character
-            let c = input_chars[i];
+                comments.push(Comment
+                {
+                    kind,
+                    text: input[byte..end].to_string(),
+                    span: Span { end, ..start }
+                });
 
-            // Check for a token match
-            if token_found && token_len > 0
-            {
-                current.push(c);
-                token_len -= 1;
-                i += 1;
+                line_start = false;
+                continue;
             }
-            else
+
+            // Check for a string literal, which separates words and is added as its own word
+            // including the quotes. Escaped characters are skipped so that an escaped quote does
+            // not end the literal
+            if c == '"'
             {
-                if token_found
-                {
-                    if !current.is_empty()
-                    {
-                        string_list.push(current);
-                        current = String::new();
-                    }
-                    token_found = false;
-                }
+                push_word(input, &mut string_list, &mut current, byte);
 
-                let token_test_len = check_separator(&input[i..]);
+                let start = Span::new(byte, byte + 1, line, column);
+                let mut escaped = false;
+                let mut closed = false;
 
-                if token_test_len > 0
+                chars.next();
+                column += 1;
+
+                for (_, c) in chars.by_ref()
                 {
-                    token_found = true;
-                    token_len = token_test_len;
+                    if c == '\n'
+                    {
+                        line += 1;
+                        column = 1;
+                    }
+                    else
+                    {
+                        column += 1;
+                    }
 
-                    if !current.is_empty()
+                    if escaped
                     {
-                        string_list.push(current);
-                        current = String::new();
+                        escaped = false;
                     }
-                }
-                else if c.is_whitespace()
-                {
-                    if !current.is_empty()
+                    else if c == '\\'
                     {
-                        string_list.push(current);
-                        current = String::new();
+                        escaped = true;
+                    }
+                    else if c == '"'
+                    {
+                        closed = true;
+                        break;
                     }
-                    i += 1;
                 }
-                else
+
+                if !closed
+                {
+                    return Err(LexError::UnterminatedString { span: start });
+                }
+
+                line_start = false;
+
+                let end = chars.peek().map(|(i, _)| *i).unwrap_or(input.len());
+                string_list.push((&input[byte..end], Span { end, ..start }));
+                continue;
+            }
+
+            // Check for an operator, which separates words and is added as its own word
+            let op_len = match Token::match_operator(&input[byte..])
+            {
+                Ok(v) => v,
+                Err(text) => return Err(LexError::AmbiguousOperator
+                {
+                    text,
+                    span: Span::new(byte, byte + c.len_utf8(), line, column)
+                })
+            };
+
+            if op_len > 0
+            {
+                push_word(input, &mut string_list, &mut current, byte);
+                string_list.push((&input[byte..byte + op_len], Span::new(byte, byte + op_len, line, column)));
+
+                // Operators are ASCII, so each byte is a single character
+                for _ in 0..op_len
                 {
-                    current.push(c);
-                    i += 1;
+                    chars.next();
                 }
+                column += op_len;
+
+                line_start = false;
+                continue;
             }
 
+            // Separate words on whitespace, otherwise adding the character to the current word
+            if c.is_whitespace()
+            {
+                push_word(input, &mut string_list, &mut current, byte);
+            }
+            else if current.is_none()
+            {
+                current = Some(Span::new(byte, byte, line, column));
+            }
+
+            // Move to the next character, updating the line and column locations
+            chars.next();
+
+            if c == '\n'
+            {
+                line += 1;
+                column = 1;
+                line_start = true;
+            }
+            else
+            {
+                column += 1;
+                line_start = line_start && c.is_whitespace();
+            }
         }
 
         // Add any last word if not empty
-        if !current.is_empty()
+        push_word(input, &mut string_list, &mut current, input.len());
+
+        // Return the list
+        return Ok((string_list, comments));
+    }
+
+    /// Returns true if the input is a non-empty string of ASCII digits
+    fn is_digits(input: &str) -> bool
+    {
+        return !input.is_empty() && input.bytes().all(|b| b.is_ascii_digit());
+    }
+
+    /// Returns true if the word is an integer literal. Negative values are provided by the
+    /// parser, as the minus sign is always split into a separate operator token
+    fn is_int(word: &str) -> bool
+    {
+        return Token::is_digits(word);
+    }
+
+    /// Returns true if the word is a float literal, being digits with a single decimal point.
+    /// Digits are required on at least one side of the point
+    fn is_float(word: &str) -> bool
+    {
+        return match word.find('.')
         {
-            string_list.push(current);
+            Some(i) =>
+            {
+                let (whole, fraction) = (&word[..i], &word[i + 1..]);
+                (whole.is_empty() || Token::is_digits(whole)) &&
+                    (fraction.is_empty() || Token::is_digits(fraction)) &&
+                    !(whole.is_empty() && fraction.is_empty())
+            },
+            None => false
+        };
+    }
+
+    /// Returns true if the word is a variable name, being a letter followed by letters and digits
+    fn is_identifier(word: &str) -> bool
+    {
+        let mut chars = word.chars();
+
+        return match chars.next()
+        {
+            Some(c) => c.is_ascii_alphabetic() && chars.all(|c| c.is_ascii_alphanumeric()),
+            None => false
+        };
+    }
+
+    /// Returns the text as a quoted string literal, escaping characters such that tokenizing the
+    /// literal provides the original text
+    pub fn quote(text: &str) -> String
+    {
+        let mut literal = String::with_capacity(text.len() + 2);
+        literal.push('"');
+
+        for c in text.chars()
+        {
+            match c
+            {
+                '\n' => literal.push_str("\\n"),
+                '\t' => literal.push_str("\\t"),
+                '"' => literal.push_str("\\\""),
+                '\\' => literal.push_str("\\\\"),
+                c if c.is_control() => literal.push_str(&format!("\\u{{{0:x}}}", c as u32)),
+                c => literal.push(c)
+            }
         }
 
-        // Return the list
-        return string_list;
+        literal.push('"');
+        return literal;
     }
 
-    /// Tokenizes a string to extract the string into a list of tokens if possible. If an invalid
-    /// token is identified, an error string will be returned
-    pub fn tokenize(input: &str) -> Result<TokenList, String>
+    /// Reads the escape sequence at the start of the input, which starts with a backslash, to
+    /// return the byte length of the sequence and the character it represents, or None if the
+    /// sequence is not valid
+    fn escape_value(input: &str) -> (usize, Option<char>)
+    {
+        return match input[1..].chars().next()
+        {
+            Some('n') => (2, Some('\n')),
+            Some('t') => (2, Some('\t')),
+            Some('"') => (2, Some('"')),
+            Some('\\') => (2, Some('\\')),
+            Some('u') =>
+            {
+                // Read the braced hexadecimal code point, allowing up to six digits
+                let digits = input[2..].strip_prefix('{').map(|v| v.bytes().take_while(|b| b.is_ascii_hexdigit()).count());
+
+                match digits
+                {
+                    Some(n) if input[3 + n..].starts_with('}') =>
+                    {
+                        let value = if (1..=6).contains(&n)
+                        {
+                            u32::from_str_radix(&input[3..3 + n], 16).ok().and_then(char::from_u32)
+                        }
+                        else
+                        {
+                            None
+                        };
+
+                        (n + 4, value)
+                    },
+                    Some(n) => (n + 3, None),
+                    None => (2, None)
+                }
+            },
+            Some(c) => (1 + c.len_utf8(), None),
+            None => (1, None)
+        };
+    }
+
+    /// Converts a quoted string literal into the text it represents, replacing each escape
+    /// sequence with its character. An error is provided for the first invalid escape sequence
+    fn unescape(word: &str, span: Span) -> Result<String, LexError>
     {
-        // Split the input string into words
-        let words = Token::split_tokens(input);
+        let body = &word[1..word.len() - 1];
+        let mut text = String::with_capacity(body.len());
 
-        // Define the token list
-        let mut tokens: Vec<Token> = Vec::new();
+        // Define the location of the current character, starting after the opening quote
+        let mut line = span.line;
+        let mut column = span.column + 1;
+        let mut i = 0;
 
-        fn check_for_token(word: &str) -> Result<Token, String>
+        while let Some(c) = body[i..].chars().next()
         {
-            // Check for keyword
-            for k in Token::KEYWORDS.iter()
+            let length = if c == '\\'
             {
-                if word == *k
+                let (length, value) = Token::escape_value(&body[i..]);
+
+                match value
                 {
-                    return Ok(Token::Keyword(word.to_string()));
+                    Some(v) => text.push(v),
+                    None => return Err(LexError::InvalidEscape
+                    {
+                        text: body[i..i + length].to_string(),
+                        span: Span::new(span.start + 1 + i, span.start + 1 + i + length, line, column)
+                    })
                 }
+
+                length
             }
+            else
+            {
+                text.push(c);
+                c.len_utf8()
+            };
 
-            // Check for boolean
-            if word == "true" || word == "false"
+            // Move past the character or escape sequence, updating the line and column locations
+            if c == '\n'
+            {
+                line += 1;
+                column = 1;
+            }
+            else
             {
-                return Ok(Token::Bool(word == "true"));
+                column += body[i..i + length].chars().count();
             }
 
-            // Define numeric regex expressions
-            let float_re = Regex::new(r"^-?(([0-9]+\.[0-9]*)|([0-9]*\.[0-9]+))$").unwrap();
-            let int_re = Regex::new(r"^-?([0-9]+)$").unwrap();
+            i += length;
+        }
+
+        return Ok(text);
+    }
+
+    /// Converts a single word into a token, providing an error describing the word if it is not
+    /// a valid token
+    fn check_for_token(word: &str, span: Span) -> Result<Token, LexError>
+    {
+        // Check for string literal
+        if word.starts_with('"')
+        {
+            return Token::unescape(word, span).map(Token::Str);
+        }
+
+        // Check for keyword
+        if let Some(k) = Keyword::lookup(word)
+        {
+            return Ok(Token::Keyword(k));
+        }
+
+        // Check for boolean
+        if word == "true" || word == "false"
+        {
+            return Ok(Token::Bool(word == "true"));
+        }
 
-            // Check for a match
-            if float_re.is_match(word)
+        // Check for numeric values
+        if Token::is_float(word)
+        {
+            match word.parse::<f32>()
             {
-                match word.parse::<f32>()
+                Ok(v) if v.is_finite() => return Ok(Token::Float(v)),
+                Ok(_) => return Err(LexError::FloatOverflow
                 {
-                    Ok(v) => return Ok(Token::Float(v)),
-                    Err(_) => return Err(format!("unable to parse token {0:} as float", word))
-                }
+                    text: word.to_string(),
+                    span
+                }),
+                Err(_) => return Err(LexError::InvalidNumber
+                {
+                    text: word.to_string(),
+                    span
+                })
             }
+        }
 
-            if int_re.is_match(word)
+        if Token::is_int(word)
+        {
+            match word.parse::<u32>()
             {
-                match word.parse::<i32>()
+                Ok(v) if v <= i32::MIN.unsigned_abs() => return Ok(Token::Int(v)),
+                _ => return Err(LexError::IntegerOverflow
                 {
-                    Ok(v) => return Ok(Token::Int(v)),
-                    Err(_) => return Err(format!("unable to parse token {0:} as int", word))
-                }
+                    text: word.to_string(),
+                    span
+                })
             }
+        }
+
+        // Check for operator
+        if let Some(op) = Operator::lookup(word)
+        {
+            return Ok(Token::Operator(op));
+        }
+
+        // Check for variable
+        if Token::is_identifier(word)
+        {
+            return Ok(Token::Variable(word.to_string()));
+        }
 
-            // Check for operator
-            for op in Token::OPERATORS.iter()
+        // Determine the error type, starting with any unknown characters
+        for (column, (i, c)) in (span.column..).zip(word.char_indices())
+        {
+            if !c.is_ascii_alphanumeric() && c != '_' && c != '.'
             {
-                if word == *op
+                return Err(LexError::UnknownCharacter
                 {
-                    return Ok(Token::Operator(word.to_string()));
-                }
+                    character: c,
+                    span: Span::new(span.start + i, span.start + i + c.len_utf8(), span.line, column)
+                });
             }
+        }
+
+        return if word.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        {
+            Err(LexError::InvalidNumber
+            {
+                text: word.to_string(),
+                span
+            })
+        }
+        else
+        {
+            Err(LexError::InvalidIdentifier
+            {
+                text: word.to_string(),
+                span
+            })
+        };
+    }
+
+    /// Tokenizes a string to extract the string into a list of tokens if possible. If an invalid
+    /// token is identified, a lexer error describing the token will be returned. Comments are
+    /// skipped
+    pub fn tokenize(input: &str) -> Result<TokenList, LexError>
+    {
+        return Token::from_words(&Token::split_tokens(input)?);
+    }
+
+    /// Tokenizes a string as with tokenize, also keeping each comment as trivia of an adjacent
+    /// token. A comment starting on the same line as the end of the token before it is trailing
+    /// trivia of that token, and any other comment is leading trivia of the token after it.
+    /// Comments after the last token on later lines are kept as the end trivia of the list
+    pub fn tokenize_with_trivia(input: &str) -> Result<TokenList, LexError>
+    {
+        let (words, comments) = Token::split_words(input)?;
+        let mut token_list = Token::from_words(&words)?;
 
-            // Check for variable
-            let var_re = Regex::new(r"^([a-zA-Z])([a-zA-Z0-9])*$").unwrap();
+        let mut trivia = vec![Trivia::default(); token_list.tokens.len()];
+        let mut end_trivia: Vec<Comment> = Vec::new();
 
-            if var_re.is_match(word)
+        // Define the index of the first token starting after the current comment
+        let mut next = 0usize;
+
+        for comment in comments
+        {
+            while next < token_list.spans.len() && token_list.spans[next].start < comment.span.start
             {
-                return Ok(Token::Variable(word.to_string()));
+                next += 1;
             }
 
-            return Err(format!("unable to parse {0:} as a token", word));
+            if next > 0 && !input[token_list.spans[next - 1].end..comment.span.start].contains('\n')
+            {
+                trivia[next - 1].trailing.push(comment);
+            }
+            else if next < trivia.len()
+            {
+                trivia[next].leading.push(comment);
+            }
+            else
+            {
+                end_trivia.push(comment);
+            }
         }
 
+        token_list.trivia = trivia;
+        token_list.end_trivia = end_trivia;
+        return Ok(token_list);
+    }
+
+    /// Converts each word into a token, keeping the word locations
+    fn from_words(words: &[(&str, Span)]) -> Result<TokenList, LexError>
+    {
+        // Define the token list and token locations
+        let mut tokens: Vec<Token> = Vec::with_capacity(words.len());
+        let mut spans: Vec<Span> = Vec::with_capacity(words.len());
+
         // Iterate over each word type
-        for w in words.iter()
+        for (w, span) in words.iter()
         {
-            // Check for a valid result
-            match check_for_token(w)
-            {
-                Ok(v) => tokens.push(v),
-                Err(s) => return Err(s)
-            }
+            tokens.push(Token::check_for_token(w, *span)?);
+            spans.push(*span);
         }
 
-        return Ok(TokenList::new(tokens));
+        return Ok(TokenList::with_spans(tokens, spans));
     }
 }
 
 /// Defines tests of the Token types
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::unused_unit)]
 mod tests
 {
     use super::{Token, TokenList};
+    use super::error::LexError;
+    use super::symbol::{Keyword, Operator};
+    use super::trivia::{Comment, CommentKind};
+    use crate::span::Span;
 
     /// Tests the split works/tokens class
     #[test]
     fn split_tokens() -> ()
     {
         // Define the words and expected words
-        let words = Token::split_tokens("this   is(a test&&=||)====998 -13 -2.0");
+        let words = Token::split_tokens("this   is(a test&&=||)====998 -13 -2.0").unwrap();
         let expected_words = vec!{
             "this",
             "is",
@@ -456,10 +931,41 @@ mod tests
         // Ensure that values match
         for i in 0..words.len()
         {
-            assert_eq!(words[i], expected_words[i]);
+            assert_eq!(words[i].0, expected_words[i]);
         }
     }
 
+    /// Tests that token locations are tracked across lines
+    #[test]
+    fn tokenize_spans()
+    {
+        let token_list = Token::tokenize("a = 12;\n  if (b<=c)\n{").unwrap();
+        let expected_spans = vec!{
+            Span::new(0, 1, 1, 1),
+            Span::new(2, 3, 1, 3),
+            Span::new(4, 6, 1, 5),
+            Span::new(6, 7, 1, 7),
+            Span::new(10, 12, 2, 3),
+            Span::new(13, 14, 2, 6),
+            Span::new(14, 15, 2, 7),
+            Span::new(15, 17, 2, 8),
+            Span::new(17, 18, 2, 10),
+            Span::new(18, 19, 2, 11),
+            Span::new(20, 21, 3, 1)
+        };
+
+        assert_eq!(token_list.spans, expected_spans);
+
+        // Check that the spans are provided alongside tokens from the queue
+        let mut token_list = token_list;
+        assert_eq!(token_list.pop_spanned(), Some((Token::Variable(String::from("a")), Span::new(0, 1, 1, 1))));
+        assert_eq!(token_list.peek_spanned(), Some((Token::Operator(Operator::Assign), Span::new(2, 3, 1, 3))));
+        assert_eq!(token_list.last_span(), Span::new(0, 1, 1, 1));
+
+        // Check that the error provides the location of the invalid token
+        assert_eq!(Token::tokenize("a = 1;\nb = 234a34;").err().unwrap().span(), Span::new(11, 17, 2, 5));
+    }
+
     /// Tests the tokenize function against valid, expected input
     #[test]
     fn tokenize_valid()
@@ -471,32 +977,32 @@ mod tests
         let tokens_expected: Vec<Token> = vec!{
             Token::Variable(String::from("this")),
             Token::Variable(String::from("is")),
-            Token::Operator(String::from("(")),
+            Token::Operator(Operator::LeftParen),
             Token::Variable(String::from("a")),
-            Token::Operator(String::from("+")),
+            Token::Operator(Operator::Plus),
             Token::Variable(String::from("test")),
-            Token::Operator(String::from("&&")),
-            Token::Operator(String::from("=")),
-            Token::Operator(String::from("||")),
-            Token::Operator(String::from(")")),
-            Token::Operator(String::from("==")),
-            Token::Operator(String::from("==")),
+            Token::Operator(Operator::And),
+            Token::Operator(Operator::Assign),
+            Token::Operator(Operator::Or),
+            Token::Operator(Operator::RightParen),
+            Token::Operator(Operator::Equal),
+            Token::Operator(Operator::Equal),
             Token::Int(998),
-            Token::Operator(String::from("-")),
+            Token::Operator(Operator::Minus),
             Token::Int(13),
             Token::Variable(String::from("a93")),
             Token::Variable(String::from("Z384")),
-            Token::Operator(String::from("=")),
+            Token::Operator(Operator::Assign),
             Token::Float(3.458),
-            Token::Keyword(String::from("for")),
-            Token::Keyword(String::from("if")),
+            Token::Keyword(Keyword::For),
+            Token::Keyword(Keyword::If),
             Token::Variable(String::from("FOR")),
             Token::Variable(String::from("If")),
-            Token::Keyword(String::from("else")),
-            Token::Operator(String::from("!")),
+            Token::Keyword(Keyword::Else),
+            Token::Operator(Operator::Not),
             Token::Variable(String::from("test")),
-            Token::Operator(String::from("!=")),
-            Token::Operator(String::from("!")),
+            Token::Operator(Operator::NotEqual),
+            Token::Operator(Operator::Not),
             Token::Variable(String::from("asdf"))
         };
 
@@ -536,6 +1042,222 @@ mod tests
         assert_eq!(token_list.pop().unwrap(), tokens_expected[1]);
     }
 
+    /// Tests that every keyword in the keyword table is tokenized as a keyword
+    #[test]
+    fn tokenize_keywords()
+    {
+        for (k, text) in Keyword::TABLE.iter()
+        {
+            let tokens = Token::tokenize(text).unwrap().tokens;
+            assert_eq!(tokens, vec!{Token::Keyword(*k)});
+        }
+
+        assert_eq!(Token::tokenize("while").unwrap().tokens, vec!{Token::Keyword(Keyword::While)});
+        assert_eq!(Token::tokenize("While").unwrap().tokens, vec!{Token::Variable(String::from("While"))});
+    }
+
+    /// Tests that comparison operators are split with the longest matching operator
+    #[test]
+    fn tokenize_comparison()
+    {
+        let tokens = Token::tokenize("a<=3 b>=c d<e f>-2 g==h i!=j").unwrap().tokens;
+        let operators = tokens
+            .iter()
+            .filter_map(|t| match t
+            {
+                Token::Operator(op) => Some(op.as_str()),
+                _ => None
+            })
+            .collect::<Vec<&str>>();
+
+        assert_eq!(operators, vec!{"<=", ">=", "<", ">", "-", "==", "!="});
+    }
+
+    /// Tests that compound assignment operators are split from adjacent values
+    #[test]
+    fn tokenize_compound_assignment()
+    {
+        let tokens = Token::tokenize("a+=1 b-=-2 c*=d e /= 2.5").unwrap().tokens;
+        let operators = tokens
+            .iter()
+            .filter_map(|t| match t
+            {
+                Token::Operator(op) => Some(*op),
+                _ => None
+            })
+            .collect::<Vec<Operator>>();
+
+        assert_eq!(operators, vec!{Operator::PlusAssign, Operator::MinusAssign, Operator::Minus, Operator::StarAssign, Operator::SlashAssign});
+    }
+
+    /// Tests that string literals are read as single tokens with escape sequences replaced
+    #[test]
+    fn tokenize_strings()
+    {
+        let token_list = Token::tokenize("s = \"a (b) + \\\"c\\\"\"+\"\\n\\t\\\\\\u{e9}\\u{1F600}\";\nt = \"\";").unwrap();
+
+        assert_eq!(
+            token_list.tokens,
+            vec!{
+                Token::Variable(String::from("s")),
+                Token::Operator(Operator::Assign),
+                Token::Str(String::from("a (b) + \"c\"")),
+                Token::Operator(Operator::Plus),
+                Token::Str(String::from("\n\t\\\u{e9}\u{1F600}")),
+                Token::Operator(Operator::Semicolon),
+                Token::Variable(String::from("t")),
+                Token::Operator(Operator::Assign),
+                Token::Str(String::new()),
+                Token::Operator(Operator::Semicolon)
+            });
+        assert_eq!(token_list.spans[2], Span::new(4, 19, 1, 5));
+        assert_eq!(token_list.spans[8], Span::new(49, 51, 2, 5));
+
+        // Check that quoting the text provides a literal that tokenizes to the same text
+        for text in ["plain", "tab\tand\nnewline", "\"quoted\" \\ \u{7}", "\u{e9}"].iter()
+        {
+            assert_eq!(Token::tokenize(&Token::quote(text)).unwrap().tokens, vec!{Token::Str(text.to_string())});
+        }
+
+        assert_eq!(Token::quote("a\"b\n\u{0}"), "\"a\\\"b\\n\\u{0}\"");
+        assert_eq!(Token::Str(String::from("x\ty")).to_string(), "(string \"x\\ty\")");
+    }
+
+    /// Tests that invalid string literals provide the location of the error
+    #[test]
+    fn tokenize_invalid_strings()
+    {
+        assert_eq!(
+            Token::tokenize("a = \"abc;\nb = 1;").err(),
+            Some(LexError::UnterminatedString { span: Span::new(4, 5, 1, 5) }));
+        assert_eq!(
+            Token::tokenize("a = \"ends with \\\"").err(),
+            Some(LexError::UnterminatedString { span: Span::new(4, 5, 1, 5) }));
+        assert_eq!(
+            Token::tokenize("a = \"x\\qy\";").err(),
+            Some(LexError::InvalidEscape { text: String::from("\\q"), span: Span::new(6, 8, 1, 7) }));
+        assert_eq!(
+            Token::tokenize("\"\\u{110000}\"").err(),
+            Some(LexError::InvalidEscape { text: String::from("\\u{110000}"), span: Span::new(1, 11, 1, 2) }));
+        assert_eq!(
+            Token::tokenize("\"\\u{}\" \"\\u{12\"").err().map(|e| e.span()),
+            Some(Span::new(1, 5, 1, 2)));
+        assert!(matches!(Token::tokenize("\"\\u12\""), Err(LexError::InvalidEscape { text, .. }) if text == "\\u"));
+        assert!(matches!(Token::tokenize("\"\\u{d800}\""), Err(LexError::InvalidEscape { .. })));
+    }
+
+    /// Tests that line, nested block and REM comments are skipped, separating words
+    #[test]
+    fn tokenize_comments()
+    {
+        let input = "a = 1; // one\r\nb/* x /* y\n */ z */= 2;\nREM note / * \"\n  REM\nc = REM; REMARK = a//b\n;";
+        let token_list = Token::tokenize(input).unwrap();
+
+        assert_eq!(
+            token_list.tokens,
+            vec!{
+                Token::Variable(String::from("a")),
+                Token::Operator(Operator::Assign),
+                Token::Int(1),
+                Token::Operator(Operator::Semicolon),
+                Token::Variable(String::from("b")),
+                Token::Operator(Operator::Assign),
+                Token::Int(2),
+                Token::Operator(Operator::Semicolon),
+                Token::Variable(String::from("c")),
+                Token::Operator(Operator::Assign),
+                Token::Variable(String::from("REM")),
+                Token::Operator(Operator::Semicolon),
+                Token::Variable(String::from("REMARK")),
+                Token::Operator(Operator::Assign),
+                Token::Variable(String::from("a")),
+                Token::Operator(Operator::Semicolon)
+            });
+
+        // Check that locations are tracked through comments spanning lines
+        assert_eq!(token_list.spans[5], Span::new(34, 35, 3, 9));
+        assert_eq!(token_list.spans[8], Span::new(60, 61, 6, 1));
+
+        // Check that comment delimiters within string literals are kept
+        assert_eq!(Token::tokenize("\"// /* REM\"").unwrap().tokens, vec!{Token::Str(String::from("// /* REM"))});
+
+        assert_eq!(
+            Token::tokenize("a = 1;\n/* outer /* inner */").err(),
+            Some(LexError::UnterminatedComment { span: Span::new(7, 9, 2, 1) }));
+    }
+
+    /// Tests that comments are kept as trivia of the adjacent tokens when requested
+    #[test]
+    fn tokenize_trivia()
+    {
+        let input = "// header\n/* about a */ a = 1; // set a\nREM b next\nb = a /* inline */ + 2; /* end */\n// footer\n";
+        let token_list = Token::tokenize_with_trivia(input).unwrap();
+        fn texts(comments: &[Comment]) -> Vec<&str>
+        {
+            return comments.iter().map(|c| c.text.as_str()).collect();
+        }
+
+        assert_eq!(token_list.trivia.len(), token_list.tokens.len());
+        assert_eq!(texts(&token_list.trivia[0].leading), vec!{"// header", "/* about a */"});
+        assert_eq!(texts(&token_list.trivia[3].trailing), vec!{"// set a"});
+        assert_eq!(texts(&token_list.trivia[4].leading), vec!{"REM b next"});
+        assert_eq!(texts(&token_list.trivia[6].trailing), vec!{"/* inline */"});
+        assert_eq!(texts(&token_list.trivia[9].trailing), vec!{"/* end */"});
+        assert_eq!(texts(&token_list.end_trivia), vec!{"// footer"});
+        assert_eq!(token_list.trivia.iter().filter(|t| !t.is_empty()).count(), 5);
+
+        assert_eq!(
+            token_list.trivia[0].leading[1],
+            Comment
+            {
+                kind: CommentKind::Block,
+                text: String::from("/* about a */"),
+                span: Span::new(10, 23, 2, 1)
+            });
+        assert_eq!(token_list.trivia[4].leading[0].kind, CommentKind::Rem);
+        assert_eq!(token_list.trivia[3].trailing[0].kind, CommentKind::Line);
+
+        // Check that comments are dropped by default, and kept at the end without any tokens
+        assert!(Token::tokenize(input).unwrap().trivia.is_empty());
+        assert_eq!(texts(&Token::tokenize_with_trivia("/* only */").unwrap().end_trivia), vec!{"/* only */"});
+        assert_eq!(Token::tokenize_with_trivia(input).unwrap().tokens, Token::tokenize(input).unwrap().tokens);
+    }
+
+    /// Tests that tokenization time scales linearly with the input size, printing the time taken
+    /// for each input size. This test is timing-dependent and is ignored by default; run it with
+    /// cargo test --release -- --ignored --nocapture tokenize_scaling
+    #[test]
+    #[ignore]
+    fn tokenize_scaling()
+    {
+        let line = "total = total + (i * 3.5) / 2; if (total >= 100 && !done) { count = count - 1; }\n";
+        let mut ns_per_byte: Vec<f64> = Vec::new();
+
+        for lines in [1000usize, 2000, 4000, 8000, 16000].iter()
+        {
+            let input = line.repeat(*lines);
+
+            // Take the best of several runs to reduce noise
+            let best = (0..5)
+                .map(|_|
+                {
+                    let start = std::time::Instant::now();
+                    let tokens = Token::tokenize(&input).unwrap();
+                    assert_eq!(tokens.tokens.len(), 29 * lines);
+                    start.elapsed()
+                })
+                .min()
+                .unwrap();
+
+            let per_byte = best.as_nanos() as f64 / input.len() as f64;
+            println!("{0:>6} lines, {1:>8} bytes: {2:>10.3?} ({3:.2} ns/byte)", lines, input.len(), best, per_byte);
+            ns_per_byte.push(per_byte);
+        }
+
+        // A 16x larger input should take roughly 16x as long, so the per-byte time should stay flat
+        assert!(ns_per_byte[ns_per_byte.len() - 1] < 3.0 * ns_per_byte[0]);
+    }
+
     /// Tests the tokenize input against invalid input
     #[test]
     fn tokenize_invalid()
@@ -543,5 +1265,41 @@ mod tests
         assert_eq!(Token::tokenize("123.345.3").is_err(), true);
         assert_eq!(Token::tokenize("234a34").is_err(), true);
         assert_eq!(Token::tokenize("[asdf]").is_err(), true);
+
+        // Check that each error kind is provided with the offending text
+        assert_eq!(
+            Token::tokenize("123.345.3").err(),
+            Some(LexError::InvalidNumber { text: String::from("123.345.3"), span: Span::new(0, 9, 1, 1) }));
+        assert_eq!(
+            Token::tokenize("a = 234a34;").err(),
+            Some(LexError::InvalidNumber { text: String::from("234a34"), span: Span::new(4, 10, 1, 5) }));
+        assert_eq!(
+            Token::tokenize("x [asdf]").err(),
+            Some(LexError::UnknownCharacter { character: '[', span: Span::new(2, 3, 1, 3) }));
+        assert_eq!(
+            Token::tokenize("ab$c").err(),
+            Some(LexError::UnknownCharacter { character: '$', span: Span::new(2, 3, 1, 3) }));
+        assert_eq!(
+            Token::tokenize("my_var").err(),
+            Some(LexError::InvalidIdentifier { text: String::from("my_var"), span: Span::new(0, 6, 1, 1) }));
+        assert_eq!(
+            Token::tokenize("99999999999").err(),
+            Some(LexError::IntegerOverflow { text: String::from("99999999999"), span: Span::new(0, 11, 1, 1) }));
+
+        // Check that non-ASCII input provides an error rather than a panic
+        assert!(matches!(Token::tokenize("a = \u{e9};").err(), Some(LexError::UnknownCharacter { character: '\u{e9}', .. })));
+    }
+
+    /// Tests that float literals too large to be represented as a finite float are rejected
+    #[test]
+    fn tokenize_float_range()
+    {
+        let max = format!("{0:}.0", f32::MAX);
+        assert_eq!(Token::tokenize(&max).unwrap().pop(), Some(Token::Float(f32::MAX)));
+
+        let over = format!("{0:}0.0", f32::MAX);
+        assert_eq!(
+            Token::tokenize(&over).err(),
+            Some(LexError::FloatOverflow { text: over.clone(), span: Span::new(0, 42, 1, 1) }));
     }
 }
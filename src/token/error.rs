@@ -0,0 +1,109 @@
+use crate::span::Span;
+
+/// Defines the errors that may be raised while splitting source text into tokens
+#[derive(Debug, PartialEq, Clone)]
+pub enum LexError
+{
+    /// Indicates that a word starting with a digit could not be read as a number
+    InvalidNumber
+    {
+        text: String,
+        span: Span
+    },
+
+    /// Indicates that a word starting with a letter is not a valid variable name
+    InvalidIdentifier
+    {
+        text: String,
+        span: Span
+    },
+
+    /// Indicates that a character is not part of any valid token
+    UnknownCharacter
+    {
+        character: char,
+        span: Span
+    },
+
+    /// Indicates that an integer literal is outside of the allowed integer range
+    IntegerOverflow
+    {
+        text: String,
+        span: Span
+    },
+
+    /// Indicates that a float literal is too large to be represented as a finite float
+    FloatOverflow
+    {
+        text: String,
+        span: Span
+    },
+
+    /// Indicates that more than one operator could be matched at the same location
+    AmbiguousOperator
+    {
+        text: String,
+        span: Span
+    },
+
+    /// Indicates that a string literal has no closing quote before the end of the input
+    UnterminatedString
+    {
+        span: Span
+    },
+
+    /// Indicates that a backslash within a string literal does not start a valid escape sequence
+    InvalidEscape
+    {
+        text: String,
+        span: Span
+    },
+
+    /// Indicates that a block comment has no matching close before the end of the input
+    UnterminatedComment
+    {
+        span: Span
+    }
+}
+
+impl LexError
+{
+    /// Returns the source location associated with the error
+    pub fn span(&self) -> Span
+    {
+        return match self
+        {
+            LexError::InvalidNumber { span, .. } => *span,
+            LexError::InvalidIdentifier { span, .. } => *span,
+            LexError::UnknownCharacter { span, .. } => *span,
+            LexError::IntegerOverflow { span, .. } => *span,
+            LexError::FloatOverflow { span, .. } => *span,
+            LexError::AmbiguousOperator { span, .. } => *span,
+            LexError::UnterminatedString { span } => *span,
+            LexError::InvalidEscape { span, .. } => *span,
+            LexError::UnterminatedComment { span } => *span
+        };
+    }
+}
+
+impl std::fmt::Display for LexError
+{
+    /// Writes a human-readable description of the lexer error
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return match self
+        {
+            LexError::InvalidNumber { text, span } => write!(f, "invalid number {0:} at {1:}", text, span),
+            LexError::InvalidIdentifier { text, span } => write!(f, "invalid identifier {0:} at {1:}", text, span),
+            LexError::UnknownCharacter { character, span } => write!(f, "unknown character '{0:}' at {1:}", character, span),
+            LexError::IntegerOverflow { text, span } => write!(f, "integer {0:} is out of range at {1:}", text, span),
+            LexError::FloatOverflow { text, span } => write!(f, "float {0:} is out of range at {1:}", text, span),
+            LexError::AmbiguousOperator { text, span } => write!(f, "ambiguous operator {0:} at {1:}", text, span),
+            LexError::UnterminatedString { span } => write!(f, "unterminated string at {0:}", span),
+            LexError::InvalidEscape { text, span } => write!(f, "invalid escape sequence {0:} at {1:}", text, span),
+            LexError::UnterminatedComment { span } => write!(f, "unterminated block comment at {0:}", span)
+        };
+    }
+}
+
+impl std::error::Error for LexError {}
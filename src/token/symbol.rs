@@ -0,0 +1,140 @@
+/// Defines an enum of fixed source symbols, such as operators or keywords, along with the table
+/// associating each variant with its source text. The table drives the lexer, and the FromStr
+/// and Display implementations convert between the variants and their text
+macro_rules! symbol_enum
+{
+    (
+        $(#[$meta:meta])*
+        $name:ident, $kind:expr,
+        {
+            $($(#[$variant_meta:meta])* $variant:ident => $text:expr),* $(,)?
+        }
+    ) =>
+    {
+        $(#[$meta])*
+        #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+        pub enum $name
+        {
+            $($(#[$variant_meta])* $variant),*
+        }
+
+        impl $name
+        {
+            /// Defines each value along with its source text
+            pub const TABLE: &'static [($name, &'static str)] = &[
+                $(($name::$variant, $text)),*
+            ];
+
+            /// Returns the value with the provided source text, if any
+            pub fn lookup(text: &str) -> Option<$name>
+            {
+                return $name::TABLE
+                    .iter()
+                    .find(|(_, t)| *t == text)
+                    .map(|(v, _)| *v);
+            }
+
+            /// Returns the source text of the value
+            pub fn as_str(&self) -> &'static str
+            {
+                return match self
+                {
+                    $($name::$variant => $text),*
+                };
+            }
+        }
+
+        impl std::str::FromStr for $name
+        {
+            type Err = String;
+
+            /// Returns the value with the provided source text, if any
+            fn from_str(s: &str) -> Result<$name, String>
+            {
+                return $name::lookup(s).ok_or_else(|| format!("{0:} is not a known {1:}", s, $kind));
+            }
+        }
+
+        impl std::fmt::Display for $name
+        {
+            /// Writes the source text of the value
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+            {
+                return write!(f, "{0:}", self.as_str());
+            }
+        }
+    };
+}
+
+symbol_enum!
+{
+    /// Defines the allowed operators for the ZBasic language
+    Operator, "operator",
+    {
+        Semicolon => ";",
+        Comma => ",",
+        LeftParen => "(",
+        RightParen => ")",
+        LeftBrace => "{",
+        RightBrace => "}",
+        Plus => "+",
+        Star => "*",
+        Minus => "-",
+        Slash => "/",
+        Assign => "=",
+        PlusAssign => "+=",
+        MinusAssign => "-=",
+        StarAssign => "*=",
+        SlashAssign => "/=",
+        And => "&&",
+        Or => "||",
+        Equal => "==",
+        NotEqual => "!=",
+        Not => "!",
+        Less => "<",
+        Greater => ">",
+        LessEqual => "<=",
+        GreaterEqual => ">=",
+    }
+}
+
+symbol_enum!
+{
+    /// Defines the keywords provided by the ZBasic language
+    Keyword, "keyword",
+    {
+        If => "if",
+        Else => "else",
+        For => "for",
+        While => "while",
+        Print => "print",
+        Input => "input",
+    }
+}
+
+/// Defines tests of the symbol tables
+#[cfg(test)]
+mod tests
+{
+    use super::{Keyword, Operator};
+
+    /// Tests that each table entry converts to and from its source text
+    #[test]
+    fn round_trip()
+    {
+        for (op, text) in Operator::TABLE.iter()
+        {
+            assert_eq!(text.parse::<Operator>(), Ok(*op));
+            assert_eq!(op.to_string(), *text);
+        }
+
+        for (k, text) in Keyword::TABLE.iter()
+        {
+            assert_eq!(text.parse::<Keyword>(), Ok(*k));
+            assert_eq!(k.to_string(), *text);
+        }
+
+        assert!("=>".parse::<Operator>().is_err());
+        assert!("If".parse::<Keyword>().is_err());
+    }
+}
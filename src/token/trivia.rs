@@ -0,0 +1,49 @@
+use crate::span::Span;
+
+/// Defines the form of a comment in the source text
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CommentKind
+{
+    /// Defines a comment from // to the end of the line
+    Line,
+
+    /// Defines a comment between /* and */, which may contain nested block comments
+    Block,
+
+    /// Defines a classic comment line, starting with REM as the first word of the line
+    Rem
+}
+
+/// Defines a comment skipped by the tokenizer, kept to allow the source to be rewritten
+#[derive(Debug, PartialEq, Clone)]
+pub struct Comment
+{
+    /// Defines the form of the comment
+    pub kind: CommentKind,
+
+    /// Defines the full text of the comment, including the delimiters but not any line ending
+    pub text: String,
+
+    /// Defines the source location of the comment
+    pub span: Span
+}
+
+/// Defines the comments attached to a single token
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Trivia
+{
+    /// Defines the comments before the token, each starting on a later line than the token before
+    pub leading: Vec<Comment>,
+
+    /// Defines the comments after the token that start on the same line as the end of the token
+    pub trailing: Vec<Comment>
+}
+
+impl Trivia
+{
+    /// Returns true if no comments are attached
+    pub fn is_empty(&self) -> bool
+    {
+        return self.leading.is_empty() && self.trailing.is_empty();
+    }
+}
@@ -1,10 +1,45 @@
+use crate::token::Token;
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum VariableType
 {
     Boolean(bool),
     Integer(i32),
-    Float(f32)
+    Float(f32),
+    String(String)
+}
+
+impl VariableType
+{
+    /// Returns the name of the type, independent of the stored value
+    pub fn type_name(&self) -> &'static str
+    {
+        return match self
+        {
+            VariableType::Boolean(_) => "boolean",
+            VariableType::Integer(_) => "integer",
+            VariableType::Float(_) => "float",
+            VariableType::String(_) => "string"
+        };
+    }
+}
+
+impl std::fmt::Display for VariableType
+{
+    /// Writes the stored value as it would be written in source code
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return match self
+        {
+            VariableType::Boolean(v) => write!(f, "{0:}", v),
+            VariableType::Integer(v) => write!(f, "{0:}", v),
+            VariableType::Float(v) => write!(f, "{0:?}", v),
+            VariableType::String(v) => write!(f, "{0:}", Token::quote(v))
+        };
+    }
 }
 
+#[derive(Debug, PartialEq, Clone)]
 pub struct Variable
 {
     pub vartype: VariableType,